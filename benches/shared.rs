@@ -3,3 +3,22 @@
 pub fn secret_1kb() -> &'static [u8] {
     include_bytes!("resources/1KB.txt")
 }
+
+/// A pseudo-random secret of 1MB, large enough to be split across threads
+/// when the `parallel` feature is enabled.
+pub fn secret_1mb() -> &'static [u8] {
+    use std::sync::OnceLock;
+
+    static SECRET: OnceLock<Vec<u8>> = OnceLock::new();
+    SECRET.get_or_init(|| {
+        let mut state = 0x2545_f491_u32;
+        (0..1 << 20)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    })
+}
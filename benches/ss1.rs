@@ -2,7 +2,7 @@
 #![feature(test)]
 #![cfg(feature = "dss")]
 
-extern crate rusty_secrets;
+extern crate etospheres_labs_rusty_secrets as rusty_secrets;
 extern crate test;
 
 mod shared;
@@ -57,4 +57,7 @@ mod ss1 {
 
     bench_generate!(generate_1kb_10_25, 10, 25, secret_1kb);
     bench_recover!(recover_1kb_10_25, 10, 25, secret_1kb);
+
+    bench_generate!(generate_1mb_3_5, 3, 5, secret_1mb);
+    bench_recover!(recover_1mb_3_5, 3, 5, secret_1mb);
 }
@@ -1,7 +1,7 @@
 #![cfg(test)]
 #![feature(test)]
 
-extern crate rusty_secrets;
+extern crate etospheres_labs_rusty_secrets as rusty_secrets;
 extern crate test;
 
 mod shared;
@@ -53,4 +53,7 @@ mod sss {
 
     bench_generate!(generate_1kb_10_25_signed, 10, 25, secret_1kb, true);
     bench_recover!(recover_1kb_10_25_signed, 10, 25, secret_1kb, true);
+
+    bench_generate!(generate_1mb_3_5, 3, 5, secret_1mb, false);
+    bench_recover!(recover_1mb_3_5, 3, 5, secret_1mb, false);
 }
@@ -93,9 +93,15 @@ fn generate_gf256_table() {
 fn main() {
     generate_gf256_table();
 
+    // The protobuf messages are only used by the share formats, which need `std`.
+    if env::var_os("CARGO_FEATURE_STD").is_none() {
+        return;
+    }
+
     prost_build::compile_protos(
         &[
             "protobuf/version.proto",
+            "protobuf/scheme.proto",
             "protobuf/dss/metadata.proto",
             "protobuf/dss/secret.proto",
             "protobuf/dss/share.proto",
@@ -0,0 +1,370 @@
+#ifndef RUSTY_SECRETS_H
+#define RUSTY_SECRETS_H
+
+/* Generated with cbindgen from src/capi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ Error codes returned by the C API.
+
+ Codes below 100 map to the variants of `Error`,
+ codes from 100 upwards are specific to the C API.
+ */
+typedef enum RsError {
+  /*
+   The call succeeded.
+   */
+  RS_ERROR_OK = 0,
+  /*
+   See `Error::ThresholdTooBig`.
+   */
+  RS_ERROR_THRESHOLD_TOO_BIG = 1,
+  /*
+   See `Error::ThresholdTooSmall`.
+   */
+  RS_ERROR_THRESHOLD_TOO_SMALL = 2,
+  /*
+   See `Error::SecretTooBig`.
+   */
+  RS_ERROR_SECRET_TOO_BIG = 3,
+  /*
+   See `Error::InvalidShareCountMax`.
+   */
+  RS_ERROR_INVALID_SHARE_COUNT_MAX = 4,
+  /*
+   See `Error::InvalidShareCountMin`.
+   */
+  RS_ERROR_INVALID_SHARE_COUNT_MIN = 5,
+  /*
+   See `Error::EmptySecret`.
+   */
+  RS_ERROR_EMPTY_SECRET = 6,
+  /*
+   See `Error::EmptyShares`.
+   */
+  RS_ERROR_EMPTY_SHARES = 7,
+  /*
+   See `Error::IncompatibleSets`.
+   */
+  RS_ERROR_INCOMPATIBLE_SETS = 8,
+  /*
+   See `Error::MissingShares`.
+   */
+  RS_ERROR_MISSING_SHARES = 9,
+  /*
+   See `Error::InvalidSignature`.
+   */
+  RS_ERROR_INVALID_SIGNATURE = 10,
+  /*
+   See `Error::MissingSignature`.
+   */
+  RS_ERROR_MISSING_SIGNATURE = 11,
+  /*
+   See `Error::SecretDeserializationError`.
+   */
+  RS_ERROR_SECRET_DESERIALIZATION_ERROR = 12,
+  /*
+   See `Error::ShareParsingError`, `Error::ShareParsingErrorBase64` and
+   `Error::ShareParsingErrorProtobuf`.
+   */
+  RS_ERROR_SHARE_PARSING_ERROR = 13,
+  /*
+   See `Error::ShareParsingErrorEmptyShare`.
+   */
+  RS_ERROR_SHARE_PARSING_ERROR_EMPTY_SHARE = 14,
+  /*
+   See `Error::ShareParsingInvalidShareId`.
+   */
+  RS_ERROR_SHARE_PARSING_INVALID_SHARE_ID = 15,
+  /*
+   See `Error::ShareParsingInvalidShareThreshold`.
+   */
+  RS_ERROR_SHARE_PARSING_INVALID_SHARE_THRESHOLD = 16,
+  /*
+   See `Error::InvalidSS1Parameters`.
+   */
+  RS_ERROR_INVALID_SS1_PARAMETERS = 17,
+  /*
+   See `Error::InvalidSplitParametersZero`.
+   */
+  RS_ERROR_INVALID_SPLIT_PARAMETERS_ZERO = 18,
+  /*
+   See `Error::MismatchingShares`.
+   */
+  RS_ERROR_MISMATCHING_SHARES = 19,
+  /*
+   See `Error::CannotGenerateRandomNumbers`.
+   */
+  RS_ERROR_CANNOT_GENERATE_RANDOM_NUMBERS = 20,
+  /*
+   See `Error::DuplicateShareId`.
+   */
+  RS_ERROR_DUPLICATE_SHARE_ID = 21,
+  /*
+   See `Error::InconsistentSecretLengths`.
+   */
+  RS_ERROR_INCONSISTENT_SECRET_LENGTHS = 22,
+  /*
+   See `Error::InconsistentShares`.
+   */
+  RS_ERROR_INCONSISTENT_SHARES = 23,
+  /*
+   See `Error::InconsistentThresholds`.
+   */
+  RS_ERROR_INCONSISTENT_THRESHOLDS = 24,
+  /*
+   See `Error::Io`.
+   */
+  RS_ERROR_IO = 25,
+  /*
+   See `Error::IntegerParsingError`.
+   */
+  RS_ERROR_INTEGER_PARSING_ERROR = 26,
+  /*
+   See `Error::SignaturesUnsupported`.
+   */
+  RS_ERROR_SIGNATURES_UNSUPPORTED = 27,
+  /*
+   See `Error::UnsupportedOption`.
+   */
+  RS_ERROR_UNSUPPORTED_OPTION = 28,
+  /*
+   See `Error::InconsistentSchemes`.
+   */
+  RS_ERROR_INCONSISTENT_SCHEMES = 29,
+  /*
+   See `Error::UnknownMnemonicWord`.
+   */
+  RS_ERROR_UNKNOWN_MNEMONIC_WORD = 30,
+  /*
+   See `Error::InvalidMnemonicChecksum`.
+   */
+  RS_ERROR_INVALID_MNEMONIC_CHECKSUM = 31,
+  /*
+   See `Error::InvalidMasterSecretLength`.
+   */
+  RS_ERROR_INVALID_MASTER_SECRET_LENGTH = 32,
+  /*
+   See `Error::InvalidIterationExponent`.
+   */
+  RS_ERROR_INVALID_ITERATION_EXPONENT = 33,
+  /*
+   See `Error::InvalidPassphrase`.
+   */
+  RS_ERROR_INVALID_PASSPHRASE = 34,
+  /*
+   See `Error::InvalidShareField`.
+   */
+  RS_ERROR_INVALID_SHARE_FIELD = 35,
+  /*
+   See `Error::InvalidSecurityLevel`.
+   */
+  RS_ERROR_INVALID_SECURITY_LEVEL = 36,
+  /*
+   See `Error::UnsupportedShareVersion`.
+   */
+  RS_ERROR_UNSUPPORTED_SHARE_VERSION = 37,
+  /*
+   See `Error::InvalidArmorChecksum`.
+   */
+  RS_ERROR_INVALID_ARMOR_CHECKSUM = 38,
+  /*
+   See `Error::ShareTooBig`.
+   */
+  RS_ERROR_SHARE_TOO_BIG = 39,
+  /*
+   See `Error::InvalidCorrectionCount`.
+   */
+  RS_ERROR_INVALID_CORRECTION_COUNT = 40,
+  /*
+   See `Error::UncorrectableShare`.
+   */
+  RS_ERROR_UNCORRECTABLE_SHARE = 41,
+  /*
+   See `Error::InvalidMimeType`.
+   */
+  RS_ERROR_INVALID_MIME_TYPE = 42,
+  /*
+   See `Error::TooManyShares`.
+   */
+  RS_ERROR_TOO_MANY_SHARES = 43,
+  /*
+   See `Error::UnsupportedSecretVersion`.
+   */
+  RS_ERROR_UNSUPPORTED_SECRET_VERSION = 44,
+  /*
+   A required pointer was `NULL`.
+   */
+  RS_ERROR_NULL_POINTER = 100,
+  /*
+   A string argument was not valid UTF-8.
+   */
+  RS_ERROR_INVALID_UTF8 = 101,
+  /*
+   An output buffer was too small, see the module documentation.
+   */
+  RS_ERROR_BUFFER_TOO_SMALL = 102,
+  /*
+   The library panicked, which is a bug.
+   */
+  RS_ERROR_PANIC = 103,
+} RsError;
+
+/*
+ Returns a static, NUL-terminated description of the given error code, which
+ is "Unknown error" for codes which are not an `RsError`.
+ */
+const char *rs_error_message(int code);
+
+/*
+ Performs threshold k-out-of-n Shamir's secret sharing.
+
+ Writes the shares to `out`, one per line, as a NUL-terminated string.
+
+ # Safety
+
+ `secret` must point to `secret_len` readable bytes, `out` to `out_len` writable
+ bytes, and `written` to a writable `size_t`.
+ */
+enum RsError rs_sss_split_secret(uint8_t k,
+                                 uint8_t n,
+                                 const uint8_t *secret,
+                                 size_t secret_len,
+                                 bool sign_shares,
+                                 char *out,
+                                 size_t out_len,
+                                 size_t *written);
+
+/*
+ Recovers the secret from the given shares, one per line.
+
+ Writes the secret to `out`.
+
+ # Safety
+
+ `shares` must point to a NUL-terminated string, `out` to `out_len` writable
+ bytes, and `written` to a writable `size_t`.
+ */
+enum RsError rs_sss_recover_secret(const char *shares,
+                                   bool verify_signatures,
+                                   uint8_t *out,
+                                   size_t out_len,
+                                   size_t *written);
+
+/*
+ Performs threshold k-out-of-n Shamir's secret sharing of a secret wrapped along
+ with its MIME type, which can be `NULL`.
+
+ Writes the shares to `out`, one per line, as a NUL-terminated string.
+
+ # Safety
+
+ `secret` must point to `secret_len` readable bytes, `mime_type` to a NUL-terminated
+ string or be `NULL`, `out` to `out_len` writable bytes, and `written` to a writable `size_t`.
+ */
+enum RsError rs_wrapped_split_secret(uint8_t k,
+                                     uint8_t n,
+                                     const uint8_t *secret,
+                                     size_t secret_len,
+                                     const char *mime_type,
+                                     bool sign_shares,
+                                     char *out,
+                                     size_t out_len,
+                                     size_t *written);
+
+/*
+ Recovers a wrapped secret from the given shares, one per line.
+
+ Writes the secret to `out`, and its MIME type as it was recorded, even if it is
+ not valid, as a NUL-terminated string which is empty if the secret has none, to
+ `mime_type_out`.
+
+ # Safety
+
+ `shares` must point to a NUL-terminated string, `out` to `out_len` writable bytes,
+ `mime_type_out` to `mime_type_len` writable bytes, and `written` and
+ `mime_type_written` to writable `size_t`s.
+ */
+enum RsError rs_wrapped_recover_secret(const char *shares,
+                                       bool verify_signatures,
+                                       uint8_t *out,
+                                       size_t out_len,
+                                       size_t *written,
+                                       char *mime_type_out,
+                                       size_t mime_type_len,
+                                       size_t *mime_type_written);
+
+/*
+ Performs threshold k-out-of-n secret sharing using the `ThSS` scheme.
+
+ Writes the shares to `out`, one per line, as a NUL-terminated string.
+
+ # Safety
+
+ `secret` must point to `secret_len` readable bytes, `out` to `out_len` writable
+ bytes, and `written` to a writable `size_t`.
+ */
+enum RsError rs_thss_split_secret(uint8_t k,
+                                  uint8_t n,
+                                  const uint8_t *secret,
+                                  size_t secret_len,
+                                  char *out,
+                                  size_t out_len,
+                                  size_t *written);
+
+/*
+ Recovers the secret from the given `ThSS` shares, one per line.
+
+ Writes the secret to `out`.
+
+ # Safety
+
+ `shares` must point to a NUL-terminated string, `out` to `out_len` writable
+ bytes, and `written` to a writable `size_t`.
+ */
+enum RsError rs_thss_recover_secret(const char *shares,
+                                    uint8_t *out,
+                                    size_t out_len,
+                                    size_t *written);
+
+/*
+ Performs threshold k-out-of-n deterministic secret sharing using the `SS1` scheme.
+
+ When `reproducible` is `true`, splitting the same secret twice yields the same shares.
+ Writes the shares to `out`, one per line, as a NUL-terminated string.
+
+ # Safety
+
+ `secret` must point to `secret_len` readable bytes, `out` to `out_len` writable
+ bytes, and `written` to a writable `size_t`.
+ */
+enum RsError rs_ss1_split_secret(uint8_t k,
+                                 uint8_t n,
+                                 const uint8_t *secret,
+                                 size_t secret_len,
+                                 bool reproducible,
+                                 char *out,
+                                 size_t out_len,
+                                 size_t *written);
+
+/*
+ Recovers the secret from the given `SS1` shares, one per line.
+
+ Writes the secret to `out`.
+
+ # Safety
+
+ `shares` must point to a NUL-terminated string, `out` to `out_len` writable
+ bytes, and `written` to a writable `size_t`.
+ */
+enum RsError rs_ss1_recover_secret(const char *shares,
+                                   uint8_t *out,
+                                   size_t out_len,
+                                   size_t *written);
+
+#endif  /* RUSTY_SECRETS_H */
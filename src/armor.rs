@@ -0,0 +1,285 @@
+//! Encodes shares in an ASCII-armored format, in the style of OpenPGP, whose
+//! checksum catches the typos the `K-N-D` format lets through.
+//!
+//! ```text
+//! -----BEGIN RUSTY SECRETS SHARE-----
+//! Threshold: 2
+//! Share-Id: 1
+//! Deal-Id: 5F0E3C1A9B27D4E8
+//! Scheme: Sss
+//!
+//! UlNTSAABAgEK...
+//! =kQ3x
+//! -----END RUSTY SECRETS SHARE-----
+//! ```
+//!
+//! The body is the base64 encoding of the binary encoding of the share, see
+//! `share::envelope`, wrapped at 64 columns. The line following it starts with `=`
+//! and holds the base64 encoding of the CRC-24 checksum of those bytes, as defined in
+//! RFC 4880.
+//!
+//! The `Threshold`, `Share-Id` and `Scheme` headers repeat what the body holds, and
+//! must agree with it. The `Deal-Id` header, common to the shares of a deal, only
+//! helps tell shares apart. Parsing ignores any text around the armor,
+//! the case of the header names, unknown headers, and whitespace in the body.
+
+use base64::Engine;
+
+use crate::errors::*;
+use crate::proto::SchemeProto;
+use crate::share::envelope::Envelope;
+
+const BEGIN_LINE: &str = "-----BEGIN RUSTY SECRETS SHARE-----";
+const END_LINE: &str = "-----END RUSTY SECRETS SHARE-----";
+
+const THRESHOLD_HEADER: &str = "Threshold";
+const SHARE_ID_HEADER: &str = "Share-Id";
+const DEAL_ID_HEADER: &str = "Deal-Id";
+const SCHEME_HEADER: &str = "Scheme";
+
+const LINE_WIDTH: usize = 64;
+
+const BASE64_CONFIG: base64::engine::general_purpose::GeneralPurpose =
+    base64::engine::general_purpose::STANDARD;
+
+const CRC24_INIT: u32 = 0x00B7_04CE;
+const CRC24_POLY: u32 = 0x0186_4CFB;
+
+/// Returns whether the given share is armored, rather than encoded as words or as
+/// `K-N-D`.
+pub(crate) fn is_armored(raw: &str) -> bool {
+    raw.lines().any(|line| line.trim() == BEGIN_LINE)
+}
+
+/// Armors the given share, along with the identifier of the deal if any, which must
+/// fit on a single line.
+pub(crate) fn encode(envelope: &Envelope, deal_id: Option<&str>) -> String {
+    let payload = envelope.to_bytes();
+
+    let mut armor = format!(
+        "{}\n{}: {}\n{}: {}\n",
+        BEGIN_LINE, THRESHOLD_HEADER, envelope.threshold, SHARE_ID_HEADER, envelope.id
+    );
+    if let Some(deal_id) = deal_id {
+        armor.push_str(&format!("{}: {}\n", DEAL_ID_HEADER, deal_id.trim()));
+    }
+    if let Some(scheme) = scheme_name(envelope.scheme) {
+        armor.push_str(&format!("{}: {}\n", SCHEME_HEADER, scheme));
+    }
+    armor.push('\n');
+
+    let body = BASE64_CONFIG.encode(&payload);
+    // Base64 is ASCII, so the body can be split at any byte.
+    for line in body.as_bytes().chunks(LINE_WIDTH) {
+        armor.push_str(std::str::from_utf8(line).unwrap());
+        armor.push('\n');
+    }
+    let checksum = crc24(&payload).to_be_bytes();
+    armor.push('=');
+    armor.push_str(&BASE64_CONFIG.encode(&checksum[1..]));
+    armor.push('\n');
+    armor.push_str(END_LINE);
+    armor
+}
+
+/// Decodes an armored share produced by `encode`.
+pub(crate) fn decode(raw: &str) -> Result<Envelope> {
+    let mut lines = raw
+        .lines()
+        .map(str::trim)
+        .skip_while(|&line| line != BEGIN_LINE)
+        .skip(1);
+
+    let mut headers = Vec::new();
+    let mut body_lines = Vec::new();
+    let mut ended = false;
+    for line in &mut lines {
+        if line == END_LINE {
+            ended = true;
+            break;
+        }
+        match line.split_once(':') {
+            Some((name, value)) if body_lines.is_empty() => {
+                headers.push((name.trim(), value.trim()))
+            }
+            _ if line.is_empty() => {}
+            _ => body_lines.push(line),
+        }
+    }
+    if !ended {
+        bail!(Error::ShareParsingError(format!(
+            "The armored share does not end with `{}`.",
+            END_LINE
+        )));
+    }
+
+    let checksum_line = match body_lines.pop() {
+        Some(line) if line.starts_with('=') => line,
+        _ => bail!(Error::ShareParsingError(
+            "The armored share has no checksum line, starting with `=`.".to_owned()
+        )),
+    };
+    let body = body_lines.concat().split_whitespace().collect::<String>();
+    let payload = BASE64_CONFIG
+        .decode(body)
+        .map_err(Error::ShareParsingErrorBase64)?;
+    let checksum = BASE64_CONFIG
+        .decode(checksum_line[1..].split_whitespace().collect::<String>())
+        .map_err(Error::ShareParsingErrorBase64)?;
+    if checksum.len() != 3 {
+        bail!(Error::ShareParsingError(format!(
+            "The checksum of the armored share must be 3 bytes long, not {}.",
+            checksum.len()
+        )));
+    }
+    let expected = u32::from_be_bytes([0, checksum[0], checksum[1], checksum[2]]);
+    let computed = crc24(&payload);
+    if computed != expected {
+        bail!(Error::InvalidArmorChecksum(computed, expected));
+    }
+
+    let envelope = Envelope::from_bytes(&payload)?;
+
+    for (name, value) in headers {
+        let matches = if name.eq_ignore_ascii_case(THRESHOLD_HEADER) {
+            value.parse() == Ok(envelope.threshold)
+        } else if name.eq_ignore_ascii_case(SHARE_ID_HEADER) {
+            value.parse() == Ok(envelope.id)
+        } else if name.eq_ignore_ascii_case(SCHEME_HEADER) {
+            scheme_name(envelope.scheme).is_none_or(|scheme| value.eq_ignore_ascii_case(scheme))
+        } else {
+            true
+        };
+        if !matches {
+            bail!(Error::ShareParsingError(format!(
+                "The `{}` header of the armored share does not match its body: {}.",
+                name, value
+            )));
+        }
+    }
+
+    Ok(envelope)
+}
+
+/// The name of the given scheme, if any.
+fn scheme_name(scheme: SchemeProto) -> Option<&'static str> {
+    match scheme {
+        SchemeProto::UnspecifiedScheme => None,
+        SchemeProto::Sss => Some("Sss"),
+        SchemeProto::WrappedSecrets => Some("WrappedSecrets"),
+        SchemeProto::Thss => Some("ThSS"),
+        SchemeProto::Ss1 => Some("SS1"),
+    }
+}
+
+/// The CRC-24 checksum of RFC 4880, section 6.1.
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc = CRC24_INIT;
+    for &byte in data {
+        crc ^= u32::from(byte) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0x00FF_FFFF
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn envelope(threshold: u8, id: u8, body: &[u8]) -> Envelope {
+        Envelope {
+            scheme: SchemeProto::Thss,
+            threshold,
+            id,
+            body: body.to_vec(),
+        }
+    }
+
+    #[test]
+    fn crc24_check_value() {
+        assert_eq!(crc24(b"123456789"), 0x0021_CF02);
+        assert_eq!(crc24(b""), CRC24_INIT);
+    }
+
+    #[test]
+    fn encode_then_decode() {
+        for len in [0, 1, 46, 47, 48, 200] {
+            let data = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+            let envelope = envelope(3, 7, &data);
+            let armor = encode(&envelope, Some("DEAL"));
+            assert!(is_armored(&armor));
+            assert!(armor.lines().all(|line| line.len() <= LINE_WIDTH));
+            assert!(armor.contains("\nDeal-Id: DEAL\nScheme: ThSS\n"));
+            assert_eq!(decode(&armor).unwrap(), envelope);
+        }
+    }
+
+    #[test]
+    fn whitespace_and_surrounding_text_are_ignored() {
+        let envelope = envelope(2, 1, &[42; 100]);
+        let armor = encode(&envelope, None);
+        let mangled = format!(
+            "Here is my share:\r\n\r\n{}\r\n\nThanks.",
+            armor
+                .lines()
+                .map(|line| format!("  {} ", line.replace("Threshold", "THRESHOLD")))
+                .collect::<Vec<_>>()
+                .join("\r\n\r\n")
+        );
+        assert_eq!(decode(&mangled).unwrap(), envelope);
+
+        let body = body_lines(&armor)[0];
+        let spaced = body.chars().map(|c| format!("{} ", c)).collect::<String>();
+        let armor = armor.replacen(body, &spaced, 1);
+        assert_eq!(decode(&armor).unwrap(), envelope);
+    }
+
+    #[test]
+    fn typos_are_reported() {
+        let armor = encode(&envelope(2, 1, b"some share data"), None);
+        let body = body_lines(&armor)[0];
+        let mut typo = body.to_owned().into_bytes();
+        typo[5] = if typo[5] == b'A' { b'B' } else { b'A' };
+        let typo = String::from_utf8(typo).unwrap();
+        let err = decode(&armor.replacen(body, &typo, 1)).unwrap_err();
+        let expected = crc24(&BASE64_CONFIG.decode(body).unwrap());
+        match err {
+            Error::InvalidArmorChecksum(computed, found) => {
+                assert_eq!(found, expected);
+                assert_ne!(computed, expected);
+            }
+            other => panic!("Unexpected error: {:?}", other),
+        }
+
+        let err = decode(&armor.replace("Share-Id: 1", "Share-Id: 2")).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingError(_)));
+        let err = decode(&armor.replace("Scheme: ThSS", "Scheme: SS1")).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingError(_)));
+        let err = decode(&armor.replace(END_LINE, "")).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingError(_)));
+        let without_checksum = armor
+            .lines()
+            .filter(|line| !line.starts_with('='))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let err = decode(&without_checksum).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingError(_)));
+    }
+
+    /// The lines of the body, between the headers and the checksum.
+    fn body_lines(armor: &str) -> Vec<&str> {
+        armor
+            .split("\n\n")
+            .nth(1)
+            .unwrap()
+            .lines()
+            .take_while(|line| !line.starts_with('='))
+            .collect()
+    }
+}
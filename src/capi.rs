@@ -0,0 +1,665 @@
+//! C bindings, enabled by the `capi` feature.
+//!
+//! Every function returns an `RsError` code, `RS_ERROR_OK` on success. Outputs are
+//! written to buffers provided by the caller, along with the number of bytes
+//! written. If a buffer is too small, `RS_ERROR_BUFFER_TOO_SMALL` is returned, and
+//! the size which would have been needed is still reported, so that the call can be
+//! retried with a big enough buffer. Passing a `NULL` buffer of length 0 is a way to
+//! query that size.
+//!
+//! Shares are exchanged as NUL-terminated strings holding one share per line.
+//! Secrets are exchanged as raw bytes.
+//!
+//! The matching header is `include/rusty_secrets.h`, generated with `cbindgen`.
+//! A shared library can be built with `cargo rustc --release --lib --features capi --crate-type cdylib`,
+//! and a static library with `--crate-type staticlib`.
+
+#![allow(unsafe_code)]
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, UnwindSafe};
+use std::{ptr, slice};
+
+use crate::dss::{ss1, thss};
+use crate::errors::Error;
+use crate::{sss, wrapped_secrets};
+
+/// Error codes returned by the C API.
+///
+/// Codes below 100 map to the variants of `Error`,
+/// codes from 100 upwards are specific to the C API.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RsError {
+    /// The call succeeded.
+    Ok = 0,
+    /// See `Error::ThresholdTooBig`.
+    ThresholdTooBig = 1,
+    /// See `Error::ThresholdTooSmall`.
+    ThresholdTooSmall = 2,
+    /// See `Error::SecretTooBig`.
+    SecretTooBig = 3,
+    /// See `Error::InvalidShareCountMax`.
+    InvalidShareCountMax = 4,
+    /// See `Error::InvalidShareCountMin`.
+    InvalidShareCountMin = 5,
+    /// See `Error::EmptySecret`.
+    EmptySecret = 6,
+    /// See `Error::EmptyShares`.
+    EmptyShares = 7,
+    /// See `Error::IncompatibleSets`.
+    IncompatibleSets = 8,
+    /// See `Error::MissingShares`.
+    MissingShares = 9,
+    /// See `Error::InvalidSignature`.
+    InvalidSignature = 10,
+    /// See `Error::MissingSignature`.
+    MissingSignature = 11,
+    /// See `Error::SecretDeserializationError`.
+    SecretDeserializationError = 12,
+    /// See `Error::ShareParsingError`, `Error::ShareParsingErrorBase64` and
+    /// `Error::ShareParsingErrorProtobuf`.
+    ShareParsingError = 13,
+    /// See `Error::ShareParsingErrorEmptyShare`.
+    ShareParsingErrorEmptyShare = 14,
+    /// See `Error::ShareParsingInvalidShareId`.
+    ShareParsingInvalidShareId = 15,
+    /// See `Error::ShareParsingInvalidShareThreshold`.
+    ShareParsingInvalidShareThreshold = 16,
+    /// See `Error::InvalidSS1Parameters`.
+    InvalidSs1Parameters = 17,
+    /// See `Error::InvalidSplitParametersZero`.
+    InvalidSplitParametersZero = 18,
+    /// See `Error::MismatchingShares`.
+    MismatchingShares = 19,
+    /// See `Error::CannotGenerateRandomNumbers`.
+    CannotGenerateRandomNumbers = 20,
+    /// See `Error::DuplicateShareId`.
+    DuplicateShareId = 21,
+    /// See `Error::InconsistentSecretLengths`.
+    InconsistentSecretLengths = 22,
+    /// See `Error::InconsistentShares`.
+    InconsistentShares = 23,
+    /// See `Error::InconsistentThresholds`.
+    InconsistentThresholds = 24,
+    /// See `Error::Io`.
+    Io = 25,
+    /// See `Error::IntegerParsingError`.
+    IntegerParsingError = 26,
+    /// See `Error::SignaturesUnsupported`.
+    SignaturesUnsupported = 27,
+    /// See `Error::UnsupportedOption`.
+    UnsupportedOption = 28,
+    /// See `Error::InconsistentSchemes`.
+    InconsistentSchemes = 29,
+    /// See `Error::UnknownMnemonicWord`.
+    UnknownMnemonicWord = 30,
+    /// See `Error::InvalidMnemonicChecksum`.
+    InvalidMnemonicChecksum = 31,
+    /// See `Error::InvalidMasterSecretLength`.
+    InvalidMasterSecretLength = 32,
+    /// See `Error::InvalidIterationExponent`.
+    InvalidIterationExponent = 33,
+    /// See `Error::InvalidPassphrase`.
+    InvalidPassphrase = 34,
+    /// See `Error::InvalidShareField`.
+    InvalidShareField = 35,
+    /// See `Error::InvalidSecurityLevel`.
+    InvalidSecurityLevel = 36,
+    /// See `Error::UnsupportedShareVersion`.
+    UnsupportedShareVersion = 37,
+    /// See `Error::InvalidArmorChecksum`.
+    InvalidArmorChecksum = 38,
+    /// See `Error::ShareTooBig`.
+    ShareTooBig = 39,
+    /// See `Error::InvalidCorrectionCount`.
+    InvalidCorrectionCount = 40,
+    /// See `Error::UncorrectableShare`.
+    UncorrectableShare = 41,
+    /// See `Error::InvalidMimeType`.
+    InvalidMimeType = 42,
+    /// See `Error::TooManyShares`.
+    TooManyShares = 43,
+    /// See `Error::UnsupportedSecretVersion`.
+    UnsupportedSecretVersion = 44,
+    /// A required pointer was `NULL`.
+    NullPointer = 100,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 101,
+    /// An output buffer was too small, see the module documentation.
+    BufferTooSmall = 102,
+    /// The library panicked, which is a bug.
+    Panic = 103,
+}
+
+impl RsError {
+    /// Returns the error code of the given value, if any. Codes coming from C may
+    /// hold any value, which a Rust enum must not.
+    fn from_code(code: c_int) -> Option<Self> {
+        let error = match code {
+            0 => RsError::Ok,
+            1 => RsError::ThresholdTooBig,
+            2 => RsError::ThresholdTooSmall,
+            3 => RsError::SecretTooBig,
+            4 => RsError::InvalidShareCountMax,
+            5 => RsError::InvalidShareCountMin,
+            6 => RsError::EmptySecret,
+            7 => RsError::EmptyShares,
+            8 => RsError::IncompatibleSets,
+            9 => RsError::MissingShares,
+            10 => RsError::InvalidSignature,
+            11 => RsError::MissingSignature,
+            12 => RsError::SecretDeserializationError,
+            13 => RsError::ShareParsingError,
+            14 => RsError::ShareParsingErrorEmptyShare,
+            15 => RsError::ShareParsingInvalidShareId,
+            16 => RsError::ShareParsingInvalidShareThreshold,
+            17 => RsError::InvalidSs1Parameters,
+            18 => RsError::InvalidSplitParametersZero,
+            19 => RsError::MismatchingShares,
+            20 => RsError::CannotGenerateRandomNumbers,
+            21 => RsError::DuplicateShareId,
+            22 => RsError::InconsistentSecretLengths,
+            23 => RsError::InconsistentShares,
+            24 => RsError::InconsistentThresholds,
+            25 => RsError::Io,
+            26 => RsError::IntegerParsingError,
+            27 => RsError::SignaturesUnsupported,
+            28 => RsError::UnsupportedOption,
+            29 => RsError::InconsistentSchemes,
+            30 => RsError::UnknownMnemonicWord,
+            31 => RsError::InvalidMnemonicChecksum,
+            32 => RsError::InvalidMasterSecretLength,
+            33 => RsError::InvalidIterationExponent,
+            34 => RsError::InvalidPassphrase,
+            35 => RsError::InvalidShareField,
+            36 => RsError::InvalidSecurityLevel,
+            37 => RsError::UnsupportedShareVersion,
+            38 => RsError::InvalidArmorChecksum,
+            39 => RsError::ShareTooBig,
+            40 => RsError::InvalidCorrectionCount,
+            41 => RsError::UncorrectableShare,
+            42 => RsError::InvalidMimeType,
+            43 => RsError::TooManyShares,
+            44 => RsError::UnsupportedSecretVersion,
+            100 => RsError::NullPointer,
+            101 => RsError::InvalidUtf8,
+            102 => RsError::BufferTooSmall,
+            103 => RsError::Panic,
+            _ => return None,
+        };
+        Some(error)
+    }
+}
+
+impl<'a> From<&'a Error> for RsError {
+    fn from(err: &'a Error) -> Self {
+        match *err {
+            Error::ThresholdTooBig(..) => RsError::ThresholdTooBig,
+            Error::ThresholdTooSmall(..) => RsError::ThresholdTooSmall,
+            Error::SecretTooBig(..) => RsError::SecretTooBig,
+            Error::InvalidShareCountMax(..) => RsError::InvalidShareCountMax,
+            Error::InvalidShareCountMin(..) => RsError::InvalidShareCountMin,
+            Error::EmptySecret => RsError::EmptySecret,
+            Error::EmptyShares => RsError::EmptyShares,
+            Error::IncompatibleSets(..) => RsError::IncompatibleSets,
+            Error::MissingShares(..) => RsError::MissingShares,
+            Error::InvalidSignature(..) => RsError::InvalidSignature,
+            Error::MissingSignature(..) => RsError::MissingSignature,
+            Error::SecretDeserializationError(..) => RsError::SecretDeserializationError,
+            Error::ShareParsingError(..)
+            | Error::ShareParsingErrorBase64(..)
+            | Error::ShareParsingErrorProtobuf(..) => RsError::ShareParsingError,
+            Error::ShareParsingErrorEmptyShare(..) => RsError::ShareParsingErrorEmptyShare,
+            Error::ShareParsingInvalidShareId(..) => RsError::ShareParsingInvalidShareId,
+            Error::ShareParsingInvalidShareThreshold(..) => {
+                RsError::ShareParsingInvalidShareThreshold
+            }
+            Error::InvalidSS1Parameters(..) => RsError::InvalidSs1Parameters,
+            Error::InvalidSplitParametersZero(..) => RsError::InvalidSplitParametersZero,
+            Error::MismatchingShares(..) => RsError::MismatchingShares,
+            Error::CannotGenerateRandomNumbers => RsError::CannotGenerateRandomNumbers,
+            Error::DuplicateShareId(..) => RsError::DuplicateShareId,
+            Error::InconsistentSecretLengths(..) => RsError::InconsistentSecretLengths,
+            Error::InconsistentShares => RsError::InconsistentShares,
+            Error::InconsistentThresholds(..) => RsError::InconsistentThresholds,
+            Error::Io(..) => RsError::Io,
+            Error::IntegerParsingError(..) => RsError::IntegerParsingError,
+            Error::SignaturesUnsupported => RsError::SignaturesUnsupported,
+            Error::UnsupportedOption(..) => RsError::UnsupportedOption,
+            Error::InconsistentSchemes => RsError::InconsistentSchemes,
+            Error::UnknownMnemonicWord(..) => RsError::UnknownMnemonicWord,
+            Error::InvalidMnemonicChecksum => RsError::InvalidMnemonicChecksum,
+            Error::InvalidMasterSecretLength(..) => RsError::InvalidMasterSecretLength,
+            Error::InvalidIterationExponent(..) => RsError::InvalidIterationExponent,
+            Error::InvalidPassphrase => RsError::InvalidPassphrase,
+            Error::InvalidShareField(..) => RsError::InvalidShareField,
+            Error::InvalidSecurityLevel(..) => RsError::InvalidSecurityLevel,
+            Error::UnsupportedShareVersion(..) => RsError::UnsupportedShareVersion,
+            Error::InvalidArmorChecksum(..) => RsError::InvalidArmorChecksum,
+            Error::ShareTooBig(..) => RsError::ShareTooBig,
+            Error::InvalidCorrectionCount(..) => RsError::InvalidCorrectionCount,
+            Error::UncorrectableShare => RsError::UncorrectableShare,
+            Error::InvalidMimeType(..) => RsError::InvalidMimeType,
+            Error::TooManyShares(..) => RsError::TooManyShares,
+            Error::UnsupportedSecretVersion(..) => RsError::UnsupportedSecretVersion,
+        }
+    }
+}
+
+impl From<Error> for RsError {
+    fn from(err: Error) -> Self {
+        (&err).into()
+    }
+}
+
+/// Returns a static, NUL-terminated description of the given error code, which
+/// is "Unknown error" for codes which are not an `RsError`.
+#[no_mangle]
+pub extern "C" fn rs_error_message(code: c_int) -> *const c_char {
+    let message: &'static [u8] = match RsError::from_code(code) {
+        None => b"Unknown error\0",
+        Some(RsError::Ok) => b"Success\0",
+        Some(RsError::ThresholdTooBig) => b"Threshold k must be smaller than or equal to n\0",
+        Some(RsError::ThresholdTooSmall) => b"Threshold k must be bigger than or equal to 2\0",
+        Some(RsError::SecretTooBig) => b"The secret is too long\0",
+        Some(RsError::InvalidShareCountMax) => b"Number of shares is too big\0",
+        Some(RsError::InvalidShareCountMin) => b"Number of shares is too small\0",
+        Some(RsError::EmptySecret) => b"The secret cannot be empty\0",
+        Some(RsError::EmptyShares) => b"No shares provided\0",
+        Some(RsError::IncompatibleSets) => b"The shares are incompatible with each other\0",
+        Some(RsError::MissingShares) => {
+            b"The number of shares provided is insufficient to recover the secret\0"
+        }
+        Some(RsError::InvalidSignature) => b"The signature of this share is not valid\0",
+        Some(RsError::MissingSignature) => {
+            b"Signature is missing while shares are required to be signed\0"
+        }
+        Some(RsError::SecretDeserializationError) => {
+            b"An issue was encountered deserializing the secret\0"
+        }
+        Some(RsError::ShareParsingError) => b"This share is incorrectly formatted\0",
+        Some(RsError::ShareParsingErrorEmptyShare) => b"This share is empty\0",
+        Some(RsError::ShareParsingInvalidShareId) => b"Invalid share identifier\0",
+        Some(RsError::ShareParsingInvalidShareThreshold) => {
+            b"Threshold k must be bigger than or equal to 2\0"
+        }
+        Some(RsError::InvalidSs1Parameters) => b"Invalid parameters for the SS1 sharing scheme\0",
+        Some(RsError::InvalidSplitParametersZero) => {
+            b"Parameters k and n must be greater than zero\0"
+        }
+        Some(RsError::MismatchingShares) => {
+            b"Share mismatch during verification of secret recovery\0"
+        }
+        Some(RsError::CannotGenerateRandomNumbers) => b"Cannot generate random numbers\0",
+        Some(RsError::DuplicateShareId) => {
+            b"This share number has already been used by a previous share\0"
+        }
+        Some(RsError::InconsistentSecretLengths) => {
+            b"The shares do not all have the same secret length\0"
+        }
+        Some(RsError::InconsistentShares) => b"The shares are inconsistent\0",
+        Some(RsError::InconsistentThresholds) => b"The shares do not all have the same threshold\0",
+        Some(RsError::Io) => b"I/O error\0",
+        Some(RsError::IntegerParsingError) => b"Cannot parse integer\0",
+        Some(RsError::SignaturesUnsupported) => b"Signing shares requires the std feature\0",
+        Some(RsError::UnsupportedOption) => b"The option is not supported by this scheme\0",
+        Some(RsError::InconsistentSchemes) => b"The shares were not all dealt by the same scheme\0",
+        Some(RsError::UnknownMnemonicWord) => b"A word of the share is not in the word list\0",
+        Some(RsError::InvalidMnemonicChecksum) => b"The checksum of the share does not match\0",
+        Some(RsError::InvalidMasterSecretLength) => {
+            b"The master secret must be at least 16 bytes long, and of even length\0"
+        }
+        Some(RsError::InvalidIterationExponent) => b"The iteration exponent must be at most 15\0",
+        Some(RsError::InvalidPassphrase) => {
+            b"The passphrase must only contain printable ASCII characters\0"
+        }
+        Some(RsError::InvalidShareField) => b"A field of the share is out of range\0",
+        Some(RsError::InvalidSecurityLevel) => {
+            b"The security level must be a multiple of 8 between 8 and 1024 bits\0"
+        }
+        Some(RsError::UnsupportedShareVersion) => {
+            b"The share was written by a newer version of the library\0"
+        }
+        Some(RsError::InvalidArmorChecksum) => {
+            b"The checksum of the armored share does not match its body\0"
+        }
+        Some(RsError::ShareTooBig) => b"The share is too long to be encoded this way\0",
+        Some(RsError::InvalidCorrectionCount) => {
+            b"The number of characters a share with error correction corrects is out of range\0"
+        }
+        Some(RsError::UncorrectableShare) => b"The share has too many corrupted characters\0",
+        Some(RsError::InvalidMimeType) => b"Invalid MIME type\0",
+        Some(RsError::TooManyShares) => b"More shares than needed were provided\0",
+        Some(RsError::UnsupportedSecretVersion) => {
+            b"The secret was wrapped in an unsupported version of the format\0"
+        }
+        Some(RsError::NullPointer) => b"A required pointer was NULL\0",
+        Some(RsError::InvalidUtf8) => b"A string argument was not valid UTF-8\0",
+        Some(RsError::BufferTooSmall) => b"An output buffer was too small\0",
+        Some(RsError::Panic) => b"The library panicked\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+/// Performs threshold k-out-of-n Shamir's secret sharing.
+///
+/// Writes the shares to `out`, one per line, as a NUL-terminated string.
+///
+/// # Safety
+///
+/// `secret` must point to `secret_len` readable bytes, `out` to `out_len` writable
+/// bytes, and `written` to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_sss_split_secret(
+    k: u8,
+    n: u8,
+    secret: *const u8,
+    secret_len: usize,
+    sign_shares: bool,
+    out: *mut c_char,
+    out_len: usize,
+    written: *mut usize,
+) -> RsError {
+    ffi(|| {
+        let secret = bytes_arg(secret, secret_len)?;
+        let shares = sss::split_secret(k, n, secret, sign_shares)?;
+        write_shares(&shares, out, out_len, written)
+    })
+}
+
+/// Recovers the secret from the given shares, one per line.
+///
+/// Writes the secret to `out`.
+///
+/// # Safety
+///
+/// `shares` must point to a NUL-terminated string, `out` to `out_len` writable
+/// bytes, and `written` to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_sss_recover_secret(
+    shares: *const c_char,
+    verify_signatures: bool,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> RsError {
+    ffi(|| {
+        let shares = shares_arg(shares)?;
+        let secret = sss::recover_secret(&shares, verify_signatures)?;
+        write_bytes(&secret, out, out_len, written)
+    })
+}
+
+/// Performs threshold k-out-of-n Shamir's secret sharing of a secret wrapped along
+/// with its MIME type, which can be `NULL`.
+///
+/// Writes the shares to `out`, one per line, as a NUL-terminated string.
+///
+/// # Safety
+///
+/// `secret` must point to `secret_len` readable bytes, `mime_type` to a NUL-terminated
+/// string or be `NULL`, `out` to `out_len` writable bytes, and `written` to a writable `size_t`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn rs_wrapped_split_secret(
+    k: u8,
+    n: u8,
+    secret: *const u8,
+    secret_len: usize,
+    mime_type: *const c_char,
+    sign_shares: bool,
+    out: *mut c_char,
+    out_len: usize,
+    written: *mut usize,
+) -> RsError {
+    ffi(|| {
+        let secret = bytes_arg(secret, secret_len)?;
+        let mime_type = if mime_type.is_null() {
+            None
+        } else {
+            Some(str_arg(mime_type)?.to_string())
+        };
+        let shares = wrapped_secrets::split_secret(k, n, secret, mime_type, sign_shares)?;
+        write_shares(&shares, out, out_len, written)
+    })
+}
+
+/// Recovers a wrapped secret from the given shares, one per line.
+///
+/// Writes the secret to `out`, and its MIME type as it was recorded, even if it is
+/// not valid, as a NUL-terminated string which is empty if the secret has none, to
+/// `mime_type_out`.
+///
+/// # Safety
+///
+/// `shares` must point to a NUL-terminated string, `out` to `out_len` writable bytes,
+/// `mime_type_out` to `mime_type_len` writable bytes, and `written` and
+/// `mime_type_written` to writable `size_t`s.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn rs_wrapped_recover_secret(
+    shares: *const c_char,
+    verify_signatures: bool,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+    mime_type_out: *mut c_char,
+    mime_type_len: usize,
+    mime_type_written: *mut usize,
+) -> RsError {
+    ffi(|| {
+        let shares = shares_arg(shares)?;
+        let secret = wrapped_secrets::recover_secret(&shares, verify_signatures)?;
+        let secret_result = write_bytes(&secret.data, out, out_len, written);
+        let mime_type_result = write_str(
+            secret.raw_mime_type.as_deref().unwrap_or(""),
+            mime_type_out,
+            mime_type_len,
+            mime_type_written,
+        );
+        secret_result.and(mime_type_result)
+    })
+}
+
+/// Performs threshold k-out-of-n secret sharing using the `ThSS` scheme.
+///
+/// Writes the shares to `out`, one per line, as a NUL-terminated string.
+///
+/// # Safety
+///
+/// `secret` must point to `secret_len` readable bytes, `out` to `out_len` writable
+/// bytes, and `written` to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_thss_split_secret(
+    k: u8,
+    n: u8,
+    secret: *const u8,
+    secret_len: usize,
+    out: *mut c_char,
+    out_len: usize,
+    written: *mut usize,
+) -> RsError {
+    ffi(|| {
+        let secret = bytes_arg(secret, secret_len)?;
+        let shares = thss::split_secret(k, n, secret, &None)?
+            .into_iter()
+            .map(thss::Share::into_string)
+            .collect::<Vec<_>>();
+        write_shares(&shares, out, out_len, written)
+    })
+}
+
+/// Recovers the secret from the given `ThSS` shares, one per line.
+///
+/// Writes the secret to `out`.
+///
+/// # Safety
+///
+/// `shares` must point to a NUL-terminated string, `out` to `out_len` writable
+/// bytes, and `written` to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_thss_recover_secret(
+    shares: *const c_char,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> RsError {
+    ffi(|| {
+        let shares = shares_arg(shares)?
+            .iter()
+            .map(|raw| thss::Share::from_string(raw))
+            .collect::<crate::errors::Result<Vec<_>>>()?;
+        let (secret, _, _) = thss::recover_secret(&shares)?;
+        write_bytes(&secret, out, out_len, written)
+    })
+}
+
+/// Performs threshold k-out-of-n deterministic secret sharing using the `SS1` scheme.
+///
+/// When `reproducible` is `true`, splitting the same secret twice yields the same shares.
+/// Writes the shares to `out`, one per line, as a NUL-terminated string.
+///
+/// # Safety
+///
+/// `secret` must point to `secret_len` readable bytes, `out` to `out_len` writable
+/// bytes, and `written` to a writable `size_t`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn rs_ss1_split_secret(
+    k: u8,
+    n: u8,
+    secret: *const u8,
+    secret_len: usize,
+    reproducible: bool,
+    out: *mut c_char,
+    out_len: usize,
+    written: *mut usize,
+) -> RsError {
+    ffi(|| {
+        let secret = bytes_arg(secret, secret_len)?;
+        let reproducibility = if reproducible {
+            ss1::Reproducibility::reproducible()
+        } else {
+            ss1::Reproducibility::none()
+        };
+        let shares = ss1::split_secret(k, n, secret, reproducibility, &None)?
+            .into_iter()
+            .map(ss1::Share::into_string)
+            .collect::<Vec<_>>();
+        write_shares(&shares, out, out_len, written)
+    })
+}
+
+/// Recovers the secret from the given `SS1` shares, one per line.
+///
+/// Writes the secret to `out`.
+///
+/// # Safety
+///
+/// `shares` must point to a NUL-terminated string, `out` to `out_len` writable
+/// bytes, and `written` to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_ss1_recover_secret(
+    shares: *const c_char,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> RsError {
+    ffi(|| {
+        let shares = shares_arg(shares)?
+            .iter()
+            .map(|raw| ss1::Share::from_string(raw))
+            .collect::<crate::errors::Result<Vec<_>>>()?;
+        let (secret, _, _) = ss1::recover_secret(&shares)?;
+        write_bytes(&secret, out, out_len, written)
+    })
+}
+
+/// Runs the body of an exported function, making sure no panic crosses the FFI boundary.
+fn ffi<F>(body: F) -> RsError
+where
+    F: FnOnce() -> Result<RsError, RsError> + UnwindSafe,
+{
+    match panic::catch_unwind(body) {
+        Ok(Ok(code)) | Ok(Err(code)) => code,
+        Err(_) => RsError::Panic,
+    }
+}
+
+unsafe fn bytes_arg<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], RsError> {
+    if ptr.is_null() {
+        return if len == 0 {
+            Ok(&[])
+        } else {
+            Err(RsError::NullPointer)
+        };
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char) -> Result<&'a str, RsError> {
+    if ptr.is_null() {
+        return Err(RsError::NullPointer);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| RsError::InvalidUtf8)
+}
+
+/// Splits the given string into shares, one per non-empty line.
+unsafe fn shares_arg(ptr: *const c_char) -> Result<Vec<String>, RsError> {
+    Ok(str_arg(ptr)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+unsafe fn write_bytes(
+    bytes: &[u8],
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> Result<RsError, RsError> {
+    if written.is_null() {
+        return Err(RsError::NullPointer);
+    }
+    *written = bytes.len();
+    if bytes.len() > out_len {
+        return Err(RsError::BufferTooSmall);
+    }
+    if out.is_null() {
+        return if bytes.is_empty() {
+            Ok(RsError::Ok)
+        } else {
+            Err(RsError::NullPointer)
+        };
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    Ok(RsError::Ok)
+}
+
+/// Writes the given string followed by a NUL byte, which is included in `written`.
+unsafe fn write_str(
+    s: &str,
+    out: *mut c_char,
+    out_len: usize,
+    written: *mut usize,
+) -> Result<RsError, RsError> {
+    let mut bytes = Vec::with_capacity(s.len() + 1);
+    bytes.extend_from_slice(s.as_bytes());
+    bytes.push(0);
+    write_bytes(&bytes, out as *mut u8, out_len, written)
+}
+
+unsafe fn write_shares(
+    shares: &[String],
+    out: *mut c_char,
+    out_len: usize,
+    written: *mut usize,
+) -> Result<RsError, RsError> {
+    write_str(&shares.join("\n"), out, out_len, written)
+}
@@ -0,0 +1,6 @@
+//! Interoperability with the share formats of other implementations of Shamir's
+//! secret sharing.
+
+pub mod sellibitze;
+pub mod ssss;
+pub mod vault;
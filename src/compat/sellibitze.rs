@@ -0,0 +1,194 @@
+//! Upgrades the shares of `secretshare`, the C++ implementation of Shamir's secret
+//! sharing by sellibitze which this crate started as a port of.
+//!
+//! Its shares read `k-n-data` like `sss` shares, but their data is the share itself,
+//! rather than a `ShareProto` message holding it along with its signature. Since both
+//! live in the same field, `sss::recover_secret` accepts them as they are, mixed
+//! with current shares, as long as they hold as much data as those, so that damaged
+//! shares are not mistaken for them. This module tells them apart, and converts them
+//! to the current format, which sets of shares of `secretshare` only must go through.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use etospheres_labs_rusty_secrets::compat::sellibitze;
+//! use etospheres_labs_rusty_secrets::sss;
+//!
+//! let legacy = vec!["2-1-1YAYwmOHqZ69jA".to_owned(), "2-4-F7rAjX3UOa53KA".to_owned()];
+//! assert!(sellibitze::is_legacy_share(&legacy[0]));
+//!
+//! let shares = sellibitze::upgrade_shares(&legacy, true).unwrap();
+//! assert!(!sellibitze::is_legacy_share(&shares[0]));
+//!
+//! let secret = sss::recover_secret(&shares, true).unwrap();
+//! assert_eq!(secret.as_bytes(), b"My secret\n");
+//! ```
+
+use prost::Message;
+
+use crate::errors::*;
+use crate::proto::wrapped::ShareProto;
+use crate::share::validation::validate_shares;
+use crate::sss::{Share, Sss};
+
+/// Returns whether the given share can have been dealt by `secretshare`: its data is
+/// standard base64, but not a `ShareProto` message holding the share.
+///
+/// A single share cannot be told apart from a damaged current share this way.
+/// `sss::recover_secret` only accepts such shares if they hold as much data as the
+/// current shares they are mixed with, and `upgrade_shares` as the other shares.
+pub fn is_legacy_share(share: &str) -> bool {
+    Share::from_legacy_string(share).is_ok_and(|share| share.is_some())
+}
+
+/// Returns whether the data of a share is not a `ShareProto` message holding the
+/// share, which always has some, so that it may be the share itself.
+pub(crate) fn may_be_legacy_data(data: &[u8]) -> bool {
+    !data.is_empty() && ShareProto::decode(data).map_or(true, |share| share.shamir_data.is_empty())
+}
+
+/// Converts the given shares, dealt by `secretshare` or in the current format, to
+/// the current format.
+///
+/// Shares which are not valid protobuf messages are only taken for shares of
+/// `secretshare` if they hold as much data as the other shares, and otherwise fail
+/// to parse. Unlike `sss::recover_secret`, shares of `secretshare` need not be mixed
+/// with current shares.
+///
+/// If `sign_shares` is `true`, the shares are then signed together, dropping the
+/// signatures of current shares, so they must be consistent, and at least as many
+/// as the threshold. Otherwise, current shares are returned as they are.
+pub fn upgrade_shares(shares: &[String], sign_shares: bool) -> Result<Vec<String>> {
+    let parsed = Share::parse_all_legacy(shares, false, true)?;
+    if !sign_shares {
+        return Ok(parsed
+            .into_iter()
+            .zip(shares)
+            .map(|((share, is_legacy), raw)| {
+                if is_legacy {
+                    share.into_string()
+                } else {
+                    raw.clone()
+                }
+            })
+            .collect());
+    }
+
+    let mut shares = parsed
+        .into_iter()
+        .map(|(share, _)| share)
+        .collect::<Vec<_>>();
+    validate_shares(&shares)?;
+    Sss::resign_shares(&mut shares);
+    Ok(shares.into_iter().map(Share::into_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::sss::{recover_secret, split_secret};
+
+    const LEGACY_SHARES: [&str; 4] = [
+        "2-1-1YAYwmOHqZ69jA",
+        "2-4-F7rAjX3UOa53KA",
+        "2-2-YJZQDGm22Y77Gw",
+        "2-5-j0P4PHsw4lW+rg",
+    ];
+
+    fn legacy_shares() -> Vec<String> {
+        LEGACY_SHARES
+            .iter()
+            .map(|&share| share.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn legacy_shares_are_detected() {
+        assert!(legacy_shares().iter().all(|share| is_legacy_share(share)));
+
+        let shares = split_secret(2, 3, b"My secret\n", true).unwrap();
+        assert!(!shares.iter().any(|share| is_legacy_share(share)));
+        assert!(!is_legacy_share("2-1-"));
+        assert!(!is_legacy_share("2-1-!"));
+        assert!(!is_legacy_share("2-1"));
+    }
+
+    #[test]
+    fn upgraded_shares_recover_the_secret() {
+        let upgraded = upgrade_shares(&legacy_shares(), false).unwrap();
+        assert!(!upgraded.iter().any(|share| is_legacy_share(share)));
+        assert_eq!(upgrade_shares(&upgraded, false).unwrap(), upgraded);
+        assert_eq!(
+            recover_secret(&upgraded, false).unwrap(),
+            &b"My secret\n"[..]
+        );
+
+        let mixed = [upgraded[0].clone(), LEGACY_SHARES[1].to_owned()];
+        assert_eq!(recover_secret(&mixed, false).unwrap(), &b"My secret\n"[..]);
+    }
+
+    #[test]
+    fn legacy_shares_alone_must_be_upgraded() {
+        use base64::Engine;
+
+        let err = recover_secret(&legacy_shares(), false).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingErrorProtobuf(_)));
+
+        // Current shares corrupted alike are not taken for legacy ones either.
+        let config = base64::engine::general_purpose::STANDARD_NO_PAD;
+        let shares = split_secret(2, 3, b"My secret\n", false).unwrap();
+        let corrupted = shares
+            .iter()
+            .map(|share| {
+                let (k, i, data) = crate::encoding::split_parts(share).unwrap();
+                let mut data = config.decode(data).unwrap();
+                // An invalid wire type.
+                data[0] = 0x07;
+                format!("{}-{}-{}", k, i, config.encode(&data))
+            })
+            .collect::<Vec<_>>();
+        assert!(corrupted.iter().all(|share| is_legacy_share(share)));
+        let err = recover_secret(&corrupted, false).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingErrorProtobuf(_)));
+    }
+
+    #[test]
+    fn damaged_shares_are_not_taken_for_legacy_ones() {
+        use base64::Engine;
+
+        let config = base64::engine::general_purpose::STANDARD_NO_PAD;
+        let shares = split_secret(2, 3, b"My secret\n", false).unwrap();
+        let (k, i, data) = crate::encoding::split_parts(&shares[1]).unwrap();
+        let mut data = config.decode(data).unwrap();
+        data.truncate(data.len() - 3);
+        let damaged = format!("{}-{}-{}", k, i, config.encode(&data));
+        assert!(is_legacy_share(&damaged));
+
+        let err = recover_secret(&[shares[0].clone(), damaged.clone()], false).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingErrorProtobuf(_)));
+        let err =
+            recover_secret(&[LEGACY_SHARES[0].to_owned(), damaged.clone()], false).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingErrorProtobuf(_)));
+
+        let err = upgrade_shares(&[shares[0].clone(), damaged.clone()], false).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingErrorProtobuf(_)));
+        let err = upgrade_shares(&[LEGACY_SHARES[0].to_owned(), damaged], false).unwrap_err();
+        assert!(matches!(err, Error::ShareParsingErrorProtobuf(_)));
+    }
+
+    #[test]
+    fn upgraded_shares_can_be_signed() {
+        let unsigned = upgrade_shares(&legacy_shares()[1..], false).unwrap();
+        assert!(matches!(
+            recover_secret(&unsigned, true),
+            Err(Error::MissingSignature(_))
+        ));
+
+        let signed = upgrade_shares(&legacy_shares()[1..], true).unwrap();
+        assert_eq!(recover_secret(&signed, true).unwrap(), &b"My secret\n"[..]);
+
+        let err = upgrade_shares(&legacy_shares()[..1], true).unwrap_err();
+        assert!(matches!(err, Error::MissingShares(1, 2)));
+    }
+}
@@ -0,0 +1,479 @@
+//! Splits and recovers secrets in the format of `ssss`, the `ssss-split` and
+//! `ssss-combine` command-line tools of B. Poettering.
+//!
+//! An `ssss` share reads `[token-]index-value`: the optional token is a free-form
+//! name given to the set, the index is the x-coordinate of the share, and the value is
+//! in hexadecimal, four bits of which per digit make up the security level. The
+//! shares live in the field with 2^n elements, n being the security level, which is
+//! a multiple of 8 between 8 and 1024 bits.
+//!
+//! Two quirks of `ssss` are reproduced. First, the polynomial of degree k interpolating
+//! the shares has a leading coefficient of 1, so that `k` shares are still enough to
+//! recover the secret, but shares do not tell their threshold, which must be known.
+//! Second, unless disabled with `-D`, the secret goes through a diffusion layer
+//! before being split, for security levels of at least 64 bits.
+//!
+//! See <http://point-at-infinity.org/ssss/>.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use etospheres_labs_rusty_secrets::compat::ssss::Ssss;
+//!
+//! let shares = Ssss::new()
+//!     .token("backup")
+//!     .split_secret(3, 5, b"my secret root password")
+//!     .unwrap();
+//! assert!(shares[0].starts_with("backup-1-"));
+//!
+//! let secret = Ssss::new().recover_secret(3, &shares[2..]).unwrap();
+//! assert_eq!(secret.as_bytes(), b"my secret root password");
+//! ```
+
+use rand_core::{CryptoRng, RngCore};
+
+use crate::errors::*;
+use crate::field::{Field, Gf2n};
+use crate::lagrange::LagrangeBasis;
+use crate::secret::{Secret, Zeroizing};
+
+/// The diffusion layer is skipped for smaller security levels.
+const MIN_DIFFUSION_DEGREE: usize = 64;
+/// The diffusion layer enciphers `DIFFUSION_ROUNDS / 2` slices per byte of the secret.
+const DIFFUSION_ROUNDS: usize = 40;
+
+/// Splits and recovers secrets as do `ssss-split` and `ssss-combine`.
+#[derive(Clone, Debug)]
+pub struct Ssss {
+    security_level: Option<usize>,
+    token: Option<String>,
+    diffusion: bool,
+    trim_leading_zeros: bool,
+}
+
+impl Default for Ssss {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Ssss {
+    /// Splits secrets with a security level of 8 bits per byte of the secret,
+    /// without token, and with the diffusion layer, as does `ssss-split` by default.
+    pub fn new() -> Self {
+        Ssss {
+            security_level: None,
+            token: None,
+            diffusion: true,
+            trim_leading_zeros: false,
+        }
+    }
+
+    /// Sets the security level, in bits, as does the `-s` option of `ssss-split`.
+    /// Secrets shorter than the security level are padded with zeros on the left,
+    /// which are recovered along with them unless `Ssss::trim_leading_zeros` is set.
+    pub fn security_level(mut self, bits: usize) -> Self {
+        self.security_level = Some(bits);
+        self
+    }
+
+    /// Prefixes the shares with the given token, as does the `-w` option of `ssss-split`.
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_owned());
+        self
+    }
+
+    /// Sets whether the secret goes through the diffusion layer, which the `-D`
+    /// option of `ssss-split` and `ssss-combine` disables. The shares must be
+    /// recovered with the same setting as they were split.
+    pub fn diffusion(mut self, diffusion: bool) -> Self {
+        self.diffusion = diffusion;
+        self
+    }
+
+    /// Sets whether the leading zero bytes of recovered secrets are stripped, as
+    /// `ssss-combine` does when printing them. This strips the padding of secrets
+    /// shorter than the security level, but also the leading zero bytes of the
+    /// secret itself, so it is off by default.
+    pub fn trim_leading_zeros(mut self, trim_leading_zeros: bool) -> Self {
+        self.trim_leading_zeros = trim_leading_zeros;
+        self
+    }
+
+    /// Splits the secret into `n` shares, `k` of which are needed to recover it.
+    ///
+    /// Uses the thread-local random number generator of `rand` as a source of entropy.
+    pub fn split_secret(&self, k: u8, n: u8, secret: &[u8]) -> Result<Vec<String>> {
+        self.split_secret_rng(&mut rand::rng(), k, n, secret)
+    }
+
+    /// Splits the secret into `n` shares, `k` of which are needed to recover it.
+    ///
+    /// Uses the given random number generator as a source of entropy.
+    pub fn split_secret_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        k: u8,
+        n: u8,
+        secret: &[u8],
+    ) -> Result<Vec<String>> {
+        if k < 2 {
+            bail!(Error::ThresholdTooSmall(k));
+        }
+        if k > n {
+            bail!(Error::ThresholdTooBig(k, n));
+        }
+        if secret.is_empty() {
+            bail!(Error::EmptySecret);
+        }
+        let degree = self.security_level.unwrap_or(secret.len() * 8);
+        if !Gf2n::is_valid_degree(degree) {
+            bail!(Error::InvalidSecurityLevel(degree));
+        }
+        if secret.len() > degree / 8 {
+            bail!(Error::SecretTooBig(secret.len(), degree / 8));
+        }
+
+        let mut padded = Zeroizing::new(vec![0; degree / 8]);
+        padded[degree / 8 - secret.len()..].copy_from_slice(secret);
+        if self.diffusion && degree >= MIN_DIFFUSION_DEGREE {
+            diffuse(&mut padded, false);
+        }
+
+        let mut coefficients = vec![Gf2n::from_be_bytes(degree, &padded)];
+        let mut random = Zeroizing::new(vec![0; degree / 8]);
+        for _ in 1..k {
+            rng.fill_bytes(&mut random);
+            coefficients.push(Gf2n::from_be_bytes(degree, &random));
+        }
+
+        let token = self
+            .token
+            .as_ref()
+            .map_or(String::new(), |token| format!("{}-", token));
+        let width = n.to_string().len();
+        let shares = (1..=n)
+            .map(|index| {
+                let x = Gf2n::from_be_bytes(degree, &[index]);
+                // The polynomial is monic: its coefficient of degree k is 1.
+                let y = coefficients[1..]
+                    .iter()
+                    .rev()
+                    .fold(x, |acc, &c| (acc + c) * x)
+                    + coefficients[0];
+                format!(
+                    "{}{:0width$}-{}",
+                    token,
+                    index,
+                    to_hex(&y.to_be_bytes()),
+                    width = width
+                )
+            })
+            .collect();
+        Ok(shares)
+    }
+
+    /// Recovers the secret from the first `k` of the given shares, `k` being the
+    /// threshold they were split with.
+    ///
+    /// Tokens are ignored. The secret is returned with one byte per 8 bits of the
+    /// security level, see `Ssss::trim_leading_zeros`.
+    pub fn recover_secret(&self, k: u8, shares: &[String]) -> Result<Secret> {
+        if k < 2 {
+            bail!(Error::ThresholdTooSmall(k));
+        }
+        if shares.len() < k.into() {
+            bail!(Error::MissingShares(shares.len(), k));
+        }
+
+        let shares = shares[..k.into()]
+            .iter()
+            .map(|share| parse_share(share))
+            .collect::<Result<Vec<_>>>()?;
+
+        let degree = shares[0].1.len() * 8;
+        let mut xs = Vec::with_capacity(shares.len());
+        let mut ys = Vec::with_capacity(shares.len());
+        for (index, value) in &shares {
+            if value.len() * 8 != degree {
+                let ids = shares
+                    .iter()
+                    .filter(|(_, value)| value.len() * 8 == degree)
+                    .map(|(index, _)| *index)
+                    .collect();
+                bail!(Error::InconsistentSecretLengths(
+                    *index,
+                    value.len(),
+                    ids,
+                    degree / 8
+                ));
+            }
+            if shares.iter().filter(|(other, _)| other == index).count() > 1 {
+                bail!(Error::DuplicateShareId(*index));
+            }
+
+            let x = Gf2n::from_be_bytes(degree, &[*index]);
+            // Remove the leading term of the polynomial, which has a degree of k.
+            let leading = (0..k).fold(Gf2n::one(), |acc, _| acc * x);
+            xs.push(x);
+            ys.push(Gf2n::from_be_bytes(degree, value) - leading);
+        }
+
+        let basis = LagrangeBasis::new(&xs).at(Gf2n::zero());
+        let secret = basis
+            .iter()
+            .zip(&ys)
+            .fold(Gf2n::zero(), |acc, (&b, &y)| acc + b * y);
+
+        let mut secret = Zeroizing::new(secret.to_be_bytes());
+        if self.diffusion && degree >= MIN_DIFFUSION_DEGREE {
+            diffuse(&mut secret, true);
+        }
+        let start = if self.trim_leading_zeros {
+            secret
+                .iter()
+                .position(|&byte| byte != 0)
+                .unwrap_or(secret.len())
+        } else {
+            0
+        };
+        Ok(Secret::new(secret[start..].to_vec()))
+    }
+}
+
+/// Parses a share into its index and its value.
+fn parse_share(share: &str) -> Result<(u8, Vec<u8>)> {
+    let mut parts = share.trim().rsplitn(3, '-');
+    let (value, index) = match (parts.next(), parts.next()) {
+        (Some(value), Some(index)) => (value, index),
+        _ => bail!(Error::ShareParsingError(
+            "An ssss share must read [token-]index-value.".to_owned()
+        )),
+    };
+
+    let index = index.parse::<u8>().map_err(Error::IntegerParsingError)?;
+    if index == 0 {
+        bail!(Error::ShareParsingInvalidShareId(index));
+    }
+    if !Gf2n::is_valid_degree(value.len() * 4) {
+        bail!(Error::ShareParsingError(format!(
+            "The value of share {} has an invalid length of {} digits.",
+            index,
+            value.len()
+        )));
+    }
+    let value = from_hex(value).ok_or_else(|| {
+        Error::ShareParsingError(format!("The value of share {} is not hexadecimal.", index))
+    })?;
+
+    Ok((index, value))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// The diffusion layer of `ssss`, which enciphers overlapping slices of 8 bytes of
+/// the secret with XTEA under a zero key, or deciphers them in reverse order.
+fn diffuse(secret: &mut [u8], decipher: bool) {
+    let len = secret.len();
+
+    // `ssss` lays the secret out in 16-bit words, the least significant one first,
+    // with the single byte of an incomplete word last.
+    let mut words = vec![0; len];
+    for k in 0..len / 2 {
+        words[2 * k] = secret[len - 2 - 2 * k];
+        words[2 * k + 1] = secret[len - 1 - 2 * k];
+    }
+    if len % 2 == 1 {
+        words[len - 1] = secret[0];
+    }
+
+    let slices = (0..DIFFUSION_ROUNDS * len).step_by(2);
+    if decipher {
+        for start in slices.rev() {
+            process_slice(&mut words, start, xtea_decipher);
+        }
+    } else {
+        for start in slices {
+            process_slice(&mut words, start, xtea_encipher);
+        }
+    }
+
+    for k in 0..len / 2 {
+        secret[len - 2 - 2 * k] = words[2 * k];
+        secret[len - 1 - 2 * k] = words[2 * k + 1];
+    }
+    if len % 2 == 1 {
+        secret[0] = words[len - 1];
+    }
+}
+
+/// Processes the block of 8 bytes starting at `start`, wrapping around the end.
+fn process_slice(data: &mut [u8], start: usize, process_block: fn(&mut [u32; 2])) {
+    let len = data.len();
+    let mut block = [0u32; 2];
+    for (i, word) in block.iter_mut().enumerate() {
+        *word = (0..4).fold(0, |acc, j| {
+            (acc << 8) | u32::from(data[(start + 4 * i + j) % len])
+        });
+    }
+    process_block(&mut block);
+    for (i, word) in block.iter().enumerate() {
+        for (j, byte) in word.to_be_bytes().iter().enumerate() {
+            data[(start + 4 * i + j) % len] = *byte;
+        }
+    }
+}
+
+const XTEA_DELTA: u32 = 0x9E37_79B9;
+const XTEA_ROUNDS: u32 = 32;
+
+fn xtea_encipher(v: &mut [u32; 2]) {
+    let mut sum = 0u32;
+    for _ in 0..XTEA_ROUNDS {
+        v[0] = v[0].wrapping_add((((v[1] << 4) ^ (v[1] >> 5)).wrapping_add(v[1])) ^ sum);
+        sum = sum.wrapping_add(XTEA_DELTA);
+        v[1] = v[1].wrapping_add((((v[0] << 4) ^ (v[0] >> 5)).wrapping_add(v[0])) ^ sum);
+    }
+}
+
+fn xtea_decipher(v: &mut [u32; 2]) {
+    let mut sum = XTEA_DELTA.wrapping_mul(XTEA_ROUNDS);
+    for _ in 0..XTEA_ROUNDS {
+        v[1] = v[1].wrapping_sub((((v[0] << 4) ^ (v[0] >> 5)).wrapping_add(v[0])) ^ sum);
+        sum = sum.wrapping_sub(XTEA_DELTA);
+        v[0] = v[0].wrapping_sub((((v[1] << 4) ^ (v[1] >> 5)).wrapping_add(v[1])) ^ sum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn diffusion_is_reversible() {
+        for len in [8, 9, 23, 128] {
+            let secret = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+            let mut diffused = secret.clone();
+            diffuse(&mut diffused, false);
+            assert_ne!(diffused, secret);
+            diffuse(&mut diffused, true);
+            assert_eq!(diffused, secret);
+        }
+    }
+
+    #[test]
+    fn split_then_recover() {
+        let mut rng = ChaChaRng::from_seed([42; 32]);
+        for (degree, diffusion) in [(None, true), (Some(56), true), (Some(256), false)] {
+            let mut ssss = Ssss::new().diffusion(diffusion);
+            if let Some(degree) = degree {
+                ssss = ssss.security_level(degree);
+            }
+            let shares = ssss.split_secret_rng(&mut rng, 3, 12, b"secret!").unwrap();
+            assert!(shares[0].starts_with("01-"));
+            let ssss = ssss.trim_leading_zeros(true);
+            assert_eq!(
+                ssss.recover_secret(3, &shares[5..]).unwrap(),
+                &b"secret!"[..]
+            );
+            let shuffled = [shares[11].clone(), shares[0].clone(), shares[4].clone()];
+            assert_eq!(ssss.recover_secret(3, &shuffled).unwrap(), &b"secret!"[..]);
+        }
+    }
+
+    #[test]
+    fn leading_zeros_are_kept() {
+        let mut rng = ChaChaRng::from_seed([42; 32]);
+        let secret = b"\x00\x01binarykey";
+        for ssss in [Ssss::new(), Ssss::new().diffusion(false)] {
+            let shares = ssss.split_secret_rng(&mut rng, 2, 3, secret).unwrap();
+            assert_eq!(ssss.recover_secret(2, &shares).unwrap(), &secret[..]);
+            let trimmed = ssss.trim_leading_zeros(true).recover_secret(2, &shares);
+            assert_eq!(trimmed.unwrap(), &secret[1..]);
+        }
+
+        let ssss = Ssss::new().security_level(64);
+        let shares = ssss.split_secret_rng(&mut rng, 2, 3, b"key").unwrap();
+        assert_eq!(
+            ssss.recover_secret(2, &shares).unwrap(),
+            &b"\0\0\0\0\0key"[..]
+        );
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let ssss = Ssss::new();
+        let err = ssss.split_secret(1, 5, b"secret").unwrap_err();
+        assert!(matches!(err, Error::ThresholdTooSmall(1)));
+        let err = ssss.split_secret(6, 5, b"secret").unwrap_err();
+        assert!(matches!(err, Error::ThresholdTooBig(6, 5)));
+        let err = ssss.split_secret(2, 5, b"").unwrap_err();
+        assert!(matches!(err, Error::EmptySecret));
+        let err = ssss
+            .clone()
+            .security_level(12)
+            .split_secret(2, 5, b"s")
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidSecurityLevel(12)));
+        let err = ssss
+            .clone()
+            .security_level(8)
+            .split_secret(2, 5, b"secret")
+            .unwrap_err();
+        assert!(matches!(err, Error::SecretTooBig(6, 1)));
+        let err = ssss.split_secret(2, 5, &[0; 129]).unwrap_err();
+        assert!(matches!(err, Error::InvalidSecurityLevel(1032)));
+    }
+
+    #[test]
+    fn invalid_shares() {
+        let recover = |shares: &[&str]| {
+            let shares = shares.iter().map(|&s| s.to_owned()).collect::<Vec<_>>();
+            Ssss::new().recover_secret(2, &shares).unwrap_err()
+        };
+        assert!(matches!(recover(&["1-00ff"]), Error::MissingShares(1, 2)));
+        assert!(matches!(
+            recover(&["1-00ff", "1-0102"]),
+            Error::DuplicateShareId(1)
+        ));
+        assert!(matches!(
+            recover(&["1-00ff", "2-01020"]),
+            Error::ShareParsingError(_)
+        ));
+        assert!(matches!(
+            recover(&["1-00ff", "2-01020304"]),
+            Error::InconsistentSecretLengths(2, 4, _, 2)
+        ));
+        assert!(matches!(
+            recover(&["1-00ff", "0-0102"]),
+            Error::ShareParsingInvalidShareId(0)
+        ));
+        assert!(matches!(
+            recover(&["1-00ff", "x-0102"]),
+            Error::IntegerParsingError(_)
+        ));
+        assert!(matches!(
+            recover(&["1-00ff", "2-01zz"]),
+            Error::ShareParsingError(_)
+        ));
+        assert!(matches!(
+            recover(&["1-00ff", "0102"]),
+            Error::ShareParsingError(_)
+        ));
+    }
+}
@@ -0,0 +1,277 @@
+//! Splits and recovers secrets in the format of the unseal keys of HashiCorp Vault.
+//!
+//! A Vault share is the value of the polynomial for every byte of the secret,
+//! followed by a single byte holding the x-coordinate of the share, a random non-zero
+//! value distinct from those of the other shares. Vault works in the field defined
+//! by the polynomial x^8 + x^4 + x^3 + x + 1 rather than in the one of `sss`.
+//!
+//! Since both fields differ, a Vault share cannot be turned into an `sss` share of
+//! the same secret: migrating a secret from one format to the other goes through
+//! recovering it, then splitting it again.
+//!
+//! Vault shares do not record the threshold, so recovering a secret from too few
+//! shares goes undetected and yields a wrong secret.
+//!
+//! See <https://github.com/hashicorp/vault/blob/main/shamir/shamir.go>.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use etospheres_labs_rusty_secrets::compat::vault;
+//!
+//! let unseal_keys = vault::split_secret(3, 5, b"my root key").unwrap();
+//! let secret = vault::recover_secret(&unseal_keys[1..4]).unwrap();
+//! assert_eq!(secret.as_bytes(), b"my root key");
+//! ```
+
+use base64::Engine;
+use rand::seq::SliceRandom;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::errors::*;
+use crate::field::{Field, Rijndael};
+use crate::lagrange::LagrangeBasis;
+use crate::secret::{Secret, Zeroizing};
+
+/// The unseal keys printed by `vault operator init` are padded base64.
+const BASE64_CONFIG: base64::engine::general_purpose::GeneralPurpose =
+    base64::engine::general_purpose::STANDARD;
+
+/// Splits the secret into `n` unseal keys, `k` of which are needed to recover it.
+///
+/// The keys are encoded in base64, as in the `unseal_keys_b64` field of the output
+/// of `vault operator init`. Uses the thread-local random number generator of `rand`
+/// as a source of entropy.
+pub fn split_secret(k: u8, n: u8, secret: &[u8]) -> Result<Vec<String>> {
+    split_secret_rng(&mut rand::rng(), k, n, secret)
+}
+
+/// Splits the secret into `n` unseal keys, `k` of which are needed to recover it.
+///
+/// Uses the given random number generator as a source of entropy.
+pub fn split_secret_rng<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    k: u8,
+    n: u8,
+    secret: &[u8],
+) -> Result<Vec<String>> {
+    Ok(split_secret_parts_rng(rng, k, n, secret)?
+        .iter()
+        .map(|part| format_unseal_key(part))
+        .collect())
+}
+
+/// Splits the secret into `n` raw shares, `k` of which are needed to recover it,
+/// as returned by `shamir.Split` in Vault.
+pub fn split_secret_parts_rng<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    k: u8,
+    n: u8,
+    secret: &[u8],
+) -> Result<Vec<Vec<u8>>> {
+    if k < 2 {
+        bail!(Error::ThresholdTooSmall(k));
+    }
+    if k > n {
+        bail!(Error::ThresholdTooBig(k, n));
+    }
+    if secret.is_empty() {
+        bail!(Error::EmptySecret);
+    }
+
+    let mut xs = (1..=u8::MAX).collect::<Vec<_>>();
+    xs.shuffle(rng);
+    xs.truncate(n.into());
+
+    let mut parts = xs
+        .iter()
+        .map(|&x| {
+            let mut part = vec![0; secret.len() + 1];
+            part[secret.len()] = x;
+            part
+        })
+        .collect::<Vec<_>>();
+
+    let mut coefficients = Zeroizing::new(vec![0; k.into()]);
+    for (i, &byte) in secret.iter().enumerate() {
+        coefficients[0] = byte;
+        rng.fill_bytes(&mut coefficients[1..]);
+        for part in &mut parts {
+            let x = Rijndael(part[secret.len()]);
+            // Horner's method, from the coefficient of highest degree.
+            part[i] = coefficients
+                .iter()
+                .rev()
+                .fold(Rijndael::zero(), |acc, &c| acc * x + Rijndael(c))
+                .0;
+        }
+    }
+
+    Ok(parts)
+}
+
+/// Recovers the secret from the given unseal keys, in base64 or in hexadecimal.
+pub fn recover_secret(unseal_keys: &[String]) -> Result<Secret> {
+    let parts = unseal_keys
+        .iter()
+        .map(|key| parse_unseal_key(key))
+        .collect::<Result<Vec<_>>>()?;
+    recover_secret_parts(&parts)
+}
+
+/// Recovers the secret from the given raw shares, as does `shamir.Combine` in Vault.
+///
+/// At least two shares are needed, of the same length.
+pub fn recover_secret_parts(parts: &[Vec<u8>]) -> Result<Secret> {
+    if parts.len() < 2 {
+        bail!(Error::MissingShares(parts.len(), 2));
+    }
+
+    let len = parts[0].len();
+    if len < 2 {
+        bail!(Error::ShareParsingError(
+            "A Vault share must be at least 2 bytes long.".to_owned()
+        ));
+    }
+    let mut xs = Vec::with_capacity(parts.len());
+    for part in parts {
+        let x = part.last().copied().unwrap_or(0);
+        if part.len() != len {
+            let ids = xs.iter().map(|&Rijndael(x)| x).collect();
+            bail!(Error::InconsistentSecretLengths(
+                x,
+                part.len().saturating_sub(1),
+                ids,
+                len - 1
+            ));
+        }
+        if x == 0 {
+            bail!(Error::ShareParsingInvalidShareId(x));
+        }
+        if xs.contains(&Rijndael(x)) {
+            bail!(Error::DuplicateShareId(x));
+        }
+        xs.push(Rijndael(x));
+    }
+
+    let basis = LagrangeBasis::new(&xs).at(Rijndael::zero());
+    let secret = Zeroizing::new(
+        (0..len - 1)
+            .map(|i| {
+                basis
+                    .iter()
+                    .zip(parts)
+                    .fold(Rijndael::zero(), |acc, (&b, part)| {
+                        acc + b * Rijndael(part[i])
+                    })
+                    .0
+            })
+            .collect::<Vec<_>>(),
+    );
+    Ok(Secret::new(secret.to_vec()))
+}
+
+/// Decodes an unseal key, which may be in hexadecimal or in base64.
+///
+/// Like Vault, tries hexadecimal first.
+pub fn parse_unseal_key(unseal_key: &str) -> Result<Vec<u8>> {
+    let unseal_key = unseal_key.trim();
+    if let Some(part) = from_hex(unseal_key) {
+        return Ok(part);
+    }
+    BASE64_CONFIG
+        .decode(unseal_key)
+        .map_err(Error::ShareParsingErrorBase64)
+}
+
+/// Encodes a raw share as an unseal key, in base64.
+pub fn format_unseal_key(part: &[u8]) -> String {
+    BASE64_CONFIG.encode(part)
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn split_then_recover() {
+        let mut rng = ChaChaRng::from_seed([42; 32]);
+        let secret = b"0123456789abcdef0123456789abcdef";
+        let parts = split_secret_parts_rng(&mut rng, 3, 5, secret).unwrap();
+
+        assert!(parts.iter().all(|part| part.len() == secret.len() + 1));
+        let mut xs = parts
+            .iter()
+            .map(|part| part[secret.len()])
+            .collect::<Vec<_>>();
+        xs.sort_unstable();
+        xs.dedup();
+        assert_eq!(xs.len(), 5);
+        assert!(!xs.contains(&0));
+
+        assert_eq!(recover_secret_parts(&parts[2..]).unwrap(), &secret[..]);
+        assert_eq!(recover_secret_parts(&parts).unwrap(), &secret[..]);
+        assert_ne!(recover_secret_parts(&parts[..2]).unwrap(), &secret[..]);
+    }
+
+    #[test]
+    fn unseal_keys_in_hex_or_base64() {
+        let part = vec![0x12, 0xab, 0x01];
+        assert_eq!(parse_unseal_key("12ab01").unwrap(), part);
+        assert_eq!(parse_unseal_key("12AB01\n").unwrap(), part);
+        assert_eq!(parse_unseal_key(&format_unseal_key(&part)).unwrap(), part);
+        assert!(parse_unseal_key("12ab0").is_err());
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let mut rng = ChaChaRng::from_seed([42; 32]);
+        let mut split = |k, n, secret: &[u8]| split_secret_parts_rng(&mut rng, k, n, secret);
+        assert!(matches!(
+            split(1, 5, b"secret"),
+            Err(Error::ThresholdTooSmall(1))
+        ));
+        assert!(matches!(
+            split(6, 5, b"secret"),
+            Err(Error::ThresholdTooBig(6, 5))
+        ));
+        assert!(matches!(split(2, 5, b""), Err(Error::EmptySecret)));
+        assert_eq!(split(255, 255, b"secret").unwrap().len(), 255);
+    }
+
+    #[test]
+    fn invalid_parts() {
+        let parts = [vec![1, 2, 3], vec![4, 5, 3]];
+        assert!(matches!(
+            recover_secret_parts(&parts),
+            Err(Error::DuplicateShareId(3))
+        ));
+        let parts = [vec![1, 2, 3], vec![4, 5]];
+        assert!(matches!(
+            recover_secret_parts(&parts),
+            Err(Error::InconsistentSecretLengths(5, 1, _, 2))
+        ));
+        let parts = [vec![1, 2, 3], vec![4, 5, 0]];
+        assert!(matches!(
+            recover_secret_parts(&parts),
+            Err(Error::ShareParsingInvalidShareId(0))
+        ));
+        assert!(matches!(
+            recover_secret_parts(&parts[..1]),
+            Err(Error::MissingShares(1, 2))
+        ));
+    }
+}
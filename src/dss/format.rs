@@ -1,37 +1,43 @@
-use base64::Engine;
-use prost::Message;
-
+use crate::encoding;
 use crate::errors::*;
 use crate::proto::dss::ShareProto;
-
-const BASE64_CONFIG: base64::engine::general_purpose::GeneralPurpose =
-    base64::engine::general_purpose::STANDARD_NO_PAD;
-
-pub(crate) fn format_share_protobuf(share: &ShareProto) -> String {
-    let mut buf = Vec::with_capacity(share.encoded_len());
-    // Unwrap is safe, since we have reserved sufficient capacity in the vector.
-    share.encode(&mut buf).unwrap();
-    let base64_data = BASE64_CONFIG.encode(buf);
-    format!("{}-{}-{}", share.threshold, share.id, base64_data)
+use crate::proto::SchemeProto;
+use crate::share::envelope::Envelope;
+use crate::share::version::decode_share;
+
+/// The binary encoding of a share, which every format of shares encodes.
+pub(crate) fn share_envelope(share: &ShareProto) -> Envelope {
+    // Shares are dealt with `u8` thresholds and identifiers.
+    Envelope::new(share.scheme(), share.threshold as u8, share.id as u8, share)
 }
 
 pub(crate) fn parse_share_protobuf(raw: &str) -> Result<ShareProto> {
-    let (threshold, id, base64_data) = parse_raw_share(raw)?;
+    parse_share_envelope(encoding::decode(raw)?)
+}
 
-    let data = BASE64_CONFIG.decode(base64_data).chain_err(|| {
-        ErrorKind::ShareParsingError("Base64 decoding of data block failed".to_string())
-    })?;
+/// Parses a share encoded by `Envelope::to_bytes`, dealt by the given scheme.
+pub(crate) fn parse_share_bytes(bytes: &[u8], scheme: SchemeProto) -> Result<ShareProto> {
+    let envelope = Envelope::from_bytes(bytes)?;
+    envelope.expect_scheme(&[scheme])?;
+    parse_share_envelope(envelope)
+}
+
+fn parse_share_envelope(envelope: Envelope) -> Result<ShareProto> {
+    let share_proto = share_protobuf_from_parts(
+        envelope.threshold.into(),
+        envelope.id.into(),
+        &envelope.body,
+    )?;
+    envelope.check_scheme(share_proto.scheme())?;
+    Ok(share_proto)
+}
 
-    let share_proto = ShareProto::decode(data.as_slice()).map_err(|e| {
-        ErrorKind::ShareParsingError(format!(
-            "Protobuf decoding of data block failed with error: {} .",
-            e
-        ))
-    })?;
+fn share_protobuf_from_parts(threshold: u32, id: u32, data: &[u8]) -> Result<ShareProto> {
+    let share_proto = decode_share::<ShareProto>(data)?;
 
     if threshold != share_proto.threshold {
         bail! {
-            ErrorKind::ShareParsingError(
+            Error::ShareParsingError(
                 format!(
                 "Incompatible thresholds between decoded Protobuf provided \
                  (k={}) and raw share (k={})", share_proto.threshold, threshold
@@ -41,7 +47,7 @@ pub(crate) fn parse_share_protobuf(raw: &str) -> Result<ShareProto> {
 
     if id != share_proto.id {
         bail! {
-            ErrorKind::ShareParsingError(
+            Error::ShareParsingError(
                 format!(
                 "Incompatible ids between decoded Protobuf provided \
                  (i={}) and raw share (i={})", share_proto.id, id
@@ -51,24 +57,3 @@ pub(crate) fn parse_share_protobuf(raw: &str) -> Result<ShareProto> {
 
     Ok(share_proto)
 }
-
-fn parse_raw_share(raw: &str) -> Result<(u32, u32, String)> {
-    let parts: Vec<_> = raw.trim().split('-').collect();
-
-    if parts.len() != 3 {
-        bail! {
-            ErrorKind::ShareParsingError(
-                format!(
-                    "Expected 3 parts separated by a minus sign. Found {}.",
-                    raw
-                ),
-            )
-        };
-    }
-
-    let mut iter = parts.into_iter();
-    let k = iter.next().unwrap().parse::<u32>()?;
-    let i = iter.next().unwrap().parse::<u32>()?;
-    let data = iter.next().unwrap();
-    Ok((k, i, data.to_string()))
-}
@@ -33,7 +33,7 @@ pub mod thss;
 mod metadata;
 
 mod format;
-mod random;
+pub(crate) mod random;
 mod utils;
 
 /// Define the access structure used to deal and recover the shares.
@@ -49,6 +49,7 @@ mod utils;
 /// };
 /// ```
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccessStructure {
     /// The minimum amount of shares required to recover the secret.
     pub threshold: u8,
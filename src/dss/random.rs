@@ -1,5 +1,9 @@
 use crate::errors::*;
+use crate::secret::Zeroizing;
 
+use std::cell::RefCell;
+
+use rand_core::CryptoRng;
 use ring::error::Unspecified;
 use ring::rand::SecureRandom;
 
@@ -36,15 +40,19 @@ pub(crate) fn random_bytes_count(threshold: u8, message_size: usize) -> usize {
 }
 
 /// Attempts to read `count` random bytes from the given secure random generator.
-pub(crate) fn random_bytes(random: &dyn MySecureRandom, count: usize) -> Result<Vec<u8>> {
+/// The returned bytes are wiped when dropped if the `zeroize` feature is enabled.
+pub(crate) fn random_bytes(
+    random: &dyn MySecureRandom,
+    count: usize,
+) -> Result<Zeroizing<Vec<u8>>> {
     if count == 0 {
-        return Ok(Vec::new());
+        return Ok(Zeroizing::new(Vec::new()));
     }
 
-    let mut rl = vec![0; count];
+    let mut rl = Zeroizing::new(vec![0; count]);
     random
         .fill(&mut rl)
-        .map_err(|_| ErrorKind::CannotGenerateRandomNumbers)?;
+        .map_err(|_| Error::CannotGenerateRandomNumbers)?;
 
     Ok(rl)
 }
@@ -52,7 +60,7 @@ pub(crate) fn random_bytes(random: &dyn MySecureRandom, count: usize) -> Result<
 /// An implementation of SecureRandom that fills the output slice with the slice in `src`.
 /// The length of `src` must be larger than any slice that we attempt to fill.
 pub(crate) struct FixedRandom {
-    src: Vec<u8>,
+    src: Zeroizing<Vec<u8>>,
 }
 
 impl FixedRandom {
@@ -62,7 +70,9 @@ impl FixedRandom {
         if src.is_empty() {
             panic!("The source slice of FixedRandom cannot be empty!");
         }
-        FixedRandom { src }
+        FixedRandom {
+            src: Zeroizing::new(src),
+        }
     }
 }
 
@@ -77,3 +87,25 @@ impl MySecureRandom for FixedRandom {
         Ok(())
     }
 }
+
+/// Adapts a `rand_core` random number generator, such as the one given in
+/// `SplitOptions`, into a `MySecureRandom`.
+pub(crate) struct RngRandom<'a> {
+    rng: RefCell<&'a mut dyn CryptoRng>,
+}
+
+impl<'a> RngRandom<'a> {
+    /// Wraps the given random number generator.
+    pub(crate) fn new(rng: &'a mut dyn CryptoRng) -> Self {
+        RngRandom {
+            rng: RefCell::new(rng),
+        }
+    }
+}
+
+impl MySecureRandom for RngRandom<'_> {
+    fn fill(&self, dst: &mut [u8]) -> std::result::Result<(), Unspecified> {
+        self.rng.borrow_mut().fill_bytes(dst);
+        Ok(())
+    }
+}
@@ -22,6 +22,7 @@
 //! - *New Directions in Secret Sharing* (TODO: Full reference)
 
 use crate::errors::*;
+use crate::secret::Secret;
 
 mod serialize;
 
@@ -29,8 +30,7 @@ mod share;
 pub use self::share::*;
 
 mod scheme;
-pub use self::scheme::Reproducibility;
-use self::scheme::SS1;
+pub use self::scheme::{Reproducibility, SS1};
 
 use crate::dss::AccessStructure;
 
@@ -98,7 +98,7 @@ pub fn split_secret(
 ///     }
 /// }
 /// ```
-pub fn recover_secret(shares: &[Share]) -> Result<(Vec<u8>, AccessStructure, Option<MetaData>)> {
+pub fn recover_secret(shares: &[Share]) -> Result<(Secret, AccessStructure, Option<MetaData>)> {
     SS1::default().recover_secret(shares)
 }
 
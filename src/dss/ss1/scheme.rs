@@ -4,13 +4,16 @@ use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaCha20Rng;
 use ring::digest::{Context, SHA256};
 use ring::hkdf;
-use ring::rand::{SecureRandom, SystemRandom};
+use ring::rand::SystemRandom;
 
 use super::share::*;
-use crate::dss::random::{random_bytes_count, FixedRandom, MAX_MESSAGE_SIZE};
+use crate::dss::random::{
+    random_bytes, random_bytes_count, FixedRandom, MySecureRandom, MAX_MESSAGE_SIZE,
+};
 use crate::dss::thss::{MetaData, ThSS};
 use crate::dss::{thss, AccessStructure};
 use crate::errors::*;
+use crate::secret::{Secret, Zeroizing};
 use crate::share::validation::{validate_share_count, validate_shares};
 use crate::vol_hash::VOLHash;
 
@@ -31,6 +34,7 @@ const DEFAULT_PRESEED: &[u8] = b"rusty_secrets::dss::ss1";
 /// M0 or M1, in a share-reproducible scheme, acquiring a single share
 /// will probably let you decide which of the two possibilities it was.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Reproducibility {
     /// Shares will be produced in a deterministic way, using
     /// a default, fixed seed for the internal random number generator
@@ -42,11 +46,11 @@ pub enum Reproducibility {
     /// Shares will be produced in a deterministic way, using
     /// the given seed for the internal random number generator used to
     /// generate entropy.
-    Seeded(Vec<u8>),
+    Seeded(#[cfg_attr(feature = "serde", serde(with = "crate::serde_base64"))] Vec<u8>),
     /// Shares will be produced in a deterministic way, using
     /// the given byte vector as the entropy source.
     /// *Warning: Never use this variant unless you are sure of what you are doing*
-    WithEntropy(Vec<u8>),
+    WithEntropy(#[cfg_attr(feature = "serde", serde(with = "crate::serde_base64"))] Vec<u8>),
 }
 
 impl Reproducibility {
@@ -87,14 +91,14 @@ impl Reproducibility {
 ///
 /// This scheme is implemented as the *T2 transform* over the ThSS threshold sharing scheme.
 /// found in the `rusty_secrets::dss::thss` module.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) struct SS1 {
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SS1 {
     /// How many random bytes to read from `random` to use as
     /// padding to the hash function (param `r` from the paper)
     /// and to the message in the underlying ThSS scheme.
-    pub random_padding_len: usize,
+    pub(crate) random_padding_len: usize,
     /// The length of the hash used for all shares (param `s` from the paper)
-    pub hash_len: usize,
+    pub(crate) hash_len: usize,
 }
 
 // TODO: Are those good parameters?
@@ -114,10 +118,7 @@ impl SS1 {
     /// Constructs a new sharing scheme
     pub fn new(random_padding_len: usize, hash_len: usize) -> Result<Self> {
         if random_padding_len < MIN_RANDOM_PADDING_LEN || hash_len < MIN_HASH_LEN {
-            bail!(ErrorKind::InvalidSS1Parameters(
-                random_padding_len,
-                hash_len,
-            ));
+            bail!(Error::InvalidSS1Parameters(random_padding_len, hash_len,));
         }
 
         Ok(Self {
@@ -136,18 +137,40 @@ impl SS1 {
         secret: &[u8],
         reproducibility: Reproducibility,
         metadata: &Option<MetaData>,
+    ) -> Result<Vec<Share>> {
+        self.split_secret_with(
+            &SystemRandom::new(),
+            threshold,
+            shares_count,
+            secret,
+            reproducibility,
+            metadata,
+        )
+    }
+
+    /// Split a secret like `split_secret`, drawing the random padding from `random`
+    /// when shares are not meant to be reproducible.
+    pub(crate) fn split_secret_with(
+        &self,
+        random: &dyn MySecureRandom,
+        threshold: u8,
+        shares_count: u8,
+        secret: &[u8],
+        reproducibility: Reproducibility,
+        metadata: &Option<MetaData>,
     ) -> Result<Vec<Share>> {
         let (threshold, shares_count) = validate_share_count(threshold, shares_count)?;
         let secret_len = secret.len();
 
         if secret_len == 0 {
-            bail!(ErrorKind::EmptySecret);
+            bail!(Error::EmptySecret);
         }
         if secret_len > MAX_SECRET_SIZE {
-            bail!(ErrorKind::SecretTooBig(secret_len, MAX_SECRET_SIZE));
+            bail!(Error::SecretTooBig(secret_len, MAX_SECRET_SIZE));
         }
 
-        let random_padding = self.generate_random_padding(reproducibility, secret, metadata)?;
+        let random_padding =
+            self.generate_random_padding(random, reproducibility, secret, metadata)?;
 
         let mut vol_hash = VOLHash::new(&SHA256);
         vol_hash.process(&[0]);
@@ -157,25 +180,25 @@ impl SS1 {
 
         let randomness_len = random_bytes_count(threshold, secret.len() + self.random_padding_len);
         let total_hash_len = self.hash_len + randomness_len;
-        let mut full_hash = vec![0; total_hash_len];
+        let mut full_hash = Zeroizing::new(vec![0; total_hash_len]);
 
         vol_hash.finish(&mut full_hash);
         let (hash, randomness) = full_hash.split_at(self.hash_len);
 
         let underlying = ThSS::new(Box::new(FixedRandom::new(randomness.to_vec())));
 
-        let message = [secret, &random_padding].concat();
+        let message = Zeroizing::new([secret, &random_padding].concat());
         let shares = underlying.split_secret(threshold, shares_count, &message, metadata)?;
 
         let res = shares
             .into_iter()
-            .map(|share| Share {
+            .map(|mut share| Share {
                 id: share.id,
                 threshold: share.threshold,
                 shares_count: share.shares_count,
-                data: share.data,
+                data: std::mem::take(&mut share.data),
                 hash: hash.to_vec(),
-                metadata: share.metadata,
+                metadata: share.metadata.take(),
             })
             .collect();
 
@@ -184,33 +207,28 @@ impl SS1 {
 
     fn generate_random_padding(
         &self,
+        random: &dyn MySecureRandom,
         reproducibility: Reproducibility,
         secret: &[u8],
         metadata: &Option<MetaData>,
-    ) -> Result<Vec<u8>> {
+    ) -> Result<Zeroizing<Vec<u8>>> {
         match reproducibility {
-            Reproducibility::None => {
-                let rng = SystemRandom::new();
-                let mut result = vec![0u8; self.random_padding_len];
-                rng.fill(&mut result)
-                    .map_err(|_| ErrorKind::CannotGenerateRandomNumbers)?;
-                Ok(result)
-            }
+            Reproducibility::None => random_bytes(random, self.random_padding_len),
             Reproducibility::Reproducible => {
                 let seed = self.generate_seed(DEFAULT_PRESEED, secret, metadata);
                 let mut rng = ChaCha20Rng::from_seed(seed);
-                let mut result = vec![0u8; self.random_padding_len];
+                let mut result = Zeroizing::new(vec![0u8; self.random_padding_len]);
                 rng.fill_bytes(result.as_mut_slice());
                 Ok(result)
             }
             Reproducibility::Seeded(preseed) => {
                 let seed = self.generate_seed(&preseed, secret, metadata);
                 let mut rng = ChaCha20Rng::from_seed(seed);
-                let mut result = vec![0u8; self.random_padding_len];
+                let mut result = Zeroizing::new(vec![0u8; self.random_padding_len]);
                 rng.fill_bytes(result.as_mut_slice());
                 Ok(result)
             }
-            Reproducibility::WithEntropy(entropy) => Ok(entropy),
+            Reproducibility::WithEntropy(entropy) => Ok(Zeroizing::new(entropy)),
         }
     }
 
@@ -246,7 +264,7 @@ impl SS1 {
     pub fn recover_secret(
         &self,
         shares: &[Share],
-    ) -> Result<(Vec<u8>, AccessStructure, Option<MetaData>)> {
+    ) -> Result<(Secret, AccessStructure, Option<MetaData>)> {
         let shares = shares.to_vec();
         validate_shares(&shares)?;
 
@@ -306,9 +324,9 @@ impl SS1 {
 
         for (share, test_share) in matching_shares {
             if share != test_share {
-                bail!(ErrorKind::MismatchingShares(
-                    share.clone(),
-                    test_share.clone(),
+                bail!(Error::MismatchingShares(
+                    Box::new(share.clone()),
+                    Box::new(test_share.clone()),
                 ));
             }
         }
@@ -1,17 +1,27 @@
 use super::{MetaData, Share};
-use crate::dss::format::{format_share_protobuf, parse_share_protobuf};
+use crate::dss::format::{parse_share_bytes, parse_share_protobuf, share_envelope};
 use crate::dss::utils::{btreemap_to_hashmap, hashmap_to_btreemap};
 use crate::errors::*;
 use crate::proto::dss::{MetaDataProto, ShareProto};
+use crate::proto::SchemeProto;
+use crate::share::envelope::Envelope;
+use crate::share::version::CURRENT_SHARE_VERSION;
 
-pub(crate) fn share_to_string(share: Share) -> String {
-    let proto = share_to_protobuf(share);
-    format_share_protobuf(&proto)
+pub(crate) fn share_to_envelope(share: &Share) -> Envelope {
+    share_envelope(&share_to_protobuf(share))
 }
 
 pub(crate) fn share_from_string(raw: &str) -> Result<Share> {
     let proto = parse_share_protobuf(raw)?;
+    share_from_protobuf(proto)
+}
+
+pub(crate) fn share_from_bytes(bytes: &[u8]) -> Result<Share> {
+    let proto = parse_share_bytes(bytes, SchemeProto::Ss1)?;
+    share_from_protobuf(proto)
+}
 
+fn share_from_protobuf(proto: ShareProto) -> Result<Share> {
     let metadata_proto = proto.meta_data.map(metadata_from_proto);
 
     let i = proto.id as u8;
@@ -20,7 +30,7 @@ pub(crate) fn share_from_string(raw: &str) -> Result<Share> {
 
     if k < 1 || i < 1 {
         bail! {
-            ErrorKind::ShareParsingError(
+            Error::ShareParsingError(
                 format!("Found illegal share info: threshold = {}, identifier = {}.", k, i),
             )
         }
@@ -28,7 +38,7 @@ pub(crate) fn share_from_string(raw: &str) -> Result<Share> {
 
     if n < 1 || k > n || i > n {
         bail! {
-            ErrorKind::ShareParsingError(
+            Error::ShareParsingError(
                 format!("Found illegal share info: shares_count = {}, threshold = {}, identifier = {}.", n, k, i),
             )
         }
@@ -46,14 +56,16 @@ pub(crate) fn share_from_string(raw: &str) -> Result<Share> {
     Ok(share)
 }
 
-pub(crate) fn share_to_protobuf(share: Share) -> ShareProto {
+pub(crate) fn share_to_protobuf(share: &Share) -> ShareProto {
     ShareProto {
         id: share.id.into(),
         threshold: share.threshold.into(),
         shares_count: share.shares_count.into(),
-        data: share.data,
-        hash: share.hash,
-        meta_data: share.metadata.map(metadata_to_proto),
+        data: share.data.clone(),
+        hash: share.hash.clone(),
+        meta_data: share.metadata.clone().map(metadata_to_proto),
+        version: CURRENT_SHARE_VERSION.into(),
+        scheme: SchemeProto::Ss1.into(),
     }
 }
 
@@ -1,26 +1,39 @@
 use crate::gf256::Gf256;
 use crate::poly::Poly;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Encode the given `secret` using the `ThSS[N].Share` algorithm described
 /// in the *New directions in Secret Sharing* paper.
 ///
 /// Reference: Figure 7 from the *New Directions in Secret Sharing* paper.
 pub(crate) fn encode_secret(secret: &[u8], k: u8, share_id: u8, rands: &[u8]) -> Vec<u8> {
-    secret
-        .iter()
-        .enumerate()
-        .map(|(i, m)| {
-            let k_pred = (k - 1) as usize;
-            let coeffs = (0..k_pred)
-                .map(|l| {
-                    let n = rands[i * k_pred + l];
-                    Gf256::from_byte(n)
-                })
-                .collect();
-            let poly = Poly::new(coeffs);
-            encode_secret_byte(*m, share_id, &poly)
-        })
-        .collect()
+    let encode = |(i, m): (usize, &u8)| {
+        let k_pred = (k - 1) as usize;
+        let coeffs = (0..k_pred)
+            .map(|l| {
+                let n = rands[i * k_pred + l];
+                Gf256::from_byte(n)
+            })
+            .collect();
+        let poly = Poly::new(coeffs);
+        encode_secret_byte(*m, share_id, &poly)
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        secret.iter().enumerate().map(encode).collect()
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        secret
+            .par_iter()
+            .enumerate()
+            .with_min_len(crate::parallel::CHUNK_LEN)
+            .map(encode)
+            .collect()
+    }
 }
 
 /// Encode the given secret byte `m`, by evaluating the given
@@ -13,6 +13,7 @@
 //! **Repro**    | No | Share reproducible: The scheme can produce shares in a deterministic way.
 
 use crate::errors::*;
+use crate::secret::Secret;
 
 mod encode;
 mod serialize;
@@ -21,7 +22,7 @@ mod share;
 pub use self::share::*;
 
 mod scheme;
-pub(crate) use self::scheme::ThSS;
+pub use self::scheme::ThSS;
 
 use crate::dss::AccessStructure;
 
@@ -95,7 +96,7 @@ pub fn split_secret(
 ///     }
 /// }
 /// ```
-pub fn recover_secret(shares: &[Share]) -> Result<(Vec<u8>, AccessStructure, Option<MetaData>)> {
+pub fn recover_secret(shares: &[Share]) -> Result<(Secret, AccessStructure, Option<MetaData>)> {
     ThSS::default().recover_secret(shares)
 }
 
@@ -118,4 +119,57 @@ mod tests {
         assert_eq!(access.shares_count, 10);
         assert_eq!(None, metadata);
     }
+
+    #[test]
+    fn fields_are_taken_out_of_shares() {
+        let mut metadata = MetaData::new();
+        metadata.tags.insert("key".to_owned(), "value".to_owned());
+        let shares = split_secret(2, 3, b"Hello, World!", &Some(metadata.clone())).unwrap();
+
+        let data = shares[0].data.clone();
+        assert_eq!(shares[0].clone().into_data(), data);
+        assert_eq!(shares[0].clone().into_metadata(), Some(metadata));
+    }
+
+    #[test]
+    fn recover_checks_extra_shares_against_their_own_id() {
+        let secret = "Hello, World!".to_string().into_bytes();
+        let shares = split_secret(3, 6, &secret, &None).unwrap();
+
+        let subset = vec![
+            shares[4].clone(),
+            shares[1].clone(),
+            shares[3].clone(),
+            shares[0].clone(),
+            shares[5].clone(),
+        ];
+        let (recovered, _, _) = recover_secret(&subset).unwrap();
+        assert_eq!(secret, recovered);
+    }
+
+    #[test]
+    fn large_secret_roundtrip_and_consistency_check() {
+        // Spans several chunks when the work is spread across threads.
+        let secret = (0..200_003).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+        let rands = (0..secret.len() * 2)
+            .map(|i| (i * 7) as u8)
+            .collect::<Vec<_>>();
+        let encoded = encode::encode_secret(&secret, 3, 4, &rands);
+        for (i, (&e, &m)) in encoded.iter().zip(&secret).enumerate() {
+            let coeffs = vec![
+                crate::gf256::Gf256::from_byte(rands[2 * i]),
+                crate::gf256::Gf256::from_byte(rands[2 * i + 1]),
+            ];
+            let poly = crate::poly::Poly::new(coeffs);
+            assert_eq!(e, encode::encode_secret_byte(m, 4, &poly));
+        }
+
+        let mut shares = split_secret(3, 5, &secret, &None).unwrap();
+        let (recovered, _, _) = recover_secret(&shares).unwrap();
+        assert!(recovered == secret);
+
+        shares[4].data[150_000] ^= 1;
+        assert!(recover_secret(&shares).is_err());
+    }
 }
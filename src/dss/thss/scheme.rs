@@ -7,8 +7,10 @@ use ring::rand::SystemRandom;
 use crate::dss::random::{random_bytes, random_bytes_count, MySecureRandom, MAX_MESSAGE_SIZE};
 use crate::errors::*;
 use crate::gf256::Gf256;
-use crate::lagrange;
+use crate::lagrange::LagrangeBasis;
+use crate::secret::Secret;
 use crate::share::validation::{validate_share_count, validate_shares};
+use crate::simd::linear_combination;
 
 use super::encode::encode_secret;
 use super::share::*;
@@ -20,8 +22,7 @@ use super::AccessStructure;
 const MAX_SECRET_SIZE: usize = MAX_MESSAGE_SIZE;
 
 /// A simple threshold sharing scheme
-#[allow(missing_debug_implementations)]
-pub(crate) struct ThSS {
+pub struct ThSS {
     /// The randomness source
     random: Box<dyn MySecureRandom>,
 }
@@ -40,7 +41,7 @@ impl Default for ThSS {
 
 impl ThSS {
     /// Constructs a new sharing scheme
-    pub fn new(random: Box<dyn MySecureRandom>) -> Self {
+    pub(crate) fn new(random: Box<dyn MySecureRandom>) -> Self {
         Self { random }
     }
 
@@ -53,19 +54,37 @@ impl ThSS {
         shares_count: u8,
         secret: &[u8],
         metadata: &Option<MetaData>,
+    ) -> Result<Vec<Share>> {
+        Self::split_secret_with(
+            self.random.as_ref(),
+            threshold,
+            shares_count,
+            secret,
+            metadata,
+        )
+    }
+
+    /// Split a secret like `split_secret`, using the given randomness source
+    /// instead of the one of this scheme.
+    pub(crate) fn split_secret_with(
+        random: &dyn MySecureRandom,
+        threshold: u8,
+        shares_count: u8,
+        secret: &[u8],
+        metadata: &Option<MetaData>,
     ) -> Result<Vec<Share>> {
         let (threshold, shares_count) = validate_share_count(threshold, shares_count)?;
         let secret_len = secret.len();
 
         if secret_len == 0 {
-            bail!(ErrorKind::EmptySecret);
+            bail!(Error::EmptySecret);
         }
         if secret_len > MAX_SECRET_SIZE {
-            bail!(ErrorKind::SecretTooBig(secret_len, MAX_SECRET_SIZE));
+            bail!(Error::SecretTooBig(secret_len, MAX_SECRET_SIZE));
         }
 
         let rands_len = random_bytes_count(threshold, secret_len);
-        let rands = random_bytes(self.random.as_ref(), rands_len)?;
+        let rands = random_bytes(random, rands_len)?;
 
         let shares = (1..shares_count + 1)
             .map(|id| {
@@ -88,40 +107,36 @@ impl ThSS {
     pub fn recover_secret(
         &self,
         shares: &[Share],
-    ) -> Result<(Vec<u8>, AccessStructure, Option<MetaData>)> {
-        let shares = shares.to_vec();
-        let (threshold, cypher_len) = validate_shares(&shares)?;
-
-        let polys = (0..cypher_len)
-            .map(|i| {
-                let points = shares
-                    .iter()
-                    .take(threshold as usize)
-                    .map(|share| (Gf256::from_byte(share.id), Gf256::from_byte(share.data[i])))
-                    .collect::<Vec<_>>();
-
-                lagrange::interpolate(&points)
-            })
+    ) -> Result<(Secret, AccessStructure, Option<MetaData>)> {
+        let (threshold, cypher_len) = validate_shares(shares)?;
+        let (basis_shares, remaining_shares) = shares.split_at(threshold as usize);
+
+        // The Lagrange basis only depends on the share identifiers, so it is
+        // computed once and then applied to whole shares at a time, instead of
+        // interpolating a polynomial for every byte.
+        let ids = basis_shares
+            .iter()
+            .map(|share| Gf256::from_byte(share.id))
+            .collect::<Vec<_>>();
+        let basis = LagrangeBasis::new(&ids);
+        let rows = basis_shares
+            .iter()
+            .map(|share| &share.data[..])
             .collect::<Vec<_>>();
 
-        for (i, poly) in polys.iter().enumerate() {
-            // Check remaining shares for consistency.
-            // See Figure 7 of the paper
-            let remaining_shares = shares.iter().enumerate().skip(threshold as usize);
-
-            for (u, share) in remaining_shares {
-                let value = poly.evaluate_at(Gf256::from_byte(u as u8 + 1)).to_byte();
-                if value != share.data[i] {
-                    bail!(ErrorKind::InconsistentShares);
-                }
+        // Check remaining shares for consistency.
+        // See Figure 7 of the paper
+        for share in remaining_shares {
+            let weights = basis.at(Gf256::from_byte(share.id));
+            if linear_combination(&rows, &weights, cypher_len) != share.data {
+                bail!(Error::InconsistentShares);
             }
         }
 
+        let weights = basis.at(Gf256::zero());
+        let secret = Secret::new(linear_combination(&rows, &weights, cypher_len));
+
         let metadata = shares[0].metadata.clone();
-        let secret = polys
-            .iter()
-            .map(|p| p.evaluate_at_zero().to_byte())
-            .collect();
 
         let access_structure = AccessStructure {
             threshold,
@@ -1,12 +1,18 @@
-use super::serialize::{share_from_string, share_to_string};
+use super::serialize::{share_from_bytes, share_from_string, share_to_envelope};
+use crate::encoding::TextEncoding;
 use crate::errors::*;
+use crate::share::envelope::Envelope;
 use crate::share::IsShare;
+use crate::{armor, mnemonic};
 
 pub use crate::dss::metadata::MetaData;
 
 /// A share identified by an `id`, a threshold `k`, a number of total shares `n`,
 /// the `data` held in the share, and the share's `metadata`.
+///
+/// When the `zeroize` feature is enabled, the share data is wiped when the share is dropped.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Share {
     /// The identifier of the share (varies between 1 and n where n is the total number of generated shares)
     pub id: u8,
@@ -15,25 +21,85 @@ pub struct Share {
     /// The total number of shares that have been dealt
     pub shares_count: u8,
     /// The share data itself
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_base64"))]
     pub data: Vec<u8>,
     /// The metadata associated with this share
     pub metadata: Option<MetaData>,
 }
 
 impl Share {
+    /// Takes the data out of the share, which cannot be moved out of it otherwise,
+    /// as shares implement `Drop`.
+    ///
+    /// The returned `Vec` will not be wiped when dropped, even if the `zeroize`
+    /// feature is enabled.
+    pub fn into_data(mut self) -> Vec<u8> {
+        core::mem::take(&mut self.data)
+    }
+
+    /// Takes the metadata out of the share, which cannot be moved out of it
+    /// otherwise, as shares implement `Drop`.
+    pub fn into_metadata(mut self) -> Option<MetaData> {
+        self.metadata.take()
+    }
+
     /// Format this share a string suitable for sharing
     /// over an ASCII-encoded channel, such as a text file,
     /// or an e-mail.
     pub fn into_string(self) -> String {
-        share_to_string(self)
+        self.envelope().to_text(TextEncoding::Base64)
+    }
+
+    /// Format this share as `Share::into_string` does, with its data in the given
+    /// encoding rather than in standard base64. `Share::from_string` detects it.
+    pub fn into_string_with(self, encoding: TextEncoding) -> String {
+        self.envelope().to_text(encoding)
+    }
+
+    /// Format this share as a sequence of words, along with a checksum,
+    /// which is easier to write down on paper than `Share::into_string`.
+    pub fn into_mnemonic(self) -> String {
+        mnemonic::encode(&self.envelope())
+    }
+
+    /// Format this share as an ASCII-armored block, along with a checksum,
+    /// and the given identifier of the deal if any.
+    pub fn into_armor(self, deal_id: Option<&str>) -> String {
+        armor::encode(&self.envelope(), deal_id)
     }
 
     /// Parse the given string into a `Share`.
-    /// The `raw` string must have been generated by the
-    /// `Share::to_string` method for it to succeed.
+    /// The `raw` string must have been generated by the `Share::to_string`,
+    /// `Share::into_mnemonic` or `Share::into_armor` methods for it to succeed.
     pub fn from_string(raw: &str) -> Result<Self> {
         share_from_string(raw)
     }
+
+    /// Encode this share into bytes, for storing it without going through strings:
+    /// the magic bytes `RSSH`, the version of the share format, the scheme, the
+    /// threshold and the identifier of the share, followed by its protobuf message.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.envelope().to_bytes()
+    }
+
+    /// Parse the given bytes, generated by `Share::to_bytes`, into a `Share`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        share_from_bytes(bytes)
+    }
+
+    /// The binary encoding of the share, which every format of shares encodes.
+    pub(crate) fn envelope(&self) -> Envelope {
+        share_to_envelope(self)
+    }
+}
+
+// Implemented regardless of the `zeroize` feature, so that enabling it does not
+// forbid moving fields out of the share.
+impl Drop for Share {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut self.data);
+    }
 }
 
 impl IsShare for Share {
@@ -0,0 +1,297 @@
+//! The text encodings of the data of shares in the `K-N-D` format, and how to decode
+//! shares from any of the string formats they are encoded in.
+//!
+//! The data of a share is in standard base64 by default. Other encodings mark the
+//! data with a prefix, so that parsing a share detects its encoding:
+//!
+//! ```text
+//! 2-1-CgkB/P+...              standard base64
+//! 2-1-b64u.CgkB_P-...         URL-safe base64
+//! 2-1-b32.1847ZZR...          Crockford's base32
+//! 2-1-hex.0A0901FCFF...       hexadecimal
+//! ```
+
+use base64::Engine;
+
+use crate::armor;
+use crate::errors::*;
+use crate::mnemonic;
+use crate::reed_solomon;
+use crate::share::envelope::Envelope;
+
+const BASE64_CONFIG: base64::engine::general_purpose::GeneralPurpose =
+    base64::engine::general_purpose::STANDARD_NO_PAD;
+
+const BASE64_URL_CONFIG: base64::engine::general_purpose::GeneralPurpose =
+    base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+/// The alphabet of Crockford's base32, which leaves out `I`, `L`, `O` and `U`.
+const BASE32_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Separates the marker of the encoding from the data.
+const MARKER_SEPARATOR: char = '.';
+
+/// How the data of a share in the `K-N-D` format is encoded.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TextEncoding {
+    /// Standard base64, without padding.
+    #[default]
+    Base64,
+    /// URL-safe base64, without padding, which uses `-` and `_` instead of `+` and
+    /// `/`. Marked with `b64u.`.
+    Base64Url,
+    /// Crockford's base32, in upper case, which can be read back regardless of its
+    /// case and of the confusion of `O` with `0` or of `I` and `L` with `1`.
+    /// Marked with `b32.`.
+    Base32,
+    /// Hexadecimal, in upper case. Marked with `hex.`.
+    Hex,
+}
+
+impl TextEncoding {
+    /// The prefix marking data in this encoding.
+    fn marker(self) -> &'static str {
+        match self {
+            TextEncoding::Base64 => "",
+            TextEncoding::Base64Url => "b64u",
+            TextEncoding::Base32 => "b32",
+            TextEncoding::Hex => "hex",
+        }
+    }
+
+    /// Encodes the given data, along with the marker of this encoding.
+    pub(crate) fn encode(self, data: &[u8]) -> String {
+        let encoded = match self {
+            TextEncoding::Base64 => return BASE64_CONFIG.encode(data),
+            TextEncoding::Base64Url => BASE64_URL_CONFIG.encode(data),
+            TextEncoding::Base32 => to_base32(data),
+            TextEncoding::Hex => data.iter().map(|byte| format!("{:02X}", byte)).collect(),
+        };
+        format!("{}{}{}", self.marker(), MARKER_SEPARATOR, encoded)
+    }
+
+    /// Tells the encoding of the given data from its marker, and strips the marker.
+    pub(crate) fn detect(raw: &str) -> Result<(TextEncoding, &str)> {
+        match raw.split_once(MARKER_SEPARATOR) {
+            None => Ok((TextEncoding::Base64, raw)),
+            Some((marker, data)) => [
+                TextEncoding::Base64Url,
+                TextEncoding::Base32,
+                TextEncoding::Hex,
+            ]
+            .into_iter()
+            .find(|encoding| encoding.marker().eq_ignore_ascii_case(marker))
+            .map(|encoding| (encoding, data))
+            .ok_or_else(|| {
+                Error::ShareParsingError(format!("Unknown share encoding: {}.", marker))
+            }),
+        }
+    }
+
+    /// Decodes data in any encoding, as told by its marker. Errors tell where the data
+    /// is invalid without quoting it, since it is part of a share.
+    pub(crate) fn decode(raw: &str) -> Result<Vec<u8>> {
+        let (encoding, data) = TextEncoding::detect(raw)?;
+        match encoding {
+            TextEncoding::Base64 => BASE64_CONFIG
+                .decode(data)
+                .map_err(Error::ShareParsingErrorBase64),
+            TextEncoding::Base64Url => BASE64_URL_CONFIG
+                .decode(data)
+                .map_err(Error::ShareParsingErrorBase64),
+            TextEncoding::Base32 => from_base32(data),
+            TextEncoding::Hex => from_hex(data),
+        }
+    }
+}
+
+/// Splits a share in the `K-N-D` format into its three parts. The data may hold
+/// dashes, in URL-safe base64.
+pub(crate) fn split_parts(raw: &str) -> Result<(&str, &str, &str)> {
+    let mut parts = raw.trim().splitn(SSS_SHARE_PARTS_COUNT, '-');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(k), Some(n), Some(data)) => Ok((k, n, data)),
+        _ => bail!(Error::ShareParsingError(format!(
+            "Expected 3 parts separated by a minus sign. Found {}.",
+            raw
+        ))),
+    }
+}
+
+/// Decodes a share in any format into its binary encoding.
+pub(crate) fn decode(raw: &str) -> Result<Envelope> {
+    if armor::is_armored(raw) {
+        return armor::decode(raw);
+    }
+    if reed_solomon::is_reed_solomon(raw) {
+        let (envelope, _) = reed_solomon::decode(raw)?;
+        return Ok(envelope);
+    }
+    if mnemonic::is_mnemonic(raw) {
+        return mnemonic::decode(raw);
+    }
+    Envelope::from_text(raw)
+}
+
+/// Encodes a share in the `K-N-D` format.
+pub(crate) fn to_text(threshold: u8, id: u8, data: &[u8], encoding: TextEncoding) -> String {
+    format!("{}-{}-{}", threshold, id, encoding.encode(data))
+}
+
+fn to_base32(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(5) * 8);
+    let (mut acc, mut acc_bits) = (0u16, 0);
+    for &byte in data {
+        acc = (acc << 8) | u16::from(byte);
+        acc_bits += 8;
+        while acc_bits >= 5 {
+            acc_bits -= 5;
+            encoded.push(char::from(
+                BASE32_ALPHABET[usize::from((acc >> acc_bits) & 0x1F)],
+            ));
+        }
+        acc &= (1 << acc_bits) - 1;
+    }
+    if acc_bits > 0 {
+        encoded.push(char::from(
+            BASE32_ALPHABET[usize::from((acc << (5 - acc_bits)) & 0x1F)],
+        ));
+    }
+    encoded
+}
+
+fn from_base32(encoded: &str) -> Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut acc, mut acc_bits) = (0u16, 0);
+    for (position, c) in encoded.chars().enumerate() {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&symbol| char::from(symbol) == c)
+            .ok_or_else(|| {
+                Error::ShareParsingError(format!(
+                    "Invalid base32 character at position {}.",
+                    position + 1
+                ))
+            })?;
+        // The alphabet has 32 symbols.
+        acc = (acc << 5) | value as u16;
+        acc_bits += 5;
+        if acc_bits >= 8 {
+            acc_bits -= 8;
+            data.push((acc >> acc_bits) as u8);
+            acc &= (1 << acc_bits) - 1;
+        }
+    }
+    if acc_bits >= 5 || acc != 0 {
+        bail!(Error::ShareParsingError(
+            "Invalid trailing base32 characters.".to_owned()
+        ));
+    }
+    Ok(data)
+}
+
+fn from_hex(encoded: &str) -> Result<Vec<u8>> {
+    if let Some(position) = encoded.bytes().position(|c| !c.is_ascii_hexdigit()) {
+        bail!(Error::ShareParsingError(format!(
+            "Invalid hexadecimal character at position {}.",
+            position + 1
+        )));
+    }
+    if !encoded.len().is_multiple_of(2) {
+        bail!(Error::ShareParsingError(format!(
+            "Hexadecimal data of odd length {}.",
+            encoded.len()
+        )));
+    }
+    Ok((0..encoded.len())
+        .step_by(2)
+        // The digits were checked above.
+        .map(|i| u8::from_str_radix(&encoded[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const ENCODINGS: [TextEncoding; 4] = [
+        TextEncoding::Base64,
+        TextEncoding::Base64Url,
+        TextEncoding::Base32,
+        TextEncoding::Hex,
+    ];
+
+    #[test]
+    fn encode_then_decode() {
+        for len in 0..20 {
+            let data = (0..len).map(|i| (i * 37 + 250) as u8).collect::<Vec<_>>();
+            for encoding in ENCODINGS {
+                let encoded = encoding.encode(&data);
+                assert_eq!(TextEncoding::decode(&encoded).unwrap(), data);
+                let envelope = decode(&to_text(3, 2, &data, encoding)).unwrap();
+                assert_eq!((envelope.threshold, envelope.id), (3, 2));
+                assert_eq!(envelope.body, data);
+            }
+        }
+    }
+
+    #[test]
+    fn known_encodings() {
+        let data = [0xFB, 0xFF, 0x00, 0x10];
+        assert_eq!(TextEncoding::Base64.encode(&data), "+/8AEA");
+        assert_eq!(TextEncoding::Base64Url.encode(&data), "b64u.-_8AEA");
+        assert_eq!(TextEncoding::Hex.encode(&data), "hex.FBFF0010");
+        assert_eq!(TextEncoding::Base32.encode(&data), "b32.ZFZG040");
+        assert_eq!(to_base32(b"foobar"), "CSQPYRK1E8");
+    }
+
+    #[test]
+    fn lenient_decoding() {
+        assert_eq!(TextEncoding::decode("HEX.fbff").unwrap(), [0xFB, 0xFF]);
+        assert_eq!(
+            TextEncoding::decode("b32.csqpyrkie8").unwrap(),
+            b"foobar".to_vec()
+        );
+        assert_eq!(
+            TextEncoding::decode("b32.CSQPYRKLE8").unwrap(),
+            b"foobar".to_vec()
+        );
+        assert_eq!(TextEncoding::decode("b32.C0").unwrap(), [0x60]);
+        assert_eq!(TextEncoding::decode("b32.CO").unwrap(), [0x60]);
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(TextEncoding::decode("hex.FBF").is_err());
+        assert!(TextEncoding::decode("hex.FBFG").is_err());
+        assert!(TextEncoding::decode("b32.CU").is_err());
+        // Non-zero trailing bits, and a dangling character.
+        assert!(TextEncoding::decode("b32.C1").is_err());
+        assert!(TextEncoding::decode("b32.C00").is_err());
+        assert!(TextEncoding::decode("b58.ABC").is_err());
+        assert_eq!(
+            TextEncoding::detect("B64U.-_8").unwrap(),
+            (TextEncoding::Base64Url, "-_8")
+        );
+        assert!(TextEncoding::decode("+/8AEA").is_ok());
+        assert!(TextEncoding::decode("-_8AEA").is_err());
+        match TextEncoding::decode("hex.FBFG") {
+            Err(Error::ShareParsingError(reason)) => {
+                assert_eq!(reason, "Invalid hexadecimal character at position 4.")
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        assert!(split_parts("2-1").is_err());
+        assert_eq!(
+            split_parts("2-1-b64u.-_8-").unwrap(),
+            ("2", "1", "b64u.-_8-")
+        );
+    }
+}
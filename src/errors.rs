@@ -1,9 +1,38 @@
-//! Define the various error kinds specific to deterministic secret sharing.
-
-#![allow(unknown_lints, missing_docs, unused_doc_comments)]
+//! The error type shared by every scheme and share format of this crate.
+//!
+//! Every fallible function of this crate returns an [`Error`], an enum which can be
+//! matched on directly. It is `Send + Sync + 'static`, and chains the errors it wraps
+//! through [`source()`](core::error::Error::source).
+//!
+//! # Migrating from `error-chain`
+//!
+//! Earlier versions of this crate generated their errors with `error-chain`.
+//! The variants are the same, so that migrating mostly amounts to:
+//!
+//! * matching on the error itself rather than on `error.kind()`, and replacing
+//!   `ErrorKind::` with `Error::` in patterns, both of which are still accepted for
+//!   now, but deprecated;
+//! * adding a wildcard arm to such matches, since `Error` is `#[non_exhaustive]`;
+//! * replacing `error.iter()` and `error.backtrace()` with `source()`, as `Error`
+//!   neither records a backtrace nor exposes a `ResultExt` trait.
+//!
+//! A few variants now carry the error which caused them, along with the payloads
+//! below which changed:
+//!
+//! * base64 and protobuf decoding errors of shares, previously reported as
+//!   `ShareParsingError` with a message, are now `ShareParsingErrorBase64` and
+//!   `ShareParsingErrorProtobuf`;
+//! * `SecretDeserializationError` holds the protobuf decoding error;
+//! * `MismatchingShares` boxes the shares it holds.
 
+#[cfg(feature = "dss")]
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::collections::HashSet;
-use std::fmt;
 
 #[cfg(feature = "dss")]
 use crate::dss::ss1;
@@ -15,135 +44,230 @@ pub(crate) static MIN_THRESHOLD: u8 = 2;
 /// Maximum allowed number of shares (k,n)
 pub(crate) static MAX_SHARES: u8 = 255;
 /// SSS Shares should be structured as k-n-data hence 3 parts
+#[cfg(feature = "std")]
 pub(crate) static SSS_SHARE_PARTS_COUNT: usize = 3;
 
-// Create the Error, ErrorKind, ResultExt, and Result types
-error_chain! {
-    errors {
-        ThresholdTooBig(k: u8, n: u8) {
-            description("Threshold k must be smaller than or equal to n")
-            display("Threshold k must be smaller than or equal to n, got: k = {}, n = {}.", k, n)
-        }
-
-        ThresholdTooSmall(k: u8) {
-            description("Threshold k must be bigger than or equal to 2")
-            display("Threshold k must be bigger than or equal to 2, got: k = {}", k)
-        }
-
-        SecretTooBig(len: usize, max: usize) {
-            description("The secret is too long")
-            display("The secret is too long, maximum allowed size = {} bytes, got {} bytes", max, len)
-        }
-
-        InvalidShareCountMax(nb_shares: u8, max: u8) {
-            description("Number of shares is too big")
-            display("Number of shares must be smaller than or equal {}, got: {} shares.", max, nb_shares)
-        }
-
-        InvalidShareCountMin(nb_shares: u8, min: u8) {
-            description("Number of shares is too small")
-            display("Number of shares must be larger than or equal {}, got: {} shares.", min, nb_shares)
-        }
-
-        EmptySecret {
-            description("The secret cannot be empty")
-            display("The secret cannot be empty")
-        }
-
-        EmptyShares {
-            description("No shares provided")
-            display("No shares were provided.")
-        }
-
-        IncompatibleSets(sets: Vec<HashSet<u8>>) {
-            description("The shares are incompatible with each other.")
-            display("The shares are incompatible with each other.")
-        }
-
-        MissingShares(provided: usize, required: u8) {
-            description("The number of shares provided is insufficient to recover the secret.")
-            display("{} shares are required to recover the secret, found only {}.", required, provided)
-        }
-
-        InvalidSignature(share_id: u8, signature: String) {
-            description("The signature of this share is not valid.")
-        }
-
-        MissingSignature(share_id: u8) {
-            description("Signature is missing while shares are required to be signed.")
-        }
-
-        SecretDeserializationError {
-            description("An issue was encountered deserializing the secret. \
-                         Updating to the latest version of RustySecrets might help fix this.")
-        }
-
-        ShareParsingError(reason: String) {
-            description("This share is incorrectly formatted.")
-            display("This share is incorrectly formatted. Reason: {}", reason)
-        }
-
-        ShareParsingErrorEmptyShare(share_id: u8) {
-            description("This share is empty.")
-            display("Found empty share for share identifier ({})", share_id)
-        }
-
-        ShareParsingInvalidShareId(share_id: u8) {
-            description("Invalid share identifier.")
-            display("Found invalid share identifier ({})", share_id)
-        }
-
-        ShareParsingInvalidShareThreshold(k: u8, id: u8) {
-            description("Threshold k must be bigger than or equal to 2")
-            display("Threshold k must be bigger than or equal to 2. Got k = {} for share identifier {}.", k, id)
-        }
-
-        InvalidSS1Parameters(r: usize, s: usize) {
-            description("Invalid parameters for the SS1 sharing scheme")
-            display("Invalid parameters for the SS1 sharing scheme: r = {}, s = {}.", r, s)
-        }
-
-        InvalidSplitParametersZero(k: u8, n: u8) {
-            description("Parameters k and n must be greater than zero")
-            display("Parameters k and n must be greater than zero.")
-        }
-
-        #[cfg(feature = "dss")]
-        MismatchingShares(got: ss1::Share, expected: ss1::Share) {
-            description("Share mismatch during verification of secret recovery")
-            display("Share mismatch during verification of secret recovery.")
-        }
+/// The errors which can occur while splitting or recovering secrets.
+///
+/// Some variants are only available with the `std` or `dss` features.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The threshold `k` is bigger than the number of shares `n`.
+    ThresholdTooBig(u8, u8),
+    /// The threshold `k` is smaller than 2.
+    ThresholdTooSmall(u8),
+    /// The secret is longer than the maximum allowed size, given second.
+    SecretTooBig(usize, usize),
+    /// The number of shares is bigger than the maximum, given second.
+    InvalidShareCountMax(u8, u8),
+    /// The number of shares is smaller than the minimum, given second.
+    InvalidShareCountMin(u8, u8),
+    /// The secret is empty.
+    EmptySecret,
+    /// No shares were provided.
+    EmptyShares,
+    /// The shares were signed with different keys, grouped by the identifiers of the
+    /// shares sharing a key.
+    #[cfg(feature = "std")]
+    IncompatibleSets(Vec<HashSet<u8>>),
+    /// Fewer shares than the threshold, given second, were provided.
+    MissingShares(usize, u8),
+    /// The signature of the share with the given identifier is invalid.
+    InvalidSignature(u8, String),
+    /// The share with the given identifier is not signed, while signatures are required.
+    MissingSignature(u8),
+    /// The recovered secret could not be deserialized.
+    #[cfg(feature = "std")]
+    SecretDeserializationError(prost::DecodeError),
+    /// A share is incorrectly formatted, for the given reason.
+    ShareParsingError(String),
+    /// The data of the share with the given identifier is empty.
+    ShareParsingErrorEmptyShare(u8),
+    /// The data of a share is not valid base64.
+    #[cfg(feature = "std")]
+    ShareParsingErrorBase64(base64::DecodeError),
+    /// The data of a share is not a valid protobuf message.
+    #[cfg(feature = "std")]
+    ShareParsingErrorProtobuf(prost::DecodeError),
+    /// A share has an invalid identifier.
+    ShareParsingInvalidShareId(u8),
+    /// The share with the given identifier, given second, has a threshold smaller than 2.
+    ShareParsingInvalidShareThreshold(u8, u8),
+    /// The parameters of the `SS1` scheme are invalid.
+    InvalidSS1Parameters(usize, usize),
+    /// The threshold or the number of shares is zero.
+    InvalidSplitParametersZero(u8, u8),
+    /// Splitting the recovered secret again did not yield the share it was recovered from,
+    /// given first, but the one given second.
+    #[cfg(feature = "dss")]
+    MismatchingShares(Box<ss1::Share>, Box<ss1::Share>),
+    /// The random number generator failed.
+    CannotGenerateRandomNumbers,
+    /// Several shares have the same identifier.
+    DuplicateShareId(u8),
+    /// The share with the given identifier has a different secret length than the others.
+    InconsistentSecretLengths(u8, usize, Vec<u8>, usize),
+    /// The shares do not agree on the secret.
+    InconsistentShares,
+    /// The share with the given identifier has a different threshold than the others.
+    InconsistentThresholds(u8, u8, Vec<u8>, u8),
+    /// Signing shares and verifying their signatures requires the `std` feature.
+    SignaturesUnsupported,
+    /// The scheme does not support the given option, see `scheme::SplitOptions`.
+    UnsupportedOption(&'static str),
+    /// The shares were not all dealt by the same scheme.
+    InconsistentSchemes,
+    /// The word at the given position, counting from 1, of a share encoded as words
+    /// is not in the word list.
+    UnknownMnemonicWord(usize, String),
+    /// The checksum of a share encoded as words does not match its content.
+    InvalidMnemonicChecksum,
+    /// SLIP-39 master secrets must be at least 16 bytes long, and of even length.
+    InvalidMasterSecretLength(usize),
+    /// The SLIP-39 iteration exponent must be at most 15.
+    InvalidIterationExponent(u8),
+    /// SLIP-39 passphrases may only contain printable ASCII characters.
+    InvalidPassphrase,
+    /// The given field of a SLIP-39 share is out of the range its mnemonic encodes.
+    InvalidShareField(&'static str),
+    /// The security level of `ssss` shares, in bits, must be a multiple of 8 between 8
+    /// and 1024.
+    InvalidSecurityLevel(usize),
+    /// The share was written in the given version of the share format, which is newer
+    /// than the ones this release reads.
+    UnsupportedShareVersion(i32),
+    /// The checksum of an armored share does not match its body: holds the checksum
+    /// computed from the body, then the one written after it.
+    InvalidArmorChecksum(u32, u32),
+    /// The share is too long to be encoded this way: holds its length and the
+    /// maximum length, in bytes.
+    ShareTooBig(usize, usize),
+    /// The number of corrupted characters a share with error correction corrects must
+    /// be between 1 and `reed_solomon::MAX_CORRECTIONS`.
+    #[cfg(feature = "std")]
+    InvalidCorrectionCount(u8),
+    /// The share with error correction has more corrupted characters than it can
+    /// correct.
+    UncorrectableShare,
+    /// The MIME type of a wrapped secret is not of the form `type/subtype`, with
+    /// optional parameters.
+    InvalidMimeType(String),
+    /// More shares than the threshold, given second, were provided, where exactly as many
+    /// are expected.
+    TooManyShares(usize, u8),
+    /// The wrapped secret was written in the given version of the secret format, which
+    /// is newer than the ones this release reads.
+    UnsupportedSecretVersion(i32),
+    /// An I/O error occurred.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// A number could not be parsed.
+    IntegerParsingError(core::num::ParseIntError),
+}
 
-        CannotGenerateRandomNumbers {
-            description("Cannot generate random numbers")
-            display("Cannot generate random numbers.")
-        }
+/// Former name of [`Error`], whose variants used to be wrapped in an `error-chain` error.
+#[deprecated(note = "match on `Error` directly")]
+pub type ErrorKind = Error;
 
-        DuplicateShareId(share_id: u8) {
-            description("This share number has already been used by a previous share.")
-            display("This share number ({}) has already been used by a previous share.", share_id)
-        }
+/// Result type of the fallible functions of this crate.
+pub type Result<T> = core::result::Result<T, Error>;
 
-        InconsistentSecretLengths(id: u8, slen_: usize, ids: Vec<u8>, slen: usize) {
-            description("The shares are incompatible with each other because they do not all have the same secret length.")
-            display("The share identifier {} had secret length {}, while the secret length {} was found for share identifier(s): {}.", id, slen_, slen, no_more_than_five(ids))
-        }
+impl Error {
+    /// Returns the error itself, as the kind of the error used to be wrapped.
+    #[deprecated(note = "match on the error directly")]
+    pub fn kind(&self) -> &Error {
+        self
+    }
+}
 
-        InconsistentShares {
-            description("The shares are inconsistent")
-            display("The shares are inconsistent")
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ThresholdTooBig(k, n) => write!(f, "Threshold k must be smaller than or equal to n, got: k = {}, n = {}.", k, n),
+            Error::ThresholdTooSmall(k) => write!(f, "Threshold k must be bigger than or equal to 2, got: k = {}", k),
+            Error::SecretTooBig(len, max) => write!(f, "The secret is too long, maximum allowed size = {} bytes, got {} bytes", max, len),
+            Error::InvalidShareCountMax(nb_shares, max) => write!(f, "Number of shares must be smaller than or equal {}, got: {} shares.", max, nb_shares),
+            Error::InvalidShareCountMin(nb_shares, min) => write!(f, "Number of shares must be larger than or equal {}, got: {} shares.", min, nb_shares),
+            Error::EmptySecret => write!(f, "The secret cannot be empty"),
+            Error::EmptyShares => write!(f, "No shares were provided."),
+            #[cfg(feature = "std")]
+            Error::IncompatibleSets(_) => write!(f, "The shares are incompatible with each other."),
+            Error::MissingShares(provided, required) => write!(f, "{} shares are required to recover the secret, found only {}.", required, provided),
+            Error::InvalidSignature(share_id, signature) => write!(f, "The signature of share {} is not valid: {}", share_id, signature),
+            Error::MissingSignature(share_id) => write!(f, "Signature of share {} is missing while shares are required to be signed.", share_id),
+            #[cfg(feature = "std")]
+            Error::SecretDeserializationError(_) => write!(f, "An issue was encountered deserializing the secret. Updating to the latest version of RustySecrets might help fix this."),
+            Error::ShareParsingError(reason) => write!(f, "This share is incorrectly formatted. Reason: {}", reason),
+            Error::ShareParsingErrorEmptyShare(share_id) => write!(f, "Found empty share for share identifier ({})", share_id),
+            #[cfg(feature = "std")]
+            Error::ShareParsingErrorBase64(_) => write!(f, "This share is incorrectly formatted. Reason: Base64 decoding of data block failed"),
+            #[cfg(feature = "std")]
+            Error::ShareParsingErrorProtobuf(_) => write!(f, "This share is incorrectly formatted. Reason: Protobuf decoding of data block failed"),
+            Error::ShareParsingInvalidShareId(share_id) => write!(f, "Found invalid share identifier ({})", share_id),
+            Error::ShareParsingInvalidShareThreshold(k, id) => write!(f, "Threshold k must be bigger than or equal to 2. Got k = {} for share identifier {}.", k, id),
+            Error::InvalidSS1Parameters(r, s) => write!(f, "Invalid parameters for the SS1 sharing scheme: r = {}, s = {}.", r, s),
+            Error::InvalidSplitParametersZero(..) => write!(f, "Parameters k and n must be greater than zero."),
+            #[cfg(feature = "dss")]
+            Error::MismatchingShares(..) => write!(f, "Share mismatch during verification of secret recovery."),
+            Error::CannotGenerateRandomNumbers => write!(f, "Cannot generate random numbers."),
+            Error::DuplicateShareId(share_id) => write!(f, "This share number ({}) has already been used by a previous share.", share_id),
+            Error::InconsistentSecretLengths(id, slen_, ids, slen) => write!(f, "The share identifier {} had secret length {}, while the secret length {} was found for share identifier(s): {}.", id, slen_, slen, no_more_than_five(ids)),
+            Error::InconsistentShares => write!(f, "The shares are inconsistent"),
+            Error::InconsistentThresholds(id, k_, ids, k) => write!(f, "The share identifier {} had k = {}, while k = {} was found for share identifier(s): {}.", id, k_, k, no_more_than_five(ids)),
+            Error::SignaturesUnsupported => write!(f, "Signing shares and verifying their signatures requires the `std` feature."),
+            Error::UnsupportedOption(option) => write!(f, "The {} option is not supported by this scheme.", option),
+            Error::InconsistentSchemes => write!(f, "The shares were not all dealt by the same scheme."),
+            Error::UnknownMnemonicWord(position, word) => write!(f, "The word {} at position {} is not in the word list.", word, position),
+            Error::InvalidMnemonicChecksum => write!(f, "The checksum of the share does not match. It was most likely mistyped."),
+            Error::InvalidMasterSecretLength(len) => write!(f, "The master secret must be at least 16 bytes long, and of even length, got {} bytes.", len),
+            Error::InvalidIterationExponent(exponent) => write!(f, "The iteration exponent must be at most 15, got {}.", exponent),
+            Error::InvalidPassphrase => write!(f, "The passphrase must only contain printable ASCII characters."),
+            Error::InvalidShareField(field) => write!(f, "The {} of the share is out of range.", field),
+            Error::InvalidSecurityLevel(bits) => write!(f, "The security level must be a multiple of 8 between 8 and 1024 bits, got {} bits.", bits),
+            Error::UnsupportedShareVersion(version) => write!(f, "The share was written in version {} of the share format, which is not supported. Updating to the latest version of RustySecrets might help fix this.", version),
+            Error::InvalidArmorChecksum(computed, expected) => write!(f, "The checksum of the armored share is 0x{:06X}, while its body sums to 0x{:06X}. The share was probably mistyped.", expected, computed),
+            #[cfg(feature = "std")]
+            Error::InvalidCorrectionCount(count) => write!(f, "A share with error correction corrects between 1 and {} corrupted characters, got {}.", crate::reed_solomon::MAX_CORRECTIONS, count),
+            Error::UncorrectableShare => write!(f, "The share has too many corrupted characters to be corrected."),
+            Error::InvalidMimeType(mime_type) => write!(f, "Invalid MIME type: `{}`. Expected a type and a subtype, such as `text/plain`.", mime_type),
+            Error::ShareTooBig(len, max) => write!(f, "The share is too long to be encoded this way, maximum allowed size = {} bytes, got {} bytes.", max, len),
+            Error::TooManyShares(provided, required) => write!(f, "Exactly {} shares are required to recover the secret, found {}.", required, provided),
+            Error::UnsupportedSecretVersion(version) => write!(f, "The secret was wrapped in version {} of the secret format, which is not supported. Updating to the latest version of RustySecrets might help fix this.", version),
+            #[cfg(feature = "std")]
+            Error::Io(err) => write!(f, "{}", err),
+            Error::IntegerParsingError(err) => write!(f, "{}", err),
         }
+    }
+}
 
-        InconsistentThresholds(id: u8, k_: u8, ids: Vec<u8>, k: u8) {
-            description("The shares are incompatible with each other because they do not all have the same threshold.")
-            display("The share identifier {} had k = {}, while k = {} was found for share identifier(s): {}.", id, k_, k, no_more_than_five(ids))
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Error::SecretDeserializationError(err) | Error::ShareParsingErrorProtobuf(err) => {
+                Some(err)
+            }
+            #[cfg(feature = "std")]
+            Error::ShareParsingErrorBase64(err) => Some(err),
+            #[cfg(feature = "std")]
+            Error::Io(err) => Some(err),
+            Error::IntegerParsingError(err) => Some(err),
+            _ => None,
         }
+    }
+}
 
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
     }
+}
 
-    foreign_links {
-        Io(::std::io::Error);
-        IntegerParsingError(::std::num::ParseIntError);
+impl From<core::num::ParseIntError> for Error {
+    fn from(err: core::num::ParseIntError) -> Self {
+        Error::IntegerParsingError(err)
     }
 }
 
@@ -162,3 +286,47 @@ fn no_more_than_five<T: fmt::Debug + fmt::Display>(vec: &[T]) -> String {
         format!("{:?}", vec)
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use core::error::Error as _;
+
+    fn assert_send_sync_static<T: Send + Sync + 'static>() {}
+
+    #[test]
+    fn error_is_send_sync_static() {
+        assert_send_sync_static::<Error>();
+    }
+
+    #[test]
+    fn parse_int_error_is_chained() {
+        let err = Error::from("x".parse::<u8>().unwrap_err());
+        assert!(matches!(err, Error::IntegerParsingError(_)));
+        assert!(err.source().is_some());
+        assert!(Error::EmptyShares.source().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn decoding_errors_are_chained() {
+        use base64::Engine;
+
+        let base64_err = base64::engine::general_purpose::STANDARD
+            .decode("(((")
+            .unwrap_err();
+        let err = Error::ShareParsingErrorBase64(base64_err.clone());
+        assert_eq!(err.source().unwrap().to_string(), base64_err.to_string());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn error_kind_is_the_error_itself() {
+        let err = Error::MissingShares(1, 2);
+        match *err.kind() {
+            ErrorKind::MissingShares(1, 2) => {}
+            ref err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,469 @@
+//! The finite fields the Lagrange interpolation of `lagrange` works in.
+//!
+//! Shares dealt by this crate live in `Gf256`. Other implementations of Shamir's
+//! secret sharing, which some modules interoperate with, use other fields.
+
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, Sub, SubAssign};
+
+use crate::gf256::Gf256;
+
+/// A finite field of characteristic 2, whose elements can be interpolated.
+pub(crate) trait Field:
+    Copy
+    + PartialEq
+    + Add<Output = Self>
+    + AddAssign
+    + Sub<Output = Self>
+    + SubAssign
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + DivAssign
+{
+    /// The additive neutral element of the field.
+    fn zero() -> Self;
+
+    /// The multiplicative neutral element of the field.
+    fn one() -> Self;
+}
+
+impl Field for Gf256 {
+    #[inline]
+    fn zero() -> Self {
+        Gf256::zero()
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Gf256::one()
+    }
+}
+
+/// An element of the field with 256 elements defined by the irreducible polynomial
+/// x^8 + x^4 + x^3 + x + 1, which AES and SLIP-39 use.
+///
+/// Unlike `Gf256`, multiplication and division never go through tables.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Rijndael(pub(crate) u8);
+
+#[cfg(feature = "std")]
+impl Rijndael {
+    /// The irreducible polynomial defining the field, without its leading term.
+    const POLY: u8 = 0x1B;
+
+    /// Multiplies a polynomial with x, modulo the irreducible polynomial.
+    #[inline]
+    fn xtimes(self) -> Self {
+        let mask = 0u8.wrapping_sub(self.0 >> 7);
+        Rijndael((self.0 << 1) ^ (mask & Self::POLY))
+    }
+
+    /// Computes the multiplicative inverse as `self^254`. The inverse of zero is zero.
+    fn inv(self) -> Self {
+        let mut square = self * self;
+        let mut acc = square;
+        for _ in 0..6 {
+            square = square * square;
+            acc = acc * square;
+        }
+        acc
+    }
+}
+
+#[cfg(feature = "std")]
+impl Field for Rijndael {
+    #[inline]
+    fn zero() -> Self {
+        Rijndael(0)
+    }
+
+    #[inline]
+    fn one() -> Self {
+        Rijndael(1)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Add for Rijndael {
+    type Output = Rijndael;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Rijndael) -> Rijndael {
+        Rijndael(self.0 ^ rhs.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl AddAssign for Rijndael {
+    #[inline]
+    fn add_assign(&mut self, rhs: Rijndael) {
+        *self = *self + rhs;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Sub for Rijndael {
+    type Output = Rijndael;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Rijndael) -> Rijndael {
+        Rijndael(self.0 ^ rhs.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl SubAssign for Rijndael {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Rijndael) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Mul for Rijndael {
+    type Output = Rijndael;
+
+    /// Goes through the eight steps of a carry-less multiplication, whatever the operands.
+    #[inline]
+    fn mul(self, rhs: Rijndael) -> Rijndael {
+        let mut a = self;
+        let mut acc = 0u8;
+        for i in 0..8 {
+            acc ^= 0u8.wrapping_sub((rhs.0 >> i) & 1) & a.0;
+            a = a.xtimes();
+        }
+        Rijndael(acc)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Div for Rijndael {
+    type Output = Rijndael;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Rijndael) -> Rijndael {
+        assert_ne!(rhs.0, 0, "division by zero");
+        self * rhs.inv()
+    }
+}
+
+#[cfg(feature = "std")]
+impl DivAssign for Rijndael {
+    #[inline]
+    fn div_assign(&mut self, rhs: Rijndael) {
+        *self = *self / rhs;
+    }
+}
+
+/// The number of 64-bit limbs of the elements of `Gf2n`, enough to hold the
+/// irreducible polynomial of the largest field.
+#[cfg(feature = "std")]
+const GF2N_LIMBS: usize = Gf2n::MAX_DEGREE / 64 + 1;
+
+/// The exponents of the middle terms of the irreducible polynomials
+/// x^n + x^a + x^b + x^c + 1 defining the fields of `Gf2n`, for n = 8, 16, ..., 1024.
+/// These are the polynomials used by `ssss`.
+#[cfg(feature = "std")]
+#[rustfmt::skip]
+const GF2N_PENTANOMIALS: [[u16; 3]; Gf2n::MAX_DEGREE / 8] = [
+    [4, 3, 1], [5, 3, 1], [4, 3, 1], [7, 3, 2],
+    [5, 4, 3], [5, 3, 2], [7, 4, 2], [4, 3, 1],
+    [10, 9, 3], [9, 4, 2], [7, 6, 2], [10, 9, 6],
+    [4, 3, 1], [5, 4, 3], [4, 3, 1], [7, 2, 1],
+    [5, 3, 2], [7, 4, 2], [6, 3, 2], [5, 3, 2],
+    [15, 3, 2], [11, 3, 2], [9, 8, 7], [7, 2, 1],
+    [5, 3, 2], [9, 3, 1], [7, 3, 1], [9, 8, 3],
+    [9, 4, 2], [8, 5, 3], [15, 14, 10], [10, 5, 2],
+    [9, 6, 2], [9, 3, 2], [9, 5, 2], [11, 10, 1],
+    [7, 3, 2], [11, 2, 1], [9, 7, 4], [4, 3, 1],
+    [8, 3, 1], [7, 4, 1], [7, 2, 1], [13, 11, 6],
+    [5, 3, 2], [7, 3, 2], [8, 7, 5], [12, 3, 2],
+    [13, 10, 6], [5, 3, 2], [5, 3, 2], [9, 5, 2],
+    [9, 7, 2], [13, 4, 3], [4, 3, 1], [11, 6, 4],
+    [18, 9, 6], [19, 18, 13], [11, 3, 2], [15, 9, 6],
+    [4, 3, 1], [16, 5, 2], [15, 14, 6], [8, 5, 2],
+    [15, 11, 2], [11, 6, 2], [7, 5, 3], [8, 3, 1],
+    [19, 16, 9], [11, 9, 6], [15, 7, 6], [13, 4, 3],
+    [14, 13, 3], [13, 6, 3], [9, 5, 2], [19, 13, 6],
+    [19, 10, 3], [11, 6, 5], [9, 2, 1], [14, 3, 2],
+    [13, 3, 1], [7, 5, 4], [11, 9, 8], [11, 6, 5],
+    [23, 16, 9], [19, 14, 6], [23, 10, 2], [8, 3, 2],
+    [5, 4, 3], [9, 6, 4], [4, 3, 2], [13, 8, 6],
+    [13, 11, 1], [13, 10, 3], [11, 6, 5], [19, 17, 4],
+    [15, 14, 7], [13, 9, 6], [9, 7, 3], [9, 7, 1],
+    [14, 3, 2], [11, 8, 2], [11, 6, 4], [13, 5, 2],
+    [11, 5, 1], [11, 4, 1], [19, 10, 3], [21, 10, 6],
+    [13, 3, 1], [15, 7, 5], [19, 18, 10], [7, 5, 3],
+    [12, 7, 2], [7, 5, 1], [14, 9, 6], [10, 3, 2],
+    [15, 13, 12], [12, 11, 9], [16, 9, 7], [12, 9, 3],
+    [9, 5, 2], [17, 10, 6], [24, 9, 3], [17, 15, 13],
+    [5, 4, 3], [19, 17, 8], [15, 6, 3], [19, 6, 1],
+];
+
+/// An element of one of the fields with 2^n elements, for n a multiple of 8 up to
+/// 1024, which `ssss` uses.
+///
+/// Every element knows the degree n of its field, except for `zero()` and `one()`,
+/// which belong to all of them and take the degree of the elements they are combined
+/// with. Arithmetic is not constant-time.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Gf2n {
+    limbs: [u64; GF2N_LIMBS],
+    degree: u16,
+}
+
+#[cfg(feature = "std")]
+impl Gf2n {
+    /// The degree of the largest field.
+    pub(crate) const MAX_DEGREE: usize = 1024;
+
+    /// Whether there is a field with 2^degree elements.
+    pub(crate) fn is_valid_degree(degree: usize) -> bool {
+        degree > 0 && degree <= Self::MAX_DEGREE && degree.is_multiple_of(8)
+    }
+
+    /// Reads the big-endian bytes of an element of the field with 2^degree elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such field, or if there are more than `degree / 8` bytes.
+    pub(crate) fn from_be_bytes(degree: usize, bytes: &[u8]) -> Self {
+        assert!(Self::is_valid_degree(degree) && bytes.len() <= degree / 8);
+        let mut limbs = [0; GF2N_LIMBS];
+        for (i, &byte) in bytes.iter().rev().enumerate() {
+            limbs[i / 8] |= u64::from(byte) << (8 * (i % 8));
+        }
+        Gf2n {
+            limbs,
+            degree: degree as u16,
+        }
+    }
+
+    /// Writes the element as `degree / 8` big-endian bytes.
+    pub(crate) fn to_be_bytes(self) -> Vec<u8> {
+        (0..usize::from(self.degree) / 8)
+            .rev()
+            .map(|i| (self.limbs[i / 8] >> (8 * (i % 8))) as u8)
+            .collect()
+    }
+
+    /// The irreducible polynomial defining the field of the given degree.
+    fn modulus(degree: u16) -> [u64; GF2N_LIMBS] {
+        let [a, b, c] = GF2N_PENTANOMIALS[usize::from(degree) / 8 - 1];
+        let mut modulus = [0; GF2N_LIMBS];
+        for exponent in [degree, a, b, c, 0] {
+            modulus[usize::from(exponent) / 64] |= 1 << (exponent % 64);
+        }
+        modulus
+    }
+
+    /// Computes the multiplicative inverse with the extended Euclidean algorithm.
+    fn inv(self) -> Self {
+        let (mut u, mut v) = (self.limbs, [0; GF2N_LIMBS]);
+        let (mut g1, mut g2) = ([0; GF2N_LIMBS], [0; GF2N_LIMBS]);
+        g1[0] = 1;
+        if bit_len(&u) > 1 {
+            v = Self::modulus(self.degree);
+        }
+        while bit_len(&u) > 1 {
+            if bit_len(&u) < bit_len(&v) {
+                core::mem::swap(&mut u, &mut v);
+                core::mem::swap(&mut g1, &mut g2);
+            }
+            let shift = bit_len(&u) - bit_len(&v);
+            xor_shifted(&mut u, &v, shift);
+            xor_shifted(&mut g1, &g2, shift);
+        }
+        Gf2n {
+            limbs: g1,
+            degree: self.degree,
+        }
+    }
+}
+
+/// The number of bits of a polynomial, one more than its degree.
+#[cfg(feature = "std")]
+fn bit_len(limbs: &[u64; GF2N_LIMBS]) -> usize {
+    limbs
+        .iter()
+        .rposition(|&limb| limb != 0)
+        .map_or(0, |i| 64 * (i + 1) - limbs[i].leading_zeros() as usize)
+}
+
+/// Adds `src * x^shift` to `dst`, which must not overflow.
+#[cfg(feature = "std")]
+fn xor_shifted(dst: &mut [u64; GF2N_LIMBS], src: &[u64; GF2N_LIMBS], shift: usize) {
+    let (limbs, bits) = (shift / 64, shift % 64);
+    for i in (limbs..GF2N_LIMBS).rev() {
+        let mut limb = src[i - limbs] << bits;
+        if bits > 0 && i > limbs {
+            limb |= src[i - limbs - 1] >> (64 - bits);
+        }
+        dst[i] ^= limb;
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq for Gf2n {
+    fn eq(&self, other: &Gf2n) -> bool {
+        self.limbs == other.limbs
+    }
+}
+
+#[cfg(feature = "std")]
+impl Eq for Gf2n {}
+
+#[cfg(feature = "std")]
+impl Field for Gf2n {
+    fn zero() -> Self {
+        Gf2n {
+            limbs: [0; GF2N_LIMBS],
+            degree: 0,
+        }
+    }
+
+    fn one() -> Self {
+        let mut one = Self::zero();
+        one.limbs[0] = 1;
+        one
+    }
+}
+
+#[cfg(feature = "std")]
+impl Add for Gf2n {
+    type Output = Gf2n;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(mut self, rhs: Gf2n) -> Gf2n {
+        for (limb, rhs) in self.limbs.iter_mut().zip(rhs.limbs) {
+            *limb ^= rhs;
+        }
+        self.degree = self.degree.max(rhs.degree);
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl AddAssign for Gf2n {
+    fn add_assign(&mut self, rhs: Gf2n) {
+        *self = *self + rhs;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Sub for Gf2n {
+    type Output = Gf2n;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Gf2n) -> Gf2n {
+        self + rhs
+    }
+}
+
+#[cfg(feature = "std")]
+impl SubAssign for Gf2n {
+    fn sub_assign(&mut self, rhs: Gf2n) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Mul for Gf2n {
+    type Output = Gf2n;
+
+    /// Shifts and adds, reducing the shifted operand as soon as it reaches the degree.
+    fn mul(self, rhs: Gf2n) -> Gf2n {
+        let degree = self.degree.max(rhs.degree);
+        let (mut a, mut acc) = (self.limbs, [0; GF2N_LIMBS]);
+        let modulus = if degree > 0 {
+            Self::modulus(degree)
+        } else {
+            [0; GF2N_LIMBS]
+        };
+        let top = usize::from(degree);
+        for i in 0..bit_len(&rhs.limbs) {
+            if (rhs.limbs[i / 64] >> (i % 64)) & 1 == 1 {
+                xor_shifted(&mut acc, &a, 0);
+            }
+            for j in (1..GF2N_LIMBS).rev() {
+                a[j] = (a[j] << 1) | (a[j - 1] >> 63);
+            }
+            a[0] <<= 1;
+            if degree > 0 && (a[top / 64] >> (top % 64)) & 1 == 1 {
+                xor_shifted(&mut a, &modulus, 0);
+            }
+        }
+        Gf2n { limbs: acc, degree }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Div for Gf2n {
+    type Output = Gf2n;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Gf2n) -> Gf2n {
+        assert!(rhs != Gf2n::zero(), "division by zero");
+        self * rhs.inv()
+    }
+}
+
+#[cfg(feature = "std")]
+impl DivAssign for Gf2n {
+    fn div_assign(&mut self, rhs: Gf2n) {
+        *self = *self / rhs;
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn rijndael_matches_aes() {
+        // From section 4.2 of FIPS 197.
+        assert_eq!(Rijndael(0x57) * Rijndael(0x83), Rijndael(0xc1));
+        assert_eq!(Rijndael(0x57) * Rijndael(0x13), Rijndael(0xfe));
+        // The inverse used by the S-box, from section 5.1.1.
+        assert_eq!(Rijndael(0x53).inv(), Rijndael(0xca));
+    }
+
+    #[test]
+    fn rijndael_inverses() {
+        for a in 1..=255 {
+            let a = Rijndael(a);
+            assert_eq!(a * a.inv(), Rijndael::one());
+            assert_eq!(a / a, Rijndael::one());
+        }
+    }
+
+    #[test]
+    fn gf2n_of_degree_8_is_rijndael() {
+        for a in 0..=255 {
+            for b in [0, 1, 2, 0x53, 0x83, 0xca, 0xff] {
+                let product = Gf2n::from_be_bytes(8, &[a]) * Gf2n::from_be_bytes(8, &[b]);
+                assert_eq!(product.to_be_bytes(), [(Rijndael(a) * Rijndael(b)).0]);
+            }
+        }
+    }
+
+    #[test]
+    fn gf2n_inverses() {
+        for degree in [16, 64, 184, 520, 1024] {
+            for seed in 1..8u8 {
+                let bytes = (0..degree / 8)
+                    .map(|i| seed.wrapping_mul(i as u8 + 1) ^ (i as u8))
+                    .collect::<Vec<_>>();
+                let a = Gf2n::from_be_bytes(degree, &bytes);
+                assert_eq!(a * a.inv(), Gf2n::one());
+                assert_eq!((a / a).to_be_bytes()[degree / 8 - 1], 1);
+                assert_eq!(a.to_be_bytes(), bytes);
+            }
+        }
+    }
+}
@@ -1,12 +1,34 @@
 //! This module provides the Gf256 type which is used to represent
 //! elements of a finite field with 256 elements.
+//!
+//! By default, multiplication and division are implemented using exp/log tables,
+//! whose memory access pattern depends on the operands. When the `constant-time`
+//! feature is enabled, they are instead computed with a fixed number of carry-less
+//! multiplication steps, without any secret-dependent branch or table lookup.
 
 #![allow(clippy::misrefactored_assign_op)]
 
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 include!(concat!(env!("OUT_DIR"), "/nothinghardcoded.rs"));
 
+/// The irreducible polynomial x^8 + x^4 + x^3 + x^2 + 1 defining the field,
+/// without its leading term. Must match the one used in `build.rs`.
+const POLY: u8 = 0x1D;
+
+/// Replicates the least significant bit to every other bit.
+#[inline]
+fn mask(bit: u8) -> u8 {
+    0u8.wrapping_sub(bit & 1)
+}
+
+/// Multiplies a polynomial with x and returns the residual
+/// of the polynomial division with POLY as divisor.
+#[inline]
+fn xtimes(poly: u8) -> u8 {
+    (poly << 1) ^ (mask(poly >> 7) & POLY)
+}
+
 fn get_tables() -> &'static Tables {
     &TABLES
 }
@@ -49,6 +71,7 @@ impl Gf256 {
         }
     }
 
+    #[cfg(any(feature = "dss", test))]
     #[allow(clippy::assign_op_pattern)]
     pub fn pow(&self, mut exp: u8) -> Gf256 {
         let mut base = *self;
@@ -68,6 +91,71 @@ impl Gf256 {
 
         acc
     }
+
+    /// Multiplies two elements using the exp/log tables.
+    #[cfg_attr(feature = "constant-time", allow(dead_code))]
+    #[inline]
+    fn mul_table(self, rhs: Gf256) -> Gf256 {
+        if let (Some(l1), Some(l2)) = (self.log(), rhs.log()) {
+            let tmp = (u16::from(l1) + u16::from(l2)) % 255;
+            Gf256::exp(tmp as u8)
+        } else {
+            Gf256 { poly: 0 }
+        }
+    }
+
+    /// Divides two elements using the exp/log tables.
+    #[cfg_attr(feature = "constant-time", allow(dead_code))]
+    #[inline]
+    fn div_table(self, rhs: Gf256) -> Gf256 {
+        let l2 = rhs.log().expect("division by zero");
+        if let Some(l1) = self.log() {
+            let tmp = (u16::from(l1) + 255 - u16::from(l2)) % 255;
+            Gf256::exp(tmp as u8)
+        } else {
+            Gf256 { poly: 0 }
+        }
+    }
+
+    /// Multiplies two elements in constant time, by always going through the
+    /// eight steps of a carry-less multiplication interleaved with the reduction.
+    #[cfg_attr(not(any(test, feature = "constant-time")), allow(dead_code))]
+    #[inline]
+    fn mul_ct(self, rhs: Gf256) -> Gf256 {
+        let mut a = self.poly;
+        let mut acc = 0u8;
+        for i in 0..8 {
+            acc ^= mask(rhs.poly >> i) & a;
+            a = xtimes(a);
+        }
+        Gf256 { poly: acc }
+    }
+
+    /// Computes the multiplicative inverse in constant time as `self^254`,
+    /// using a fixed chain of squarings and multiplications.
+    /// The inverse of zero is zero.
+    #[cfg_attr(not(any(test, feature = "constant-time")), allow(dead_code))]
+    #[inline]
+    fn inv_ct(self) -> Gf256 {
+        let mut square = self.mul_ct(self);
+        let mut acc = square;
+        for _ in 0..6 {
+            square = square.mul_ct(square);
+            acc = acc.mul_ct(square);
+        }
+        acc
+    }
+
+    /// Divides two elements in constant time.
+    /// Only whether `rhs` is zero leaks through timing, since this triggers a panic.
+    #[cfg_attr(not(any(test, feature = "constant-time")), allow(dead_code))]
+    #[inline]
+    fn div_ct(self, rhs: Gf256) -> Gf256 {
+        if rhs.poly == 0 {
+            panic!("division by zero");
+        }
+        self.mul_ct(rhs.inv_ct())
+    }
 }
 
 impl Add<Gf256> for Gf256 {
@@ -106,13 +194,17 @@ impl SubAssign<Gf256> for Gf256 {
 
 impl Mul<Gf256> for Gf256 {
     type Output = Gf256;
+
+    #[cfg(not(feature = "constant-time"))]
+    #[inline]
     fn mul(self, rhs: Gf256) -> Gf256 {
-        if let (Some(l1), Some(l2)) = (self.log(), rhs.log()) {
-            let tmp = (u16::from(l1) + u16::from(l2)) % 255;
-            Gf256::exp(tmp as u8)
-        } else {
-            Gf256 { poly: 0 }
-        }
+        self.mul_table(rhs)
+    }
+
+    #[cfg(feature = "constant-time")]
+    #[inline]
+    fn mul(self, rhs: Gf256) -> Gf256 {
+        self.mul_ct(rhs)
     }
 }
 
@@ -124,14 +216,17 @@ impl MulAssign<Gf256> for Gf256 {
 
 impl Div<Gf256> for Gf256 {
     type Output = Gf256;
+
+    #[cfg(not(feature = "constant-time"))]
+    #[inline]
     fn div(self, rhs: Gf256) -> Gf256 {
-        let l2 = rhs.log().expect("division by zero");
-        if let Some(l1) = self.log() {
-            let tmp = (u16::from(l1) + 255 - u16::from(l2)) % 255;
-            Gf256::exp(tmp as u8)
-        } else {
-            Gf256 { poly: 0 }
-        }
+        self.div_table(rhs)
+    }
+
+    #[cfg(feature = "constant-time")]
+    #[inline]
+    fn div(self, rhs: Gf256) -> Gf256 {
+        self.div_ct(rhs)
     }
 }
 
@@ -141,6 +236,13 @@ impl DivAssign<Gf256> for Gf256 {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Gf256 {
+    fn zeroize(&mut self) {
+        self.poly.zeroize();
+    }
+}
+
 impl Neg for Gf256 {
     type Output = Gf256;
     fn neg(self) -> Gf256 {
@@ -224,6 +326,41 @@ mod tests {
         mk_test!(pow, "^", |i: Gf256, j: Gf256| i.pow(j.to_byte()));
     }
 
+    mod backends {
+        use super::*;
+
+        #[test]
+        fn mul_ct_matches_mul_table() {
+            for i in 0..=255 {
+                for j in 0..=255 {
+                    let (i, j) = (Gf256::from_byte(i), Gf256::from_byte(j));
+                    assert_eq!(i.mul_ct(j), i.mul_table(j));
+                }
+            }
+        }
+
+        #[test]
+        fn div_ct_matches_div_table() {
+            for i in 0..=255 {
+                for j in 1..=255 {
+                    let (i, j) = (Gf256::from_byte(i), Gf256::from_byte(j));
+                    assert_eq!(i.div_ct(j), i.div_table(j));
+                }
+            }
+        }
+
+        #[test]
+        fn inv_ct_of_zero_is_zero() {
+            assert_eq!(Gf256::zero().inv_ct(), Gf256::zero());
+        }
+
+        #[test]
+        #[should_panic(expected = "division by zero")]
+        fn div_ct_by_zero_panics() {
+            let _ = Gf256::one().div_ct(Gf256::zero());
+        }
+    }
+
     impl Arbitrary for Gf256 {
         fn arbitrary(gen: &mut Gen) -> Gf256 {
             Gf256::from_byte(u8::arbitrary(gen))
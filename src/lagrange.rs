@@ -1,87 +1,84 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::field::Field;
 use crate::gf256::Gf256;
-use crate::poly::Poly;
-
-/// Evaluates an interpolated polynomial at `Gf256::zero()` where
-/// the polynomial is determined using barycentric Lagrange
-/// interpolation based on the given `points` in
-/// the G(2^8) Galois field.
-pub(crate) fn interpolate_at(k: u8, points: &[(u8, u8)]) -> u8 {
-    barycentric_interpolate_at(k as usize, points)
+
+/// The Lagrange basis determined by a set of distinct abscissas, in the G(2^8)
+/// Galois field by default, or in any other `Field`.
+///
+/// The polynomial interpolated from the points `(xs[i], ys[i])` evaluates at `x`
+/// to the sum of `basis.at(x)[i] * ys[i]`. Since the basis only depends on the
+/// abscissas, ie. on the share identifiers, it can be computed once and then
+/// applied to every byte of the shares, and evaluated at as many points as needed.
+///
+/// Uses the barycentric Lagrange interpolation algorithm from "Polynomial
+/// Interpolation: Langrange vs Newton" by Wilhelm Werner.
+pub(crate) struct LagrangeBasis<F = Gf256> {
+    xs: Vec<F>,
+    /// The barycentric weights.
+    weights: Vec<F>,
 }
 
-/// Barycentric Lagrange interpolation algorithm from "Polynomial
-/// Interpolation: Langrange vs Newton" by Wilhelm Werner. Evaluates
-/// the polynomial at `Gf256::zero()`.
-#[inline]
-fn barycentric_interpolate_at(k: usize, points: &[(u8, u8)]) -> u8 {
-    // Compute the barycentric weights `w`.
-    let mut w = vec![Gf256::zero(); k];
-    w[0] = Gf256::one();
-
-    let mut x = Vec::with_capacity(k);
-    x.push(Gf256::from_byte(points[0].0));
-
-    for i in 1..k {
-        x.push(Gf256::from_byte(points[i].0));
-        for j in 0..i {
-            let delta = x[j] - x[i];
-            assert_ne!(delta.poly, 0, "Duplicate shares");
-            w[j] /= delta;
-            let wj = w[j];
-            w[i] -= wj;
+impl<F: Field> LagrangeBasis<F> {
+    /// Computes the barycentric weights of the given abscissas, in O(k²).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `xs` is empty or contains duplicates.
+    pub(crate) fn new(xs: &[F]) -> Self {
+        let k = xs.len();
+
+        let mut w = vec![F::zero(); k];
+        w[0] = F::one();
+
+        for i in 1..k {
+            for j in 0..i {
+                let delta = xs[j] - xs[i];
+                assert!(delta != F::zero(), "Duplicate shares");
+                w[j] /= delta;
+                let wj = w[j];
+                w[i] -= wj;
+            }
         }
-    }
 
-    // Evaluate the second or "true" form of the barycentric
-    // interpolation formula at `Gf256::zero()`.
-    let (mut num, mut denom) = (Gf256::zero(), Gf256::zero());
-    for i in 0..k {
-        assert_ne!(x[i].poly, 0, "Invalid share x = 0");
-        let diff = w[i] / x[i];
-        num += diff * Gf256::from_byte(points[i].1);
-        denom += diff;
+        Self {
+            xs: xs.to_vec(),
+            weights: w,
+        }
     }
 
-    (num / denom).to_byte()
-}
+    /// Computes the values at `x` of the basis polynomials, in O(k).
+    pub(crate) fn at(&self, x: F) -> Vec<F> {
+        let k = self.xs.len();
 
-/// Computeds the coefficient of the Lagrange polynomial interpolated
-/// from the given `points`, in the G(2^8) Galois field.
-pub(crate) fn interpolate(points: &[(Gf256, Gf256)]) -> Poly {
-    let len = points.len();
-
-    let mut poly = vec![Gf256::zero(); len];
-
-    for &(x, y) in points {
-        assert_ne!(x.poly, 0, "Invalid share x = 0");
-        let mut coeffs = vec![Gf256::zero(); len];
-        coeffs[0] = y;
-
-        let mut prod = Gf256::one();
-        for &(x1, _) in points {
-            if x != x1 {
-                prod *= x - x1;
-
-                let mut prec = Gf256::zero();
-                coeffs = coeffs
-                    .into_iter()
-                    .map(|coeff| {
-                        let new_coeff = coeff * (-x1) + prec;
-                        prec = coeff;
-                        new_coeff
-                    })
-                    .collect();
-            }
+        // The interpolated polynomial goes through the given points.
+        if let Some(i) = self.xs.iter().position(|&xi| xi == x) {
+            let mut basis = vec![F::zero(); k];
+            basis[i] = F::one();
+            return basis;
         }
 
-        poly = poly
+        // Use the second or "true" form of the barycentric interpolation formula.
+        let mut basis = self
+            .weights
             .iter()
-            .zip(coeffs.iter())
-            .map(|(&old_coeff, &add)| old_coeff + add / prod)
-            .collect();
+            .zip(&self.xs)
+            .map(|(&wi, &xi)| wi / (x - xi))
+            .collect::<Vec<_>>();
+        let denom = basis.iter().fold(F::zero(), |acc, &b| acc + b);
+        for b in &mut basis {
+            *b /= denom;
+        }
+
+        basis
     }
+}
 
-    Poly::new(poly)
+/// Computes the values at `x` of the Lagrange basis polynomials determined
+/// by the abscissas `xs`. See `LagrangeBasis`.
+pub(crate) fn barycentric_basis_at(xs: &[Gf256], x: Gf256) -> Vec<Gf256> {
+    LagrangeBasis::new(xs).at(x)
 }
 
 #[cfg(test)]
@@ -90,17 +87,52 @@ mod tests {
 
     use super::*;
     use crate::gf256::*;
+    use crate::poly::Poly;
     use quickcheck::*;
 
+    /// Computes the coefficients of the Lagrange polynomial interpolated
+    /// from the given `points`, used as a reference for the basis.
+    fn interpolate(points: &[(Gf256, Gf256)]) -> Poly {
+        let len = points.len();
+
+        let mut poly = Poly::new(vec![Gf256::zero(); len]);
+
+        for &(x, y) in points {
+            assert_ne!(x.poly, 0, "Invalid share x = 0");
+            let mut coeffs = vec![Gf256::zero(); len];
+            coeffs[0] = y;
+
+            let mut prod = Gf256::one();
+            for &(x1, _) in points {
+                if x != x1 {
+                    prod *= x - x1;
+
+                    let mut prec = Gf256::zero();
+                    for coeff in coeffs.iter_mut() {
+                        let old_coeff = *coeff;
+                        *coeff = old_coeff * (-x1) + prec;
+                        prec = old_coeff;
+                    }
+                }
+            }
+
+            for (coeff, &add) in poly.coeffs.iter_mut().zip(coeffs.iter()) {
+                *coeff += add / prod;
+            }
+        }
+
+        poly
+    }
+
     quickcheck! {
 
         fn interpolate_evaluate_at_works(ys: Vec<Gf256>) -> TestResult {
-            if ys.is_empty() || ys.len() > std::u8::MAX as usize {
+            if ys.is_empty() || ys.len() > u8::MAX as usize {
                 return TestResult::discard();
             }
 
             let points = ys.into_iter()
-                           .zip(1..std::u8::MAX)
+                           .zip(1..u8::MAX)
                            .map(|(y, x)| (gf256!(x), y))
                            .collect::<Vec<_>>();
             let poly = interpolate(&points);
@@ -114,27 +146,42 @@ mod tests {
             TestResult::passed()
         }
 
-        fn interpolate_evaluate_at_0_eq_evaluate_at(ys: Vec<u8>) -> TestResult {
-            if ys.is_empty() || ys.len() > std::u8::MAX as usize {
+        fn interpolate_evaluate_at_eq_basis_at(ys: Vec<u8>, x: u8) -> TestResult {
+            if ys.is_empty() || ys.len() > u8::MAX as usize {
                 return TestResult::discard();
             }
 
             let points = ys.into_iter()
-                           .zip(1..std::u8::MAX)
-                           .map(|(y, x)| (x, y))
+                           .zip(1..u8::MAX)
+                           .map(|(y, x)| (gf256!(x), gf256!(y)))
                            .collect::<Vec<_>>();
 
-            let elems = points
+            let poly = interpolate(&points);
+
+            let xs = points.iter().map(|&(x, _)| x).collect::<Vec<_>>();
+            let basis = barycentric_basis_at(&xs, gf256!(x));
+            let value = basis
                 .iter()
-                .map(|&(x, y)| (gf256!(x), gf256!(y)))
-                .collect::<Vec<_>>();
+                .zip(points.iter())
+                .fold(Gf256::zero(), |acc, (&b, &(_, y))| acc + b * y);
+
+            TestResult::from_bool(poly.evaluate_at(gf256!(x)) == value)
+        }
 
-            let poly = interpolate(&elems);
+        fn basis_at_many_points_eq_basis_at(xs: Vec<u8>, at: Vec<u8>) -> TestResult {
+            let mut xs = xs.into_iter().filter(|&x| x != 0).collect::<Vec<_>>();
+            xs.sort();
+            xs.dedup();
+            if xs.is_empty() {
+                return TestResult::discard();
+            }
 
-            let equals = poly.evaluate_at(Gf256::zero()).to_byte()
-                == interpolate_at(points.len() as u8, points.as_slice());
+            let xs = xs.into_iter().map(|x| gf256!(x)).collect::<Vec<_>>();
+            let basis = LagrangeBasis::new(&xs);
 
-            TestResult::from_bool(equals)
+            TestResult::from_bool(at.into_iter().all(|x| {
+                basis.at(gf256!(x)) == barycentric_basis_at(&xs, gf256!(x))
+            }))
         }
 
     }
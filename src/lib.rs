@@ -13,22 +13,63 @@
     unused_qualifications
 )]
 #![allow(clippy::doc_markdown)]
-#![recursion_limit = "1024"] // `error_chain!` can recurse deeply
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-#[macro_use]
-extern crate error_chain;
+extern crate alloc;
+
+/// Returns early with the given error, converted into an `Error`.
+macro_rules! bail {
+    ($e:expr) => {
+        return Err($e.into())
+    };
+}
 
+mod field;
 #[macro_use]
 mod gf256;
 mod lagrange;
+#[cfg(any(feature = "dss", test))]
 mod poly;
 mod share;
+mod simd;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "dss")]
 mod vol_hash;
 
+#[cfg(all(feature = "serde", feature = "dss"))]
+mod serde_base64;
+
+#[cfg(feature = "std")]
+mod armor;
+#[cfg(feature = "std")]
+pub mod compat;
+#[cfg(feature = "std")]
+pub mod encoding;
 pub mod errors;
+#[cfg(feature = "std")]
+mod mnemonic;
+#[cfg(feature = "std")]
 pub mod proto;
+#[cfg(feature = "qr")]
+pub mod qr;
+#[cfg(feature = "std")]
+pub mod reed_solomon;
+#[cfg(feature = "std")]
+pub mod scheme;
+pub mod secret;
+#[cfg(feature = "std")]
+pub mod slip39;
 pub mod sss;
+#[cfg(feature = "std")]
 pub mod wrapped_secrets;
 
 #[cfg(feature = "dss")]
 pub mod dss;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
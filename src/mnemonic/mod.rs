@@ -0,0 +1,228 @@
+//! Encodes shares as sequences of words, which are easier to write down on paper
+//! and to read back than base64.
+//!
+//! The words are taken from the English word list of BIP-39, each of them holding
+//! 11 bits. The first word gives the number of padding bits ending the last one.
+//! The following words hold the binary encoding of the share, see `share::envelope`,
+//! and the first 4 bytes of its SHA-256 hash, which catches transcription errors.
+//!
+//! Words are matched regardless of their case, and may be abbreviated to their first
+//! four letters, which are enough to tell them apart.
+
+use std::sync::OnceLock;
+
+use ring::digest::{digest, SHA256};
+
+use crate::errors::*;
+use crate::share::envelope::Envelope;
+
+/// The English word list of BIP-39, one word per line, in alphabetical order.
+const WORD_LIST: &str = include_str!("english.txt");
+
+const BITS_PER_WORD: usize = 11;
+const WORD_MASK: u32 = (1 << BITS_PER_WORD) - 1;
+const CHECKSUM_LEN: usize = 4;
+
+/// The number of letters a word can be abbreviated to.
+const PREFIX_LEN: usize = 4;
+
+fn words() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORD_LIST.lines().collect())
+}
+
+/// Returns whether the given share is encoded as words, rather than as `K-N-D`.
+pub(crate) fn is_mnemonic(raw: &str) -> bool {
+    raw.trim().contains(char::is_whitespace)
+}
+
+/// Encodes the given share as a sequence of words separated by spaces.
+pub(crate) fn encode(envelope: &Envelope) -> String {
+    let mut payload = envelope.to_bytes();
+    let checksum = checksum(&payload);
+    payload.extend_from_slice(&checksum);
+
+    let words = words();
+    let padding = (BITS_PER_WORD - payload.len() * 8 % BITS_PER_WORD) % BITS_PER_WORD;
+    let mut mnemonic = Vec::with_capacity(2 + payload.len() * 8 / BITS_PER_WORD);
+    mnemonic.push(words[padding]);
+
+    let (mut acc, mut acc_bits) = (0u32, 0);
+    for byte in payload {
+        acc = (acc << 8) | u32::from(byte);
+        acc_bits += 8;
+        if acc_bits >= BITS_PER_WORD {
+            acc_bits -= BITS_PER_WORD;
+            mnemonic.push(words[((acc >> acc_bits) & WORD_MASK) as usize]);
+            acc &= (1 << acc_bits) - 1;
+        }
+    }
+    if acc_bits > 0 {
+        mnemonic.push(words[((acc << (BITS_PER_WORD - acc_bits)) & WORD_MASK) as usize]);
+    }
+
+    mnemonic.join(" ")
+}
+
+/// Decodes a sequence of words produced by `encode`.
+pub(crate) fn decode(raw: &str) -> Result<Envelope> {
+    let indices = raw
+        .split_whitespace()
+        .enumerate()
+        .map(|(position, word)| {
+            word_index(word)
+                .ok_or_else(|| Error::UnknownMnemonicWord(position + 1, word.to_owned()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (padding, indices) = match indices.split_first() {
+        Some((&padding, indices)) if padding < BITS_PER_WORD => (padding, indices),
+        _ => bail!(Error::ShareParsingError(
+            "The first word of the mnemonic is not a valid header.".to_owned()
+        )),
+    };
+    let bits = indices.len() * BITS_PER_WORD;
+    let padding_is_zero = indices
+        .last()
+        .is_some_and(|&last| last & ((1 << padding) - 1) == 0);
+    if bits < padding || !(bits - padding).is_multiple_of(8) || !padding_is_zero {
+        bail!(Error::ShareParsingError(
+            "The mnemonic has an invalid length.".to_owned()
+        ));
+    }
+
+    let mut payload = Vec::with_capacity(bits / 8);
+    let (mut acc, mut acc_bits) = (0u32, 0);
+    for &index in indices {
+        acc = (acc << BITS_PER_WORD) | index as u32;
+        acc_bits += BITS_PER_WORD;
+        while acc_bits >= 8 {
+            acc_bits -= 8;
+            payload.push((acc >> acc_bits) as u8);
+        }
+        acc &= (1 << acc_bits) - 1;
+    }
+    // Padding bits may make up a whole byte.
+    payload.truncate((bits - padding) / 8);
+
+    if payload.len() < CHECKSUM_LEN {
+        bail!(Error::ShareParsingError(
+            "The mnemonic is too short.".to_owned()
+        ));
+    }
+    let (payload, expected) = payload.split_at(payload.len() - CHECKSUM_LEN);
+    if checksum(payload) != expected {
+        bail!(Error::InvalidMnemonicChecksum);
+    }
+
+    Envelope::from_bytes(payload)
+}
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut checksum = [0; CHECKSUM_LEN];
+    checksum.copy_from_slice(&digest(&SHA256, payload).as_ref()[..CHECKSUM_LEN]);
+    checksum
+}
+
+fn word_index(word: &str) -> Option<usize> {
+    find_word(words(), word)
+}
+
+/// Looks a word up in the given alphabetically sorted word list, accepting
+/// abbreviations to the first letters of a word, as long as there are at least
+/// `PREFIX_LEN` of them. The words of the list must be unique in their first
+/// `PREFIX_LEN` letters.
+pub(crate) fn find_word(words: &[&str], word: &str) -> Option<usize> {
+    let word = word.to_ascii_lowercase();
+    match words.binary_search(&word.as_str()) {
+        Ok(index) => Some(index),
+        Err(index) if word.len() >= PREFIX_LEN => words
+            .get(index)
+            .filter(|candidate| candidate.starts_with(&word))
+            .map(|_| index),
+        Err(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::proto::SchemeProto;
+
+    fn envelope(threshold: u8, id: u8, body: &[u8]) -> Envelope {
+        Envelope {
+            scheme: SchemeProto::Sss,
+            threshold,
+            id,
+            body: body.to_vec(),
+        }
+    }
+
+    #[test]
+    fn word_list_is_sorted_and_unambiguous() {
+        let words = words();
+        assert_eq!(words.len(), 1 << BITS_PER_WORD);
+        for pair in words.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert_ne!(
+                pair[0][..PREFIX_LEN.min(pair[0].len())],
+                pair[1][..PREFIX_LEN.min(pair[1].len())]
+            );
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        for len in 0..40 {
+            let data = (0..len).map(|i| (i * 37 + 11) as u8).collect::<Vec<_>>();
+            let envelope = envelope(3, 7, &data);
+            let mnemonic = encode(&envelope);
+            assert!(is_mnemonic(&mnemonic));
+            assert_eq!(decode(&mnemonic).unwrap(), envelope);
+        }
+    }
+
+    #[test]
+    fn words_can_be_abbreviated() {
+        let envelope = envelope(2, 1, b"Hello, World!");
+        let mnemonic = encode(&envelope);
+        let abbreviated = mnemonic
+            .split(' ')
+            .map(|word| {
+                word.chars()
+                    .take(PREFIX_LEN)
+                    .collect::<String>()
+                    .to_uppercase()
+            })
+            .collect::<Vec<_>>()
+            .join("\n  ");
+        assert_eq!(decode(&abbreviated).unwrap(), envelope);
+    }
+
+    #[test]
+    fn detects_typos() {
+        let mnemonic = encode(&envelope(2, 1, b"Hello, World!"));
+        let mut words = mnemonic.split(' ').collect::<Vec<_>>();
+        words[3] = if words[3] == "zoo" { "zone" } else { "zoo" };
+        match decode(&words.join(" ")) {
+            Err(Error::InvalidMnemonicChecksum) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+
+        words[3] = "zoology";
+        match decode(&words.join(" ")) {
+            Err(Error::UnknownMnemonicWord(4, ref word)) if word == "zoology" => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_mnemonics() {
+        let mnemonic = encode(&envelope(2, 1, b"Hello, World!"));
+        let words = mnemonic.split(' ').collect::<Vec<_>>();
+        for len in 0..words.len() {
+            assert!(decode(&words[..len].join(" ")).is_err());
+        }
+    }
+}
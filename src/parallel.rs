@@ -0,0 +1,9 @@
+//! Parameters of the multi-threaded implementations, enabled by the `parallel` feature.
+//!
+//! Work is only ever partitioned over disjoint ranges of bytes or shares, and the
+//! results are gathered in order, so that the output is byte-for-byte identical to
+//! the one of the single-threaded implementations.
+
+/// Number of secret bytes processed by a single task.
+/// Secrets shorter than this are processed on the calling thread.
+pub(crate) const CHUNK_LEN: usize = 1 << 16;
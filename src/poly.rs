@@ -1,20 +1,27 @@
+use alloc::vec::Vec;
+
 use crate::gf256::Gf256;
 
+#[cfg(test)]
 static MAX_COEFFS: usize = 256;
 
 pub(crate) struct Poly {
     pub coeffs: Vec<Gf256>,
 }
 
+#[cfg(feature = "zeroize")]
+impl Drop for Poly {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.coeffs);
+    }
+}
+
 impl Poly {
     pub fn new(coeffs: Vec<Gf256>) -> Self {
         Self { coeffs }
     }
 
-    pub fn evaluate_at_zero(&self) -> Gf256 {
-        self.coeffs[0]
-    }
-
+    #[cfg(test)]
     pub fn evaluate_at(&self, x: Gf256) -> Gf256 {
         assert!(self.coeffs.len() < MAX_COEFFS);
 
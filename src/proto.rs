@@ -16,4 +16,11 @@ mod version {
     include!(concat!(env!("OUT_DIR"), "/version.rs"));
 }
 
+#[allow(missing_docs)]
+mod scheme {
+    include!(concat!(env!("OUT_DIR"), "/scheme.rs"));
+}
+
+pub(crate) use self::scheme::SchemeHeaderProto;
+pub use self::scheme::SchemeProto;
 pub use self::version::VersionProto;
@@ -0,0 +1,297 @@
+//! Renders shares as QR codes, for paper backups.
+//!
+//! A QR code holds the binary encoding of the share, see `share::envelope`, as do
+//! the mnemonic and armored formats. Shares of any scheme and in any format can be
+//! rendered.
+//!
+//! Shares too large for a single QR code, such as signed shares, are split into a
+//! numbered sequence of QR codes. Each of them starts with its position in the
+//! sequence and the length of the sequence, counted from 1, followed by the first 4
+//! bytes of the SHA-256 hash of the whole share, which tells the QR codes of a share
+//! from those of another one when reassembling it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use etospheres_labs_rusty_secrets::qr;
+//! use etospheres_labs_rusty_secrets::scheme::{Scheme, SplitOptions};
+//! use etospheres_labs_rusty_secrets::sss::Sss;
+//!
+//! let shares = Sss.split(b"my secret", SplitOptions::new(2, 3)).unwrap();
+//! let codes = qr::share_to_qr_codes(&shares[0]).unwrap();
+//! println!("{}", codes[0].to_terminal());
+//!
+//! // What a scanner reads from the QR codes, in any order.
+//! let scanned = codes.iter().map(|code| code.data().to_vec()).collect::<Vec<_>>();
+//! assert_eq!(qr::share_from_qr_data(&scanned).unwrap(), shares[0]);
+//! ```
+
+use std::fmt;
+
+use qrcode::render::{svg, unicode};
+use qrcode::{Color, EcLevel, QrCode};
+use ring::digest::{digest, SHA256};
+
+use crate::encoding::{self, TextEncoding};
+use crate::errors::*;
+use crate::share::envelope::Envelope;
+
+/// The number of bytes held by a single QR code, header included, which keeps QR
+/// codes small enough to be printed and scanned reliably. At the error correction
+/// level used, a QR code holds up to `QR_CODE_CAPACITY` bytes.
+pub const MAX_QR_CODE_LEN: usize = 1024;
+
+/// The number of bytes a QR code holds at most, at the error correction level used.
+const QR_CODE_CAPACITY: usize = 2331;
+
+/// The error correction level of the QR codes, which recovers up to 15% of damage.
+const EC_LEVEL: EcLevel = EcLevel::M;
+
+const DIGEST_LEN: usize = 4;
+const HEADER_LEN: usize = 2 + DIGEST_LEN;
+const CHUNK_LEN: usize = MAX_QR_CODE_LEN - HEADER_LEN;
+
+/// A QR code holding a share, or a part of it.
+#[derive(Clone)]
+pub struct ShareQrCode {
+    index: u8,
+    count: u8,
+    data: Vec<u8>,
+    code: QrCode,
+}
+
+impl ShareQrCode {
+    /// The position of this QR code in the sequence holding the share, from 1.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// The number of QR codes holding the share.
+    pub fn count(&self) -> u8 {
+        self.count
+    }
+
+    /// The bytes held by this QR code, as read by a scanner.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The number of modules on each side of the QR code, without the quiet zone.
+    pub fn width(&self) -> usize {
+        self.code.width()
+    }
+
+    /// The modules of the QR code, row by row, `true` being dark.
+    pub fn matrix(&self) -> Vec<Vec<bool>> {
+        self.code
+            .to_colors()
+            .chunks(self.width())
+            .map(|row| row.iter().map(|&color| color == Color::Dark).collect())
+            .collect()
+    }
+
+    /// Renders the QR code as an SVG image, with a quiet zone around it.
+    pub fn to_svg(&self) -> String {
+        self.code
+            .render::<svg::Color<'_>>()
+            .min_dimensions(256, 256)
+            .build()
+    }
+
+    /// Renders the QR code with Unicode block characters, two rows per line, with a
+    /// quiet zone around it. Colors are inverted, for terminals with a dark
+    /// background.
+    pub fn to_terminal(&self) -> String {
+        self.code
+            .render::<unicode::Dense1x2>()
+            .dark_color(unicode::Dense1x2::Light)
+            .light_color(unicode::Dense1x2::Dark)
+            .build()
+    }
+}
+
+impl fmt::Debug for ShareQrCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ShareQrCode")
+            .field("index", &self.index)
+            .field("count", &self.count)
+            .field("width", &self.width())
+            .finish()
+    }
+}
+
+/// Renders the given share, in any format, as a sequence of QR codes, holding at
+/// most `MAX_QR_CODE_LEN` bytes each.
+pub fn share_to_qr_codes(raw: &str) -> Result<Vec<ShareQrCode>> {
+    let payload = encoding::decode(raw)?.to_bytes();
+
+    let max_len = usize::from(u8::MAX) * CHUNK_LEN;
+    if payload.len() > max_len {
+        bail!(Error::ShareTooBig(payload.len(), max_len));
+    }
+    let hash = digest(&SHA256, &payload);
+    let chunks = payload.chunks(CHUNK_LEN).collect::<Vec<_>>();
+    // The count was checked above.
+    let count = chunks.len() as u8;
+
+    chunks
+        .into_iter()
+        .zip(1..=count)
+        .map(|(chunk, index)| {
+            let mut data = Vec::with_capacity(HEADER_LEN + chunk.len());
+            data.push(index);
+            data.push(count);
+            data.extend_from_slice(&hash.as_ref()[..DIGEST_LEN]);
+            data.extend_from_slice(chunk);
+            // Bytes only fail to be encoded when they do not fit in a QR code.
+            let code = QrCode::with_error_correction_level(&data, EC_LEVEL)
+                .map_err(|_| Error::ShareTooBig(data.len(), QR_CODE_CAPACITY))?;
+            Ok(ShareQrCode {
+                index,
+                count,
+                data,
+                code,
+            })
+        })
+        .collect()
+}
+
+/// Reassembles a share from the bytes read from its QR codes, in any order, and
+/// returns it in the `K-N-D` format, which all schemes parse.
+pub fn share_from_qr_data(chunks: &[Vec<u8>]) -> Result<String> {
+    let first = match chunks.first() {
+        Some(first) if first.len() >= HEADER_LEN => first,
+        Some(_) => bail!(Error::ShareParsingError(
+            "The QR code is too short to hold a share.".to_owned()
+        )),
+        None => bail!(Error::EmptyShares),
+    };
+    let count = first[1];
+    let hash = &first[2..HEADER_LEN];
+
+    let mut parts = vec![None; count.into()];
+    for chunk in chunks {
+        if chunk.len() < HEADER_LEN || chunk[1] != count || &chunk[2..HEADER_LEN] != hash {
+            bail!(Error::ShareParsingError(
+                "The QR codes do not belong to the same share.".to_owned()
+            ));
+        }
+        let index = chunk[0];
+        match parts.get_mut(usize::from(index).wrapping_sub(1)) {
+            Some(Some(_)) => bail!(Error::ShareParsingError(format!(
+                "QR code {} of {} was given more than once.",
+                index, count
+            ))),
+            Some(part) => *part = Some(&chunk[HEADER_LEN..]),
+            None => bail!(Error::ShareParsingError(format!(
+                "Invalid QR code number {} in a sequence of {}.",
+                index, count
+            ))),
+        }
+    }
+
+    let mut payload = Vec::new();
+    for (part, index) in parts.into_iter().zip(1..=count) {
+        match part {
+            Some(part) => payload.extend_from_slice(part),
+            None => bail!(Error::ShareParsingError(format!(
+                "QR code {} of {} is missing.",
+                index, count
+            ))),
+        }
+    }
+    if &digest(&SHA256, &payload).as_ref()[..DIGEST_LEN] != hash {
+        bail!(Error::ShareParsingError(
+            "The share read from the QR codes is corrupted.".to_owned()
+        ));
+    }
+
+    Ok(Envelope::from_bytes(&payload)?.to_text(TextEncoding::Base64))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::scheme::{Encoding, Scheme, Signing, SplitOptions};
+    use crate::sss::Sss;
+
+    const SECRET: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn small_shares_fit_in_one_qr_code() {
+        let shares = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+        let codes = share_to_qr_codes(&shares[0]).unwrap();
+        assert_eq!(codes.len(), 1);
+        assert_eq!((codes[0].index(), codes[0].count()), (1, 1));
+
+        let scanned = vec![codes[0].data().to_vec()];
+        assert_eq!(share_from_qr_data(&scanned).unwrap(), shares[0]);
+    }
+
+    #[test]
+    fn shares_in_any_format_are_rendered() {
+        let options = SplitOptions::new(2, 3).encoding(Encoding::Mnemonic);
+        let shares = Sss.split(SECRET, options).unwrap();
+        let codes = share_to_qr_codes(&shares[1]).unwrap();
+        let scanned = share_from_qr_data(&[codes[0].data().to_vec()]).unwrap();
+        let share = crate::sss::Share::from_string(&scanned, false).unwrap();
+        assert_eq!(share.into_mnemonic(), shares[1]);
+
+        let options = SplitOptions::new(2, 3).encoding(Encoding::Armor);
+        let shares = Sss.split(SECRET, options).unwrap();
+        assert_eq!(share_to_qr_codes(&shares[2]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn signed_shares_are_chunked() {
+        let options = SplitOptions::new(2, 3).signing(Signing::Merkle);
+        let shares = Sss.split(SECRET, options).unwrap();
+        let codes = share_to_qr_codes(&shares[0]).unwrap();
+        assert!(codes.len() > 1);
+        for (code, index) in codes.iter().zip(1..) {
+            assert_eq!(code.index(), index);
+            assert_eq!(usize::from(code.count()), codes.len());
+            assert!(code.data().len() <= MAX_QR_CODE_LEN);
+        }
+
+        let mut scanned = codes
+            .iter()
+            .map(|code| code.data().to_vec())
+            .collect::<Vec<_>>();
+        scanned.reverse();
+        assert_eq!(share_from_qr_data(&scanned).unwrap(), shares[0]);
+
+        let missing = share_from_qr_data(&scanned[1..]).unwrap_err();
+        assert!(missing.to_string().contains("is missing"));
+        scanned[1] = scanned[0].clone();
+        assert!(share_from_qr_data(&scanned).is_err());
+
+        let other = share_to_qr_codes(&shares[1]).unwrap();
+        scanned = codes.iter().map(|code| code.data().to_vec()).collect();
+        scanned[0] = other[0].data().to_vec();
+        assert!(share_from_qr_data(&scanned).is_err());
+    }
+
+    #[test]
+    fn renderings() {
+        let shares = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+        let code = &share_to_qr_codes(&shares[0]).unwrap()[0];
+        let matrix = code.matrix();
+        assert_eq!(matrix.len(), code.width());
+        assert!(matrix.iter().all(|row| row.len() == code.width()));
+        // The top-left finder pattern.
+        assert!(matrix[0][..7].iter().all(|&dark| dark));
+        assert!(!matrix[1][1]);
+
+        let svg = code.to_svg();
+        assert!(svg.contains("<svg") && svg.ends_with("</svg>"));
+
+        let terminal = code.to_terminal();
+        // A quiet zone of 4 modules on each side, two rows per line.
+        assert_eq!(terminal.lines().count(), (code.width() + 8).div_ceil(2));
+        assert!(terminal
+            .lines()
+            .all(|line| line.chars().count() == code.width() + 8));
+    }
+}
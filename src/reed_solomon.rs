@@ -0,0 +1,495 @@
+//! Encodes shares with Reed–Solomon error correction, for shares written on media
+//! which can get damaged, such as paper or steel.
+//!
+//! A share is encoded as `RS<t>:` followed by its binary encoding, see
+//! `share::envelope`, and by Reed–Solomon parity bytes over `Gf256`, in hexadecimal:
+//!
+//! ```text
+//! RS4:5253 5348 0001 0201 ... 3F9C
+//! ```
+//!
+//! Up to `t` corrupted characters, anywhere in the share, are corrected. Characters
+//! which cannot be read are to be written as `?`: since their position is known,
+//! twice as many of them are restored. Whitespace and dashes are ignored, and so is
+//! the case of the hexadecimal digits.
+//!
+//! Shares are split into blocks of at most 255 bytes, each of them followed by `2t`
+//! parity bytes, so that a block corrects up to `t` bytes on its own.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use etospheres_labs_rusty_secrets::reed_solomon;
+//! use etospheres_labs_rusty_secrets::scheme::{Scheme, SplitOptions};
+//! use etospheres_labs_rusty_secrets::sss::Sss;
+//!
+//! let shares = Sss.split(b"my secret", SplitOptions::new(2, 3)).unwrap();
+//! let encoded = reed_solomon::encode_share(&shares[0], 4).unwrap();
+//!
+//! let damaged = encoded.replacen("RS4:5253 ", "RS4:5?53 ", 1);
+//! let corrected = reed_solomon::decode_share(&damaged).unwrap();
+//! assert_eq!(corrected.share, shares[0]);
+//! assert_eq!(corrected.corrections, 1);
+//! ```
+
+use crate::encoding::{self, TextEncoding};
+use crate::errors::*;
+use crate::gf256::Gf256;
+use crate::share::envelope::Envelope;
+
+/// The number of corrupted characters a share corrects at most.
+pub const MAX_CORRECTIONS: u8 = 64;
+
+const PREFIX: &str = "RS";
+const SEPARATOR: char = ':';
+const ERASURE: char = '?';
+const GROUP_LEN: usize = 4;
+
+/// The length of a Reed–Solomon code word over `Gf256`, parity bytes included.
+const BLOCK_LEN: usize = 255;
+
+/// A share decoded by `decode_share`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorrectedShare {
+    /// The share, in the `K-N-D` format.
+    pub share: String,
+    /// The number of characters which were corrupted or missing, and were corrected.
+    pub corrections: usize,
+}
+
+/// Encodes the given share, in any format, so that up to `corrections` corrupted
+/// characters are corrected when parsing it.
+pub fn encode_share(raw: &str, corrections: u8) -> Result<String> {
+    encode(&encoding::decode(raw)?, corrections)
+}
+
+/// Decodes the given share, correcting the corrupted or missing characters.
+pub fn decode_share(raw: &str) -> Result<CorrectedShare> {
+    let (envelope, corrections) = decode(raw)?;
+    Ok(CorrectedShare {
+        share: envelope.to_text(TextEncoding::Base64),
+        corrections,
+    })
+}
+
+/// Returns whether the given share is encoded with error correction.
+pub(crate) fn is_reed_solomon(raw: &str) -> bool {
+    parse_prefix(raw).is_some()
+}
+
+/// Encodes the given share with enough parity bytes to correct `corrections`
+/// corrupted characters.
+pub(crate) fn encode(envelope: &Envelope, corrections: u8) -> Result<String> {
+    if corrections == 0 || corrections > MAX_CORRECTIONS {
+        bail!(Error::InvalidCorrectionCount(corrections));
+    }
+    let nsym = 2 * usize::from(corrections);
+
+    let payload = envelope.to_bytes();
+
+    let generator = generator_poly(nsym);
+    let mut encoded = Vec::new();
+    for block in payload.chunks(BLOCK_LEN - nsym) {
+        encoded.extend_from_slice(block);
+        encoded.extend(parity(block, &generator));
+    }
+
+    let hex = encoded
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<String>();
+    let groups = hex
+        .as_bytes()
+        .chunks(GROUP_LEN)
+        // Hexadecimal is ASCII, so it can be split at any byte.
+        .map(|group| std::str::from_utf8(group).unwrap())
+        .collect::<Vec<_>>();
+    Ok(format!(
+        "{}{}{}{}",
+        PREFIX,
+        corrections,
+        SEPARATOR,
+        groups.join(" ")
+    ))
+}
+
+/// Decodes a share produced by `encode`, along with the number of characters which
+/// were corrected.
+pub(crate) fn decode(raw: &str) -> Result<(Envelope, usize)> {
+    let (t, body) = parse_prefix(raw).ok_or_else(|| {
+        Error::ShareParsingError(format!(
+            "Expected a share starting with `{}<t>{}`.",
+            PREFIX, SEPARATOR
+        ))
+    })?;
+    if t == 0 || t > MAX_CORRECTIONS {
+        bail!(Error::InvalidCorrectionCount(t));
+    }
+    let nsym = 2 * usize::from(t);
+
+    // The digits of the share, `None` standing for unreadable ones.
+    let digits = body
+        .chars()
+        .filter(|&c| !c.is_whitespace() && c != '-')
+        .map(|c| match c {
+            ERASURE => Ok(None),
+            _ => c
+                .to_digit(16)
+                .map(|digit| Some(digit as u8))
+                .ok_or_else(|| {
+                    Error::ShareParsingError(format!(
+                        "Unexpected character in a share with error correction: {}.",
+                        c
+                    ))
+                }),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if !digits.len().is_multiple_of(2) {
+        bail!(Error::ShareParsingError(
+            "A share with error correction must have an even number of digits.".to_owned()
+        ));
+    }
+
+    let mut encoded = Vec::with_capacity(digits.len() / 2);
+    let mut erasures = Vec::new();
+    for (i, pair) in digits.chunks(2).enumerate() {
+        match (pair[0], pair[1]) {
+            (Some(high), Some(low)) => encoded.push((high << 4) | low),
+            _ => {
+                encoded.push(0);
+                erasures.push(i);
+            }
+        }
+    }
+
+    let mut payload = Vec::with_capacity(encoded.len());
+    for (index, block) in encoded.chunks_mut(BLOCK_LEN).enumerate() {
+        if block.len() <= nsym {
+            bail!(Error::ShareParsingError(
+                "The share with error correction is truncated.".to_owned()
+            ));
+        }
+        let start = index * BLOCK_LEN;
+        let block_erasures = erasures
+            .iter()
+            .filter(|&&i| i >= start && i < start + block.len())
+            .map(|&i| i - start)
+            .collect::<Vec<_>>();
+        correct(block, nsym, &block_erasures)?;
+        payload.extend_from_slice(&block[..block.len() - nsym]);
+    }
+
+    // Counts the digits which differ from the corrected ones, unreadable ones included.
+    let corrections = encoded
+        .iter()
+        .flat_map(|byte| [Some(byte >> 4), Some(byte & 0x0F)])
+        .zip(&digits)
+        .filter(|(corrected, digit)| corrected != *digit)
+        .count();
+
+    Ok((Envelope::from_bytes(&payload)?, corrections))
+}
+
+/// Splits `RS<t>:<body>` into `t` and the body.
+fn parse_prefix(raw: &str) -> Option<(u8, &str)> {
+    let raw = raw.trim();
+    let rest = raw
+        .get(..PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(PREFIX))
+        .map(|_| &raw[PREFIX.len()..])?;
+    let (corrections, body) = rest.split_once(SEPARATOR)?;
+    Some((corrections.trim().parse().ok()?, body))
+}
+
+/// Raises the generator of the multiplicative group of `Gf256` to the given power.
+fn alpha_pow(power: usize) -> Gf256 {
+    Gf256::exp((power % 255) as u8)
+}
+
+/// Evaluates the polynomial, whose coefficients are given from the highest degree.
+fn poly_eval(poly: &[Gf256], x: Gf256) -> Gf256 {
+    poly.iter().fold(Gf256::zero(), |acc, &c| acc * x + c)
+}
+
+fn poly_mul(p: &[Gf256], q: &[Gf256]) -> Vec<Gf256> {
+    let mut product = vec![Gf256::zero(); p.len() + q.len() - 1];
+    for (i, &a) in p.iter().enumerate() {
+        for (j, &b) in q.iter().enumerate() {
+            product[i + j] += a * b;
+        }
+    }
+    product
+}
+
+/// The generator polynomial of the code, `(x - a^0) (x - a^1) ... (x - a^(nsym-1))`.
+fn generator_poly(nsym: usize) -> Vec<Gf256> {
+    (0..nsym).fold(vec![Gf256::one()], |g, i| {
+        poly_mul(&g, &[Gf256::one(), alpha_pow(i)])
+    })
+}
+
+/// The parity bytes of the given block, the remainder of its division by the
+/// generator polynomial once shifted by as many bytes.
+fn parity(block: &[u8], generator: &[Gf256]) -> Vec<u8> {
+    let nsym = generator.len() - 1;
+    let mut remainder = vec![Gf256::zero(); nsym];
+    for &byte in block {
+        let factor = Gf256::from_byte(byte) + remainder[0];
+        remainder.rotate_left(1);
+        remainder[nsym - 1] = Gf256::zero();
+        for (r, &g) in remainder.iter_mut().zip(&generator[1..]) {
+            *r += g * factor;
+        }
+    }
+    remainder.into_iter().map(Gf256::to_byte).collect()
+}
+
+/// Corrects the given code word in place, whose bytes at the given positions are
+/// missing, with a Berlekamp–Massey decoder.
+fn correct(block: &mut [u8], nsym: usize, erasures: &[usize]) -> Result<()> {
+    if erasures.len() > nsym {
+        bail!(Error::UncorrectableShare);
+    }
+    let word = block
+        .iter()
+        .map(|&byte| Gf256::from_byte(byte))
+        .collect::<Vec<_>>();
+    let syndromes = (0..nsym)
+        .map(|i| poly_eval(&word, alpha_pow(i)))
+        .collect::<Vec<_>>();
+    if syndromes.iter().all(|&s| s == Gf256::zero()) {
+        return Ok(());
+    }
+
+    // The syndromes with the erasures taken out, from which the errors are located.
+    let mut forney_syndromes = syndromes.clone();
+    for &position in erasures {
+        let x = alpha_pow(word.len() - 1 - position);
+        for j in 0..nsym - 1 {
+            forney_syndromes[j] = forney_syndromes[j] * x + forney_syndromes[j + 1];
+        }
+    }
+
+    // Berlekamp–Massey, coefficients from the highest degree.
+    let mut locator = vec![Gf256::one()];
+    let mut old_locator = vec![Gf256::one()];
+    for i in 0..nsym - erasures.len() {
+        let mut delta = forney_syndromes[i];
+        for j in 1..locator.len() {
+            delta += locator[locator.len() - 1 - j] * forney_syndromes[i - j];
+        }
+        old_locator.push(Gf256::zero());
+        if delta != Gf256::zero() {
+            if old_locator.len() > locator.len() {
+                let new_locator = scale(&old_locator, delta);
+                old_locator = scale(&locator, Gf256::one() / delta);
+                locator = new_locator;
+            }
+            let scaled = scale(&old_locator, delta);
+            locator = add(&locator, &scaled);
+        }
+    }
+    let leading_zeros = locator.iter().take_while(|&&c| c == Gf256::zero()).count();
+    locator.drain(..leading_zeros);
+    let error_count = locator.len() - 1;
+    if 2 * error_count + erasures.len() > nsym {
+        bail!(Error::UncorrectableShare);
+    }
+
+    // Chien search, for the roots of the locator.
+    let reversed = locator.iter().rev().copied().collect::<Vec<_>>();
+    let errors = (0..word.len())
+        .filter(|&i| poly_eval(&reversed, alpha_pow(i)) == Gf256::zero())
+        .map(|i| word.len() - 1 - i)
+        .collect::<Vec<_>>();
+    if errors.len() != error_count {
+        bail!(Error::UncorrectableShare);
+    }
+
+    let positions = erasures.iter().chain(&errors).copied().collect::<Vec<_>>();
+    let corrected = correct_errata(&word, &syndromes, &positions);
+    if (0..nsym).any(|i| poly_eval(&corrected, alpha_pow(i)) != Gf256::zero()) {
+        bail!(Error::UncorrectableShare);
+    }
+    for (byte, c) in block.iter_mut().zip(corrected) {
+        *byte = c.to_byte();
+    }
+    Ok(())
+}
+
+/// Computes the values at the given positions with the Forney algorithm.
+fn correct_errata(word: &[Gf256], syndromes: &[Gf256], positions: &[usize]) -> Vec<Gf256> {
+    let xs = positions
+        .iter()
+        .map(|&p| alpha_pow(word.len() - 1 - p))
+        .collect::<Vec<_>>();
+
+    // The errata locator, the product of the `1 - X x`.
+    let locator = xs.iter().fold(vec![Gf256::one()], |loc, &x| {
+        poly_mul(&loc, &[x, Gf256::one()])
+    });
+    // The errata evaluator, the product of the syndromes, shifted by one degree, and
+    // the locator modulo `x^(errata + 1)`.
+    let mut reversed_syndromes = syndromes.iter().rev().copied().collect::<Vec<_>>();
+    reversed_syndromes.push(Gf256::zero());
+    let product = poly_mul(&reversed_syndromes, &locator);
+    let evaluator = &product[product.len().saturating_sub(locator.len())..];
+
+    let mut corrected = word.to_vec();
+    for (i, (&x, &position)) in xs.iter().zip(positions).enumerate() {
+        let x_inv = Gf256::one() / x;
+        let derivative = xs
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(Gf256::one(), |acc, (_, &xj)| {
+                acc * (Gf256::one() + x_inv * xj)
+            });
+        let y = x * poly_eval(evaluator, x_inv);
+        corrected[position] += y / derivative;
+    }
+    corrected
+}
+
+fn scale(poly: &[Gf256], factor: Gf256) -> Vec<Gf256> {
+    poly.iter().map(|&c| c * factor).collect()
+}
+
+/// Adds two polynomials, whose coefficients are given from the highest degree.
+fn add(p: &[Gf256], q: &[Gf256]) -> Vec<Gf256> {
+    let len = p.len().max(q.len());
+    let mut sum = vec![Gf256::zero(); len];
+    for (s, &c) in sum[len - p.len()..].iter_mut().zip(p) {
+        *s += c;
+    }
+    for (s, &c) in sum[len - q.len()..].iter_mut().zip(q) {
+        *s += c;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::proto::SchemeProto;
+    use rand::seq::index::sample;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    fn corrupt(
+        encoded: &str,
+        count: usize,
+        replacement: Option<char>,
+        rng: &mut ChaChaRng,
+    ) -> String {
+        let start = encoded.find(SEPARATOR).unwrap() + 1;
+        let mut chars = encoded.chars().collect::<Vec<_>>();
+        let positions = (start..chars.len())
+            .filter(|&i| chars[i] != ' ')
+            .collect::<Vec<_>>();
+        for i in sample(rng, positions.len(), count) {
+            let c = &mut chars[positions[i]];
+            *c = match replacement {
+                Some(replacement) => replacement,
+                None => loop {
+                    let digit = char::from_digit(rng.random_range(0..16), 16).unwrap();
+                    if digit != c.to_ascii_lowercase() {
+                        break digit;
+                    }
+                },
+            };
+        }
+        chars.into_iter().collect()
+    }
+
+    fn envelope(threshold: u8, id: u8, body: Vec<u8>) -> Envelope {
+        Envelope {
+            scheme: SchemeProto::Sss,
+            threshold,
+            id,
+            body,
+        }
+    }
+
+    #[test]
+    fn corrects_up_to_t_errors() {
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        for (len, t) in [(10, 1), (40, 4), (200, 8), (600, 3)] {
+            let envelope = envelope(2, 5, (0..len).map(|_| rng.random()).collect());
+            let encoded = encode(&envelope, t).unwrap();
+            assert!(is_reed_solomon(&encoded));
+            assert_eq!(decode(&encoded).unwrap(), (envelope.clone(), 0));
+
+            for count in 1..=usize::from(t) {
+                let damaged = corrupt(&encoded, count, None, &mut rng);
+                assert_eq!(decode(&damaged).unwrap(), (envelope.clone(), count));
+            }
+            for count in 1..=2 * usize::from(t) {
+                let damaged = corrupt(&encoded, count, Some(ERASURE), &mut rng);
+                assert_eq!(decode(&damaged).unwrap(), (envelope.clone(), count));
+            }
+        }
+    }
+
+    #[test]
+    fn errors_and_erasures() {
+        let mut rng = ChaChaRng::from_seed([8; 32]);
+        let envelope = envelope(3, 1, (0..50).map(|_| rng.random()).collect());
+        let encoded = encode(&envelope, 6).unwrap();
+        // Corrupts whole bytes, 2 wrong ones and 8 unreadable ones.
+        let mut bytes = encoded.split(':').nth(1).unwrap().replace(' ', "");
+        bytes.replace_range(0..2, "??");
+        for i in [3, 10, 17, 24, 31, 38, 45] {
+            bytes.replace_range(2 * i..2 * i + 2, "??");
+        }
+        let flipped = |c: &str| if c == "00" { "FF" } else { "00" };
+        let (a, b) = (bytes[22..24].to_owned(), bytes[42..44].to_owned());
+        bytes.replace_range(22..24, flipped(&a));
+        bytes.replace_range(42..44, flipped(&b));
+        let (decoded, _) = decode(&format!("rs6:{}", bytes)).unwrap();
+        assert_eq!(decoded, envelope);
+    }
+
+    #[test]
+    fn too_many_errors_are_reported() {
+        let mut rng = ChaChaRng::from_seed([9; 32]);
+        let envelope = envelope(2, 1, (0..30).map(|_| rng.random()).collect());
+        let encoded = encode(&envelope, 2).unwrap();
+        let mut failures = 0;
+        for _ in 0..20 {
+            let damaged = corrupt(&encoded, 12, None, &mut rng);
+            match decode(&damaged) {
+                Err(Error::UncorrectableShare) => failures += 1,
+                // Too many errors may be miscorrected into another code word, which
+                // is seldom a share.
+                Err(Error::ShareParsingError(_)) => {}
+                Err(err) => panic!("Unexpected error: {:?}", err),
+                Ok((decoded, _)) => assert_ne!(decoded, envelope),
+            }
+        }
+        assert!(failures > 0);
+
+        let damaged = corrupt(&encoded, 9, Some(ERASURE), &mut rng);
+        assert!(matches!(decode(&damaged), Err(Error::UncorrectableShare)));
+    }
+
+    #[test]
+    fn invalid_shares() {
+        let envelope = envelope(2, 1, b"data".to_vec());
+        assert!(matches!(
+            encode(&envelope, 0),
+            Err(Error::InvalidCorrectionCount(0))
+        ));
+        let err = encode(&envelope, MAX_CORRECTIONS + 1).unwrap_err();
+        assert!(matches!(err, Error::InvalidCorrectionCount(_)));
+        assert!(err
+            .to_string()
+            .contains(&format!("between 1 and {} ", MAX_CORRECTIONS)));
+        let encoded = encode(&envelope, 2).unwrap();
+        assert!(decode(&encoded.replacen(':', ":X", 1)).is_err());
+        assert!(decode(&encoded[..encoded.len() - 1]).is_err());
+        assert!(decode("RS2:0201").is_err());
+        assert!(!is_reed_solomon("2-1-AgEK"));
+    }
+}
@@ -0,0 +1,1180 @@
+//! A common interface to the secret sharing schemes of this crate.
+//!
+//! Each scheme takes different parameters when splitting a secret. The `Scheme` trait
+//! lets them all be driven by the same `SplitOptions`, so that the scheme to use can
+//! be picked at runtime, from configuration for example.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use etospheres_labs_rusty_secrets::scheme::{Scheme, Signing, SplitOptions};
+//! use etospheres_labs_rusty_secrets::sss::Sss;
+//!
+//! let options = SplitOptions::new(3, 5).signing(Signing::Merkle);
+//! let shares = Sss.split(b"Hello, World!", options).unwrap();
+//!
+//! let secret = Sss.recover(&shares[..3], true).unwrap();
+//! assert_eq!(secret.as_bytes(), b"Hello, World!");
+//! ```
+//!
+//! Shares record the scheme which dealt them, so that `recover_any` can recover a
+//! secret without being told which scheme to use:
+//!
+//! ```rust
+//! use etospheres_labs_rusty_secrets::scheme::{recover_any, RecoveredSecret};
+//! use etospheres_labs_rusty_secrets::wrapped_secrets;
+//!
+//! let shares = wrapped_secrets::split_secret(2, 3, b"Hello, World!", None, false).unwrap();
+//!
+//! match recover_any(&shares[1..], false).unwrap() {
+//!     RecoveredSecret::Wrapped(secret) => assert_eq!(secret.data, b"Hello, World!"[..]),
+//!     other => panic!("Unexpected secret: {:?}", other),
+//! }
+//! ```
+
+use std::fmt;
+use std::time::SystemTime;
+
+use prost::Message;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::armor;
+use crate::compat::sellibitze;
+use crate::encoding::{self, TextEncoding};
+use crate::errors::*;
+use crate::mnemonic;
+use crate::proto::wrapped::SecretProto;
+use crate::proto::{SchemeHeaderProto, SchemeProto};
+use crate::reed_solomon;
+use crate::secret::Secret;
+use crate::share::envelope::Envelope;
+use crate::share::version::{check_version, CURRENT_SHARE_VERSION};
+use crate::sss::{self, Sss};
+use crate::wrapped_secrets::{self, WrappedSecret, WrappedSecrets};
+
+#[cfg(feature = "dss")]
+use crate::dss::random::RngRandom;
+#[cfg(feature = "dss")]
+use crate::dss::ss1::{self, Reproducibility, SS1};
+#[cfg(feature = "dss")]
+use crate::dss::thss::{self, MetaData, ThSS};
+#[cfg(feature = "dss")]
+use crate::dss::AccessStructure;
+#[cfg(feature = "dss")]
+use ring::rand::SystemRandom;
+
+/// The metadata tag under which the dealer-based schemes store the MIME type.
+#[cfg(feature = "dss")]
+const MIME_TYPE_TAG: &str = "mime_type";
+
+/// The schemes implementing `Scheme`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SchemeKind {
+    /// See `sss::Sss`.
+    Sss,
+    /// See `wrapped_secrets::WrappedSecrets`.
+    WrappedSecrets,
+    /// See `dss::thss::ThSS`.
+    #[cfg(feature = "dss")]
+    ThSS,
+    /// See `dss::ss1::SS1`.
+    #[cfg(feature = "dss")]
+    SS1,
+}
+
+/// How shares are signed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Signing {
+    /// Shares are not signed.
+    #[default]
+    None,
+    /// Shares are signed with Lamport one-time signatures, whose public keys are
+    /// authenticated by a Merkle tree. Only supported by `Sss` and `WrappedSecrets`.
+    Merkle,
+}
+
+/// How shares are encoded into strings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// The `k-n-data` format, where `data` is the protobuf message holding the share,
+    /// in the given text encoding.
+    Text(TextEncoding),
+    /// A sequence of words from the English BIP-39 word list, holding the binary
+    /// encoding of the share along with a checksum, which is easier to write down
+    /// on paper. Shares are parsed from either format.
+    Mnemonic,
+    /// An ASCII-armored block holding the binary encoding of the share, in
+    /// base64 wrapped at 64 columns and followed by a CRC-24 checksum, with headers
+    /// naming the threshold, the share, the scheme, and the deal. The shares of a
+    /// deal share a random `Deal-Id`. Shares are parsed from any format.
+    Armor,
+    /// The binary encoding of the share in hexadecimal, protected by
+    /// Reed–Solomon parity bytes correcting up to the given number of corrupted
+    /// characters, see `reed_solomon`.
+    ReedSolomon(u8),
+}
+
+impl Default for Encoding {
+    /// The `k-n-data` format, with `data` in standard base64.
+    fn default() -> Self {
+        Encoding::Text(TextEncoding::Base64)
+    }
+}
+
+/// The parameters used to split a secret, given to `Scheme::split`.
+///
+/// Options which do not apply to the scheme used, such as a MIME type with `Sss`,
+/// are reported as `Error::UnsupportedOption`.
+pub struct SplitOptions<'a> {
+    threshold: u8,
+    shares_count: u8,
+    signing: Signing,
+    mime_type: Option<String>,
+    filename: Option<String>,
+    created_at: Option<SystemTime>,
+    labels: Vec<String>,
+    #[cfg(feature = "dss")]
+    metadata: Option<MetaData>,
+    #[cfg(feature = "dss")]
+    reproducibility: Option<Reproducibility>,
+    rng: Option<&'a mut dyn CryptoRng>,
+    encoding: Encoding,
+}
+
+impl<'a> SplitOptions<'a> {
+    /// Splits a secret into `shares_count` shares, `threshold` of which are needed
+    /// to recover it.
+    ///
+    /// Shares are not signed, and are generated with the thread-local random number
+    /// generator of `rand`.
+    pub fn new(threshold: u8, shares_count: u8) -> Self {
+        SplitOptions {
+            threshold,
+            shares_count,
+            signing: Signing::default(),
+            mime_type: None,
+            filename: None,
+            created_at: None,
+            labels: Vec::new(),
+            #[cfg(feature = "dss")]
+            metadata: None,
+            #[cfg(feature = "dss")]
+            reproducibility: None,
+            rng: None,
+            encoding: Encoding::default(),
+        }
+    }
+
+    /// Signs the shares with the given backend.
+    pub fn signing(mut self, signing: Signing) -> Self {
+        self.signing = signing;
+        self
+    }
+
+    /// Attaches the MIME type of the secret to the shares.
+    ///
+    /// `ThSS` and `SS1` store it in the metadata of the shares, under the `mime_type` tag.
+    pub fn mime_type<S: Into<String>>(mut self, mime_type: S) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Records the name of the file holding the secret. Only supported by
+    /// `WrappedSecrets`.
+    pub fn filename<S: Into<String>>(mut self, filename: S) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Records when the secret was split, to the second. Only supported by
+    /// `WrappedSecrets`.
+    pub fn created_at(mut self, created_at: SystemTime) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    /// Adds a free-form label describing the secret. Only supported by
+    /// `WrappedSecrets`.
+    pub fn label<S: Into<String>>(mut self, label: S) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    /// Attaches the given metadata to the shares. Only supported by `ThSS` and `SS1`.
+    #[cfg(feature = "dss")]
+    pub fn metadata(mut self, metadata: MetaData) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Sets how reproducible the shares are. Only supported by `SS1`, which
+    /// defaults to `Reproducibility::None`.
+    #[cfg(feature = "dss")]
+    pub fn reproducibility(mut self, reproducibility: Reproducibility) -> Self {
+        self.reproducibility = Some(reproducibility);
+        self
+    }
+
+    /// Generates the shares with the given random number generator.
+    ///
+    /// `SS1` only draws from it when its shares are not reproducible.
+    pub fn rng<R: CryptoRng>(mut self, rng: &'a mut R) -> Self {
+        self.rng = Some(rng);
+        self
+    }
+
+    /// Encodes the shares with the given encoding.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    #[cfg(feature = "dss")]
+    fn unsupported_signing(&self) -> Result<()> {
+        if self.signing != Signing::None {
+            bail!(Error::UnsupportedOption("signing"));
+        }
+        Ok(())
+    }
+
+    fn unsupported_mime_type(&self) -> Result<()> {
+        if self.mime_type.is_some() {
+            bail!(Error::UnsupportedOption("MIME type"));
+        }
+        Ok(())
+    }
+
+    fn unsupported_wrapping(&self) -> Result<()> {
+        if self.filename.is_some() {
+            bail!(Error::UnsupportedOption("filename"));
+        }
+        if self.created_at.is_some() {
+            bail!(Error::UnsupportedOption("creation time"));
+        }
+        if !self.labels.is_empty() {
+            bail!(Error::UnsupportedOption("labels"));
+        }
+        Ok(())
+    }
+
+    fn unsupported_metadata(&self) -> Result<()> {
+        #[cfg(feature = "dss")]
+        if self.metadata.is_some() {
+            bail!(Error::UnsupportedOption("metadata"));
+        }
+        Ok(())
+    }
+
+    fn unsupported_reproducibility(&self) -> Result<()> {
+        #[cfg(feature = "dss")]
+        if self.reproducibility.is_some() {
+            bail!(Error::UnsupportedOption("reproducibility"));
+        }
+        Ok(())
+    }
+
+    /// Merges the MIME type, if any, into the metadata.
+    #[cfg(feature = "dss")]
+    fn take_metadata(&mut self) -> Option<MetaData> {
+        match self.mime_type.take() {
+            None => self.metadata.take(),
+            Some(mime_type) => {
+                let mut metadata = self.metadata.take().unwrap_or_default();
+                metadata.tags.insert(MIME_TYPE_TAG.to_string(), mime_type);
+                Some(metadata)
+            }
+        }
+    }
+
+    fn encode<S>(&mut self, shares: Vec<S>, envelope: fn(&S) -> Envelope) -> Result<Vec<String>> {
+        let deal_id = match self.encoding {
+            Encoding::Armor => Some(self.deal_id()),
+            _ => None,
+        };
+        shares
+            .iter()
+            .map(|share| {
+                let envelope = envelope(share);
+                Ok(match self.encoding {
+                    Encoding::Text(text_encoding) => envelope.to_text(text_encoding),
+                    Encoding::Mnemonic => mnemonic::encode(&envelope),
+                    Encoding::Armor => armor::encode(&envelope, deal_id.as_deref()),
+                    Encoding::ReedSolomon(corrections) => {
+                        reed_solomon::encode(&envelope, corrections)?
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// A random identifier common to the armored shares of a deal.
+    fn deal_id(&mut self) -> String {
+        let mut id = [0; 8];
+        match self.rng {
+            Some(ref mut rng) => rng.fill_bytes(&mut id),
+            None => rand::rng().fill_bytes(&mut id),
+        }
+        id.iter().map(|byte| format!("{:02X}", byte)).collect()
+    }
+}
+
+impl fmt::Debug for SplitOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("SplitOptions");
+        debug
+            .field("threshold", &self.threshold)
+            .field("shares_count", &self.shares_count)
+            .field("signing", &self.signing)
+            .field("mime_type", &self.mime_type)
+            .field("filename", &self.filename)
+            .field("created_at", &self.created_at)
+            .field("labels", &self.labels);
+        #[cfg(feature = "dss")]
+        debug
+            .field("metadata", &self.metadata)
+            .field("reproducibility", &self.reproducibility);
+        debug
+            .field("rng", &self.rng.as_ref().map(|_| ".."))
+            .field("encoding", &self.encoding)
+            .finish()
+    }
+}
+
+/// A secret sharing scheme, which splits secrets into shares encoded as strings,
+/// and recovers them from such shares.
+pub trait Scheme {
+    /// Identifies this scheme.
+    const KIND: SchemeKind;
+
+    /// What recovering a secret yields.
+    type Recovered;
+
+    /// Splits the given secret according to the given options.
+    fn split(&self, secret: &[u8], options: SplitOptions<'_>) -> Result<Vec<String>>;
+
+    /// Recovers the secret from the given shares, checking their signatures if
+    /// `verify_signatures` is `true`, which only `Sss` and `WrappedSecrets` support.
+    fn recover(&self, shares: &[String], verify_signatures: bool) -> Result<Self::Recovered>;
+}
+
+impl Scheme for Sss {
+    const KIND: SchemeKind = SchemeKind::Sss;
+    type Recovered = Secret;
+
+    fn split(&self, secret: &[u8], mut options: SplitOptions<'_>) -> Result<Vec<String>> {
+        options.unsupported_mime_type()?;
+        options.unsupported_wrapping()?;
+        options.unsupported_metadata()?;
+        options.unsupported_reproducibility()?;
+
+        let (k, n) = (options.threshold, options.shares_count);
+        let sign_shares = options.signing == Signing::Merkle;
+        let shares = match options.rng {
+            Some(ref mut rng) => self.split_secret(rng, k, n, secret, sign_shares)?,
+            None => self.split_secret(&mut rand::rng(), k, n, secret, sign_shares)?,
+        };
+        options.encode(shares, sss::Share::envelope)
+    }
+
+    fn recover(&self, shares: &[String], verify_signatures: bool) -> Result<Secret> {
+        sss::recover_secret(shares, verify_signatures)
+    }
+}
+
+impl Scheme for WrappedSecrets {
+    const KIND: SchemeKind = SchemeKind::WrappedSecrets;
+    type Recovered = WrappedSecret;
+
+    fn split(&self, secret: &[u8], mut options: SplitOptions<'_>) -> Result<Vec<String>> {
+        options.unsupported_metadata()?;
+        options.unsupported_reproducibility()?;
+
+        let (k, n) = (options.threshold, options.shares_count);
+        let sign_shares = options.signing == Signing::Merkle;
+        let wrapped = SecretProto {
+            secret: secret.to_owned(),
+            mime_type: options.mime_type.take().unwrap_or_default(),
+            filename: options.filename.take().unwrap_or_default(),
+            created_at: options.created_at.map_or(0, wrapped_secrets::unix_time),
+            labels: std::mem::take(&mut options.labels),
+            ..Default::default()
+        };
+        let shares = match options.rng {
+            Some(ref mut rng) => self.split_wrapped(rng, k, n, wrapped, sign_shares)?,
+            None => self.split_wrapped(&mut rand::rng(), k, n, wrapped, sign_shares)?,
+        };
+        options.encode(shares, sss::Share::envelope)
+    }
+
+    fn recover(&self, shares: &[String], verify_signatures: bool) -> Result<WrappedSecret> {
+        wrapped_secrets::recover_secret(shares, verify_signatures)
+    }
+}
+
+#[cfg(feature = "dss")]
+impl Scheme for ThSS {
+    const KIND: SchemeKind = SchemeKind::ThSS;
+    type Recovered = (Secret, AccessStructure, Option<MetaData>);
+
+    fn split(&self, secret: &[u8], mut options: SplitOptions<'_>) -> Result<Vec<String>> {
+        options.unsupported_signing()?;
+        options.unsupported_wrapping()?;
+        options.unsupported_reproducibility()?;
+
+        let (k, n) = (options.threshold, options.shares_count);
+        let metadata = options.take_metadata();
+        let shares = match options.rng {
+            Some(ref mut rng) => {
+                ThSS::split_secret_with(&RngRandom::new(&mut **rng), k, n, secret, &metadata)?
+            }
+            None => self.split_secret(k, n, secret, &metadata)?,
+        };
+        options.encode(shares, thss::Share::envelope)
+    }
+
+    fn recover(&self, shares: &[String], verify_signatures: bool) -> Result<Self::Recovered> {
+        if verify_signatures {
+            bail!(Error::UnsupportedOption("signature verification"));
+        }
+        let shares = shares
+            .iter()
+            .map(|raw| thss::Share::from_string(raw))
+            .collect::<Result<Vec<_>>>()?;
+        self.recover_secret(&shares)
+    }
+}
+
+#[cfg(feature = "dss")]
+impl Scheme for SS1 {
+    const KIND: SchemeKind = SchemeKind::SS1;
+    type Recovered = (Secret, AccessStructure, Option<MetaData>);
+
+    fn split(&self, secret: &[u8], mut options: SplitOptions<'_>) -> Result<Vec<String>> {
+        options.unsupported_signing()?;
+        options.unsupported_wrapping()?;
+
+        let (k, n) = (options.threshold, options.shares_count);
+        let metadata = options.take_metadata();
+        let reproducibility = options
+            .reproducibility
+            .take()
+            .unwrap_or_else(Reproducibility::none);
+        let shares = match options.rng {
+            Some(ref mut rng) => self.split_secret_with(
+                &RngRandom::new(&mut **rng),
+                k,
+                n,
+                secret,
+                reproducibility,
+                &metadata,
+            )?,
+            None => self.split_secret_with(
+                &SystemRandom::new(),
+                k,
+                n,
+                secret,
+                reproducibility,
+                &metadata,
+            )?,
+        };
+        options.encode(shares, ss1::Share::envelope)
+    }
+
+    fn recover(&self, shares: &[String], verify_signatures: bool) -> Result<Self::Recovered> {
+        if verify_signatures {
+            bail!(Error::UnsupportedOption("signature verification"));
+        }
+        let shares = shares
+            .iter()
+            .map(|raw| ss1::Share::from_string(raw))
+            .collect::<Result<Vec<_>>>()?;
+        self.recover_secret(&shares)
+    }
+}
+
+/// A secret recovered by `recover_any`, whose shape depends on the scheme of the shares.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RecoveredSecret {
+    /// A secret recovered from `Sss` shares.
+    Raw(Secret),
+    /// A secret recovered from `WrappedSecrets` shares, along with its MIME type
+    /// and the rest of the information it was wrapped with.
+    Wrapped(WrappedSecret),
+    /// A secret recovered from `ThSS` or `SS1` shares.
+    #[cfg(feature = "dss")]
+    Dss {
+        /// The secret itself.
+        secret: Secret,
+        /// The threshold and number of shares the secret was split with.
+        access_structure: AccessStructure,
+        /// The metadata held by the shares.
+        metadata: Option<MetaData>,
+    },
+}
+
+impl RecoveredSecret {
+    /// Returns the bytes of the secret, whatever the scheme it was recovered with.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            RecoveredSecret::Raw(secret) => secret.as_bytes(),
+            RecoveredSecret::Wrapped(secret) => secret.data.as_bytes(),
+            #[cfg(feature = "dss")]
+            RecoveredSecret::Dss { secret, .. } => secret.as_bytes(),
+        }
+    }
+}
+
+/// Detects the scheme which dealt the given share.
+///
+/// Shares dealt before shares recorded their scheme are told apart by the shape of
+/// their protobuf message. `WrappedSecrets` shares cannot be told apart from `Sss`
+/// ones this way, and are reported as `Sss` shares.
+///
+/// Shares of `secretshare`, whose data is not a protobuf message, are reported as
+/// `Sss` shares, even when their data happens to decode as one which records no
+/// scheme, unless it holds a `ThSS` or `SS1` share matching the threshold and the
+/// identifier of the share. Data recording an unsupported version or scheme is
+/// reported as such.
+pub fn detect_scheme(raw: &str) -> Result<SchemeKind> {
+    let is_base64 = !armor::is_armored(raw)
+        && !reed_solomon::is_reed_solomon(raw)
+        && !mnemonic::is_mnemonic(raw);
+    let envelope = encoding::decode(raw)?;
+    // See `compat::sellibitze`.
+    let may_be_legacy = is_base64 && sellibitze::may_be_legacy_data(&envelope.body);
+
+    match detect_recorded_scheme(envelope.threshold, envelope.id, &envelope.body) {
+        Ok(SchemeKind::WrappedSecrets) | Err(Error::ShareParsingErrorProtobuf(_))
+            if may_be_legacy =>
+        {
+            Ok(SchemeKind::Sss)
+        }
+        result => result,
+    }
+}
+
+/// Detects the scheme recorded in the protobuf message of a share, or told by its
+/// shape for shares dealt before it was recorded.
+fn detect_recorded_scheme(k: u8, i: u8, data: &[u8]) -> Result<SchemeKind> {
+    let header = SchemeHeaderProto::decode(data).map_err(Error::ShareParsingErrorProtobuf)?;
+    check_version(
+        header.version,
+        CURRENT_SHARE_VERSION,
+        Error::UnsupportedShareVersion,
+    )?;
+
+    match SchemeProto::try_from(header.scheme) {
+        Ok(SchemeProto::Sss) => Ok(SchemeKind::Sss),
+        Ok(SchemeProto::WrappedSecrets) => Ok(SchemeKind::WrappedSecrets),
+        #[cfg(feature = "dss")]
+        Ok(SchemeProto::Thss) if decode_dss_share(k, i, data).is_some() => Ok(SchemeKind::ThSS),
+        #[cfg(feature = "dss")]
+        Ok(SchemeProto::Ss1) if decode_dss_share(k, i, data).is_some() => Ok(SchemeKind::SS1),
+        #[cfg(feature = "dss")]
+        Ok(SchemeProto::Thss) | Ok(SchemeProto::Ss1) => bail!(Error::ShareParsingError(
+            "The share does not match its threshold and identifier.".to_owned()
+        )),
+        Ok(SchemeProto::UnspecifiedScheme) => Ok(detect_legacy_scheme(k, i, data)),
+        _ => bail!(Error::ShareParsingError(format!(
+            "Unsupported scheme: {}.",
+            header.scheme
+        ))),
+    }
+}
+
+/// The messages of `ThSS` and `SS1` shares start with a varint field, where those of
+/// `Sss` shares start with a length-delimited one, so they only decode as their own.
+#[cfg_attr(not(feature = "dss"), allow(unused_variables))]
+fn detect_legacy_scheme(k: u8, i: u8, data: &[u8]) -> SchemeKind {
+    #[cfg(feature = "dss")]
+    if let Some(share) = decode_dss_share(k, i, data) {
+        return if share.hash.is_empty() {
+            SchemeKind::ThSS
+        } else {
+            SchemeKind::SS1
+        };
+    }
+    SchemeKind::Sss
+}
+
+/// Decodes the message of a `ThSS` or `SS1` share. Since the data of shares of
+/// `secretshare` may happen to decode as one, it must also hold the threshold and
+/// the identifier of the share, and some data.
+#[cfg(feature = "dss")]
+fn decode_dss_share(k: u8, i: u8, data: &[u8]) -> Option<crate::proto::dss::ShareProto> {
+    crate::proto::dss::ShareProto::decode(data)
+        .ok()
+        .filter(|share| {
+            share.threshold == u32::from(k)
+                && share.id == u32::from(i)
+                && share.shares_count >= share.threshold
+                && !share.data.is_empty()
+        })
+}
+
+/// Recovers the secret from the given shares, whichever scheme dealt them.
+///
+/// As with `Scheme::recover`, signatures are only verified if `verify_signatures`
+/// is set, in which case every share must be signed. Only `Sss` and
+/// `WrappedSecrets` shares can be signed: asking to verify the signatures of
+/// `ThSS` or `SS1` shares fails with `UnsupportedOption`.
+pub fn recover_any(shares: &[String], verify_signatures: bool) -> Result<RecoveredSecret> {
+    let kind = match shares.first() {
+        None => bail!(Error::EmptyShares),
+        Some(share) => detect_scheme(share)?,
+    };
+    for share in &shares[1..] {
+        if detect_scheme(share)? != kind {
+            bail!(Error::InconsistentSchemes);
+        }
+    }
+
+    match kind {
+        SchemeKind::Sss => {
+            let shares = sss::Share::parse_all(shares, true)?;
+            Sss::recover_secret(&shares, verify_signatures).map(RecoveredSecret::Raw)
+        }
+        SchemeKind::WrappedSecrets => {
+            let shares = sss::Share::parse_all(shares, true)?;
+            WrappedSecrets::recover_secret(&shares, verify_signatures).map(RecoveredSecret::Wrapped)
+        }
+        #[cfg(feature = "dss")]
+        SchemeKind::ThSS => ThSS::default()
+            .recover(shares, verify_signatures)
+            .map(RecoveredSecret::from),
+        #[cfg(feature = "dss")]
+        SchemeKind::SS1 => SS1::default()
+            .recover(shares, verify_signatures)
+            .map(RecoveredSecret::from),
+    }
+}
+
+#[cfg(feature = "dss")]
+impl From<(Secret, AccessStructure, Option<MetaData>)> for RecoveredSecret {
+    fn from(
+        (secret, access_structure, metadata): (Secret, AccessStructure, Option<MetaData>),
+    ) -> Self {
+        RecoveredSecret::Dss {
+            secret,
+            access_structure,
+            metadata,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    const SECRET: &[u8] = b"Hello, World!";
+
+    /// Splits and recovers `SECRET` with the given scheme, through the trait only.
+    fn roundtrip<S: Scheme>(scheme: &S, options: SplitOptions<'_>) -> S::Recovered {
+        let shares = scheme.split(SECRET, options).unwrap();
+        assert_eq!(shares.len(), 5);
+        scheme.recover(&shares[2..], false).unwrap()
+    }
+
+    #[test]
+    fn split_then_recover_with_every_scheme() {
+        let secret = roundtrip(&Sss, SplitOptions::new(3, 5));
+        assert_eq!(secret, SECRET);
+
+        let secret = roundtrip(
+            &WrappedSecrets,
+            SplitOptions::new(3, 5).mime_type("text/plain"),
+        );
+        assert_eq!(secret.data, SECRET);
+        assert_eq!(secret.mime_type(), Some(mime::TEXT_PLAIN));
+    }
+
+    #[test]
+    fn wrapped_secrets_keep_their_information() {
+        let created_at = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let options = SplitOptions::new(3, 5)
+            .mime_type("application/pdf")
+            .filename("will.pdf")
+            .created_at(created_at)
+            .label("family")
+            .label("legal");
+        let secret = roundtrip(&WrappedSecrets, options);
+        assert_eq!(secret.data, SECRET);
+        assert_eq!(secret.mime_type(), Some(mime::APPLICATION_PDF));
+        assert_eq!(secret.version, 0);
+        assert_eq!(secret.created_at, Some(created_at));
+        assert_eq!(secret.filename.as_deref(), Some("will.pdf"));
+        assert_eq!(secret.labels, ["family", "legal"]);
+
+        let secret = roundtrip(&WrappedSecrets, SplitOptions::new(3, 5));
+        assert_eq!(secret.mime_type(), None);
+        assert_eq!(secret.created_at, None);
+        assert_eq!(secret.filename, None);
+        assert!(secret.labels.is_empty());
+
+        let options = SplitOptions::new(2, 3).mime_type("plain text");
+        let err = WrappedSecrets.split(SECRET, options).unwrap_err();
+        assert!(matches!(err, Error::InvalidMimeType(ref mime_type) if mime_type == "plain text"));
+    }
+
+    #[test]
+    #[cfg(feature = "dss")]
+    fn split_then_recover_with_every_dss_scheme() {
+        let mut metadata = MetaData::new();
+        metadata
+            .tags
+            .insert("owner".to_string(), "alice".to_string());
+
+        let options = SplitOptions::new(3, 5)
+            .metadata(metadata.clone())
+            .mime_type("text/plain");
+        let (secret, access, recovered) = roundtrip(&ThSS::default(), options);
+        assert_eq!(secret, SECRET);
+        assert_eq!((access.threshold, access.shares_count), (3, 5));
+        let recovered = recovered.unwrap();
+        assert_eq!(recovered.tags["owner"], "alice");
+        assert_eq!(recovered.tags[MIME_TYPE_TAG], "text/plain");
+
+        let options = SplitOptions::new(3, 5).metadata(metadata.clone());
+        let (secret, _, recovered) = roundtrip(&SS1::default(), options);
+        assert_eq!(secret, SECRET);
+        assert_eq!(recovered, Some(metadata));
+    }
+
+    #[test]
+    fn signed_shares_are_verified() {
+        let shares = Sss
+            .split(SECRET, SplitOptions::new(2, 3).signing(Signing::Merkle))
+            .unwrap();
+        assert_eq!(Sss.recover(&shares[..2], true).unwrap(), SECRET);
+
+        let shares = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+        assert!(matches!(
+            Sss.recover(&shares[..2], true),
+            Err(Error::MissingSignature(_))
+        ));
+    }
+
+    #[test]
+    fn seeded_rng_yields_the_same_shares() {
+        fn split<S: Scheme>(scheme: &S) -> Vec<String> {
+            let mut rng = ChaChaRng::from_seed([42; 32]);
+            scheme
+                .split(SECRET, SplitOptions::new(3, 5).rng(&mut rng))
+                .unwrap()
+        }
+
+        assert_eq!(split(&Sss), split(&Sss));
+        assert_eq!(split(&WrappedSecrets), split(&WrappedSecrets));
+        #[cfg(feature = "dss")]
+        {
+            assert_eq!(split(&ThSS::default()), split(&ThSS::default()));
+            assert_eq!(split(&SS1::default()), split(&SS1::default()));
+        }
+    }
+
+    #[test]
+    fn unsupported_options_are_rejected() {
+        let err = Sss
+            .split(SECRET, SplitOptions::new(3, 5).mime_type("text/plain"))
+            .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedOption("MIME type")));
+
+        let err = Sss
+            .split(SECRET, SplitOptions::new(3, 5).label("backup"))
+            .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedOption("labels")));
+
+        #[cfg(feature = "dss")]
+        {
+            let options = SplitOptions::new(3, 5).reproducibility(Reproducibility::reproducible());
+            let err = WrappedSecrets.split(SECRET, options).unwrap_err();
+            assert!(matches!(err, Error::UnsupportedOption("reproducibility")));
+
+            let options = SplitOptions::new(3, 5).signing(Signing::Merkle);
+            let err = ThSS::default().split(SECRET, options).unwrap_err();
+            assert!(matches!(err, Error::UnsupportedOption("signing")));
+
+            let shares = SS1::default()
+                .split(SECRET, SplitOptions::new(3, 5))
+                .unwrap();
+            let err = SS1::default().recover(&shares, true).unwrap_err();
+            assert!(matches!(err, Error::UnsupportedOption(_)));
+        }
+    }
+
+    #[test]
+    fn mnemonic_shares_are_parsed_back() {
+        fn split<S: Scheme>(scheme: &S, options: SplitOptions<'_>) -> Vec<String> {
+            let shares = scheme
+                .split(SECRET, options.encoding(Encoding::Mnemonic))
+                .unwrap();
+            assert!(shares.iter().all(|share| share.split(' ').count() > 10));
+            shares
+        }
+
+        let shares = split(&Sss, SplitOptions::new(2, 3).signing(Signing::Merkle));
+        assert_eq!(Sss.recover(&shares[1..], true).unwrap(), SECRET);
+        assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::Sss);
+
+        let shares = split(&WrappedSecrets, SplitOptions::new(2, 3));
+        assert_eq!(recover_any(&shares[..2], false).unwrap().as_bytes(), SECRET);
+
+        #[cfg(feature = "dss")]
+        {
+            let shares = split(&ThSS::default(), SplitOptions::new(2, 3));
+            assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::ThSS);
+            assert_eq!(recover_any(&shares[1..], false).unwrap().as_bytes(), SECRET);
+
+            let shares = split(&SS1::default(), SplitOptions::new(2, 3));
+            assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::SS1);
+            let (secret, ..) = SS1::default().recover(&shares[1..], false).unwrap();
+            assert_eq!(secret, SECRET);
+        }
+    }
+
+    #[test]
+    fn armored_shares_are_parsed_back() {
+        fn split<S: Scheme>(scheme: &S, options: SplitOptions<'_>) -> Vec<String> {
+            let shares = scheme
+                .split(SECRET, options.encoding(Encoding::Armor))
+                .unwrap();
+            let deal_id = |share: &String| {
+                share
+                    .lines()
+                    .find(|line| line.starts_with("Deal-Id: "))
+                    .map(str::to_owned)
+            };
+            assert!(deal_id(&shares[0]).is_some());
+            assert!(shares
+                .iter()
+                .all(|share| deal_id(share) == deal_id(&shares[0])));
+            shares
+        }
+
+        let shares = split(&Sss, SplitOptions::new(2, 3).signing(Signing::Merkle));
+        assert!(shares[0].contains("\nScheme: Sss\n"));
+        assert_eq!(Sss.recover(&shares[1..], true).unwrap(), SECRET);
+        assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::Sss);
+
+        let shares = split(&WrappedSecrets, SplitOptions::new(2, 3));
+        assert_eq!(recover_any(&shares[..2], false).unwrap().as_bytes(), SECRET);
+
+        #[cfg(feature = "dss")]
+        {
+            let shares = split(&ThSS::default(), SplitOptions::new(2, 3));
+            assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::ThSS);
+            assert_eq!(recover_any(&shares[1..], false).unwrap().as_bytes(), SECRET);
+
+            let shares = split(&SS1::default(), SplitOptions::new(2, 3));
+            assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::SS1);
+            let (secret, ..) = SS1::default().recover(&shares[1..], false).unwrap();
+            assert_eq!(secret, SECRET);
+        }
+    }
+
+    #[test]
+    fn text_encodings_are_detected() {
+        for (encoding, marker) in [
+            (TextEncoding::Base64Url, "b64u."),
+            (TextEncoding::Base32, "b32."),
+            (TextEncoding::Hex, "hex."),
+        ] {
+            let options = SplitOptions::new(2, 3)
+                .signing(Signing::Merkle)
+                .encoding(Encoding::Text(encoding));
+            let shares = Sss.split(SECRET, options).unwrap();
+            assert!(shares.iter().all(|share| share.contains(marker)));
+            assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::Sss);
+            assert_eq!(Sss.recover(&shares[1..], true).unwrap(), SECRET);
+
+            let share = sss::Share::from_string(&shares[0], true).unwrap();
+            let base64 = share.clone().into_string();
+            assert!(!base64.contains('.'));
+            assert_eq!(sss::Share::from_string(&base64, true).unwrap(), share);
+
+            #[cfg(feature = "dss")]
+            {
+                let options = SplitOptions::new(2, 3).encoding(Encoding::Text(encoding));
+                let shares = ThSS::default().split(SECRET, options).unwrap();
+                assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::ThSS);
+                assert_eq!(recover_any(&shares[1..], false).unwrap().as_bytes(), SECRET);
+
+                let options = SplitOptions::new(2, 3).encoding(Encoding::Text(encoding));
+                let shares = SS1::default().split(SECRET, options).unwrap();
+                assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::SS1);
+                let (secret, ..) = SS1::default().recover(&shares[1..], false).unwrap();
+                assert_eq!(secret, SECRET);
+            }
+        }
+
+        // Shares in different encodings are recovered together.
+        let shares = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+        let hex = sss::Share::from_string(&shares[0], false)
+            .unwrap()
+            .into_string_with(TextEncoding::Hex);
+        assert!(hex.starts_with("2-1-hex."));
+        let mixed = vec![hex.to_lowercase(), shares[1].clone()];
+        assert_eq!(recover_any(&mixed, false).unwrap().as_bytes(), SECRET);
+    }
+
+    #[test]
+    fn damaged_reed_solomon_shares_are_corrected() {
+        // Replaces the second and the fifth group of digits.
+        fn damage(share: &str) -> String {
+            let mut groups = share.split(' ').map(str::to_owned).collect::<Vec<_>>();
+            groups[1] = "????".to_owned();
+            groups[4] = if groups[4] == "0000" { "FFFF" } else { "0000" }.to_owned();
+            groups.join(" ")
+        }
+
+        let options = SplitOptions::new(2, 3).encoding(Encoding::ReedSolomon(4));
+        let shares = Sss.split(SECRET, options).unwrap();
+        assert!(shares.iter().all(|share| share.starts_with("RS4:")));
+        let damaged = shares.iter().map(|share| damage(share)).collect::<Vec<_>>();
+        assert_eq!(detect_scheme(&damaged[0]).unwrap(), SchemeKind::Sss);
+        assert_eq!(
+            recover_any(&damaged[1..], false).unwrap().as_bytes(),
+            SECRET
+        );
+        let corrected = reed_solomon::decode_share(&damaged[0]).unwrap();
+        assert!((5..=8).contains(&corrected.corrections));
+
+        let options = SplitOptions::new(2, 3).encoding(Encoding::ReedSolomon(0));
+        let err = Sss.split(SECRET, options).unwrap_err();
+        assert!(matches!(err, Error::InvalidCorrectionCount(0)));
+
+        #[cfg(feature = "dss")]
+        {
+            let options = SplitOptions::new(2, 3).encoding(Encoding::ReedSolomon(4));
+            let shares = ThSS::default().split(SECRET, options).unwrap();
+            let damaged = shares.iter().map(|share| damage(share)).collect::<Vec<_>>();
+            assert_eq!(detect_scheme(&damaged[0]).unwrap(), SchemeKind::ThSS);
+            assert_eq!(
+                recover_any(&damaged[..2], false).unwrap().as_bytes(),
+                SECRET
+            );
+        }
+    }
+
+    #[test]
+    fn mnemonic_and_base64_shares_can_be_mixed() {
+        let shares = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+        let mnemonic = sss::Share::from_string(&shares[0], false)
+            .unwrap()
+            .into_mnemonic();
+        let mixed = vec![mnemonic, shares[1].clone()];
+        assert_eq!(recover_any(&mixed, false).unwrap().as_bytes(), SECRET);
+    }
+
+    #[test]
+    fn recover_any_detects_the_scheme() {
+        let shares = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+        assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::Sss);
+        let recovered = recover_any(&shares[1..], false).unwrap();
+        assert!(matches!(recovered, RecoveredSecret::Raw(_)));
+        assert_eq!(recovered.as_bytes(), SECRET);
+
+        let options = SplitOptions::new(2, 3).mime_type("text/plain");
+        let shares = WrappedSecrets.split(SECRET, options).unwrap();
+        assert_eq!(
+            detect_scheme(&shares[0]).unwrap(),
+            SchemeKind::WrappedSecrets
+        );
+        match recover_any(&shares[1..], false).unwrap() {
+            RecoveredSecret::Wrapped(secret) => {
+                assert_eq!(secret.mime_type(), Some(mime::TEXT_PLAIN))
+            }
+            other => panic!("Unexpected secret: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unsupported_headers_are_not_taken_for_legacy_shares() {
+        let newer = crate::proto::wrapped::ShareProto {
+            version: CURRENT_SHARE_VERSION as i32 + 1,
+            ..Default::default()
+        };
+        let share = encoding::to_text(2, 1, &newer.encode_to_vec(), TextEncoding::Base64);
+        assert!(matches!(
+            detect_scheme(&share),
+            Err(Error::UnsupportedShareVersion(1))
+        ));
+
+        let unknown = crate::proto::wrapped::ShareProto {
+            scheme: 42,
+            ..Default::default()
+        };
+        let share = encoding::to_text(2, 1, &unknown.encode_to_vec(), TextEncoding::Base64);
+        assert!(matches!(
+            detect_scheme(&share),
+            Err(Error::ShareParsingError(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "dss")]
+    fn recover_any_detects_dss_schemes() {
+        let shares = ThSS::default()
+            .split(SECRET, SplitOptions::new(2, 3))
+            .unwrap();
+        assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::ThSS);
+        match recover_any(&shares[1..], false).unwrap() {
+            RecoveredSecret::Dss {
+                secret,
+                access_structure,
+                ..
+            } => {
+                assert_eq!(secret, SECRET);
+                assert_eq!(access_structure.shares_count, 3);
+            }
+            other => panic!("Unexpected secret: {:?}", other),
+        }
+
+        let shares = SS1::default()
+            .split(SECRET, SplitOptions::new(2, 3))
+            .unwrap();
+        assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::SS1);
+        assert_eq!(recover_any(&shares[1..], false).unwrap().as_bytes(), SECRET);
+    }
+
+    #[test]
+    fn recover_any_verifies_signatures_when_asked() {
+        let options = SplitOptions::new(2, 3).signing(Signing::Merkle);
+        let mut shares = Sss.split(SECRET, options).unwrap();
+        assert_eq!(recover_any(&shares[..2], true).unwrap().as_bytes(), SECRET);
+
+        shares[1] = Sss
+            .split(SECRET, SplitOptions::new(2, 3))
+            .unwrap()
+            .remove(1);
+        assert!(matches!(
+            recover_any(&shares[..2], true),
+            Err(Error::MissingSignature(2))
+        ));
+
+        // Unsigned shares are not accepted when signatures must be verified, even
+        // if none of them is signed.
+        let shares = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+        assert!(matches!(
+            recover_any(&shares[..2], true),
+            Err(Error::MissingSignature(_))
+        ));
+        assert_eq!(recover_any(&shares[..2], false).unwrap().as_bytes(), SECRET);
+
+        #[cfg(feature = "dss")]
+        {
+            let shares = ThSS::default()
+                .split(SECRET, SplitOptions::new(2, 3))
+                .unwrap();
+            assert!(matches!(
+                recover_any(&shares[..2], true),
+                Err(Error::UnsupportedOption(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn recover_any_rejects_mixed_schemes() {
+        let mut shares = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+        shares[1] = WrappedSecrets
+            .split(SECRET, SplitOptions::new(2, 3))
+            .unwrap()
+            .remove(1);
+        assert!(matches!(
+            recover_any(&shares[..2], false),
+            Err(Error::InconsistentSchemes)
+        ));
+        assert!(matches!(recover_any(&[], false), Err(Error::EmptyShares)));
+    }
+
+    #[test]
+    fn detect_legacy_shares() {
+        // Dealt before shares recorded their scheme.
+        let shares = vec![
+            "2-1-Cha7s14Q/mSwWko0ittr+/Uf79RHQMIP".to_string(),
+            "2-4-ChaydsUJDypD9ZWxwvIICh/cmZvzusOF".to_string(),
+        ];
+        assert_eq!(detect_scheme(&shares[0]).unwrap(), SchemeKind::Sss);
+        let expected = sss::recover_secret(&shares, false).unwrap();
+        assert_eq!(
+            recover_any(&shares, false).unwrap().as_bytes(),
+            expected.as_bytes()
+        );
+
+        #[cfg(feature = "dss")]
+        for (hash, kind) in [(vec![], SchemeKind::ThSS), (vec![42; 32], SchemeKind::SS1)] {
+            use base64::Engine;
+
+            let proto = crate::proto::dss::ShareProto {
+                id: 1,
+                threshold: 2,
+                shares_count: 3,
+                data: vec![1, 2, 3],
+                hash,
+                ..Default::default()
+            };
+            let data =
+                base64::engine::general_purpose::STANDARD_NO_PAD.encode(proto.encode_to_vec());
+            assert_eq!(detect_scheme(&format!("2-1-{}", data)).unwrap(), kind);
+        }
+    }
+
+    #[test]
+    fn shares_of_secretshare_are_never_taken_for_dss_shares() {
+        use base64::Engine;
+
+        // Decodes as the message of a `ThSS` share, with another identifier.
+        assert_eq!(
+            detect_scheme("2-1-sWqxoPt53j+0AA").unwrap(),
+            SchemeKind::Sss
+        );
+
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        for _ in 0..2000 {
+            let mut secret = vec![0; 1 + (rng.next_u32() % 32) as usize];
+            rng.fill_bytes(&mut secret);
+            let shares = Sss
+                .split(&secret, SplitOptions::new(2, 3).rng(&mut rng))
+                .unwrap();
+            // Shares of `secretshare` hold the data of the share as it is.
+            let legacy = shares[..2]
+                .iter()
+                .map(|raw| {
+                    let share = sss::Share::from_string(raw, false).unwrap();
+                    let data = base64::engine::general_purpose::STANDARD_NO_PAD.encode(&share.data);
+                    format!("{}-{}-{}", share.threshold, share.id, data)
+                })
+                .collect::<Vec<_>>();
+            let mut detected = true;
+            for share in &legacy {
+                match detect_scheme(share) {
+                    Ok(kind) => assert_eq!(kind, SchemeKind::Sss, "{}", share),
+                    // The data happens to record an unsupported version or scheme.
+                    Err(err) => {
+                        assert!(
+                            matches!(
+                                err,
+                                Error::ShareParsingError(_) | Error::UnsupportedShareVersion(_)
+                            ),
+                            "{}",
+                            share
+                        );
+                        detected = false;
+                    }
+                }
+            }
+            if detected {
+                // Shares of `secretshare` are only recovered mixed with current ones.
+                let mixed = [&legacy[..], &shares[2..]].concat();
+                assert_eq!(recover_any(&mixed, false).unwrap().as_bytes(), &secret[..]);
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Defines the `Secret` type returned when recovering a secret.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+
+/// A recovered secret.
+///
+/// Dereferences to the bytes of the secret. Its `Debug` implementation never prints
+/// the secret itself, and when the `zeroize` feature is enabled, the memory holding
+/// the secret is wiped when it is dropped.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct Secret {
+    bytes: Vec<u8>,
+}
+
+impl Secret {
+    /// Wraps the given bytes into a `Secret`.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the bytes of the secret.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Unwraps the bytes of the secret.
+    ///
+    /// The returned `Vec` will not be wiped when dropped, even if the `zeroize`
+    /// feature is enabled.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        core::mem::take(&mut self.bytes)
+    }
+
+    /// Splits the secret in two at the given index, returning the tail.
+    #[cfg(feature = "dss")]
+    pub(crate) fn split_off(&mut self, at: usize) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.bytes.split_off(at))
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Secret([REDACTED; {} bytes])", self.bytes.len())
+    }
+}
+
+impl Deref for Secret {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for Secret {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl From<Vec<u8>> for Secret {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl PartialEq<[u8]> for Secret {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.bytes == other
+    }
+}
+
+impl<'a> PartialEq<&'a [u8]> for Secret {
+    fn eq(&self, other: &&'a [u8]) -> bool {
+        self.bytes == *other
+    }
+}
+
+impl PartialEq<Vec<u8>> for Secret {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        &self.bytes == other
+    }
+}
+
+impl PartialEq<Secret> for Vec<u8> {
+    fn eq(&self, other: &Secret) -> bool {
+        self == &other.bytes
+    }
+}
+
+// Implemented regardless of the `zeroize` feature, so that enabling it does not
+// forbid moving fields out of the secret.
+impl Drop for Secret {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut self.bytes);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Secret {}
+
+/// Wrapper for intermediate buffers holding sensitive data, such as polynomial
+/// coefficients or random padding, which wipes them when dropped.
+#[cfg(feature = "zeroize")]
+pub(crate) use zeroize::Zeroizing;
+
+/// Stand-in for `zeroize::Zeroizing` when the `zeroize` feature is disabled,
+/// which simply holds on to the value.
+#[cfg(not(feature = "zeroize"))]
+pub(crate) struct Zeroizing<T>(T);
+
+#[cfg(not(feature = "zeroize"))]
+impl<T> Zeroizing<T> {
+    #[inline]
+    pub(crate) fn new(value: T) -> Self {
+        Zeroizing(value)
+    }
+}
+
+#[cfg(not(feature = "zeroize"))]
+impl<T> Deref for Zeroizing<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(not(feature = "zeroize"))]
+impl<T> core::ops::DerefMut for Zeroizing<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn debug_does_not_reveal_secret() {
+        let secret = Secret::new(b"Hello, World!".to_vec());
+        assert_eq!(format!("{:?}", secret), "Secret([REDACTED; 13 bytes])");
+    }
+
+    #[test]
+    fn compares_with_bytes() {
+        let secret = Secret::new(b"Hello, World!".to_vec());
+        assert_eq!(secret, b"Hello, World!".to_vec());
+        assert_eq!(b"Hello, World!".to_vec(), secret);
+        assert_eq!(secret, &b"Hello, World!"[..]);
+        assert_eq!(secret.into_vec(), b"Hello, World!");
+    }
+}
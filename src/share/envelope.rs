@@ -0,0 +1,163 @@
+//! The binary encoding of shares, which every other format of shares wraps.
+//!
+//! A share is encoded as the magic bytes `RSSH`, the version of the share format,
+//! the scheme which dealt the share, the threshold and the identifier of the share,
+//! one byte each, followed by the protobuf message holding the share.
+//!
+//! The mnemonic, armored, Reed–Solomon and QR code formats hold these bytes. The
+//! `K-N-D` string format only holds the threshold, the identifier and the message,
+//! which records the version and the scheme of the share itself.
+
+use prost::Message;
+
+use crate::encoding::{self, TextEncoding};
+use crate::errors::*;
+use crate::proto::{SchemeHeaderProto, SchemeProto};
+use crate::share::version::CURRENT_SHARE_VERSION;
+
+/// The bytes every binary share starts with.
+pub(crate) const MAGIC: &[u8; 4] = b"RSSH";
+
+const HEADER_LEN: usize = MAGIC.len() + 4;
+
+/// The parts of a share held by its binary encoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Envelope {
+    pub scheme: SchemeProto,
+    pub threshold: u8,
+    pub id: u8,
+    pub body: Vec<u8>,
+}
+
+impl Envelope {
+    /// Wraps the given protobuf message holding a share.
+    pub fn new<M: Message>(scheme: SchemeProto, threshold: u8, id: u8, message: &M) -> Self {
+        let mut body = Vec::with_capacity(message.encoded_len());
+        // Unwrap is safe, since we have reserved sufficient capacity in the vector.
+        message.encode(&mut body).unwrap();
+        Envelope {
+            scheme,
+            threshold,
+            id,
+            body,
+        }
+    }
+
+    /// Encodes the share into bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.body.len());
+        bytes.extend_from_slice(MAGIC);
+        // Both enumerations have a handful of values.
+        bytes.push(CURRENT_SHARE_VERSION as u8);
+        bytes.push(self.scheme as u8);
+        bytes.push(self.threshold);
+        bytes.push(self.id);
+        bytes.extend_from_slice(&self.body);
+        bytes
+    }
+
+    /// Encodes the share in the `K-N-D` format, in the given encoding.
+    pub fn to_text(&self, encoding: TextEncoding) -> String {
+        encoding::to_text(self.threshold, self.id, &self.body, encoding)
+    }
+
+    /// Decodes a share encoded by `Envelope::to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN || !bytes.starts_with(MAGIC) {
+            bail!(Error::ShareParsingError(
+                "Expected a binary share, starting with `RSSH`.".to_owned()
+            ));
+        }
+        let header = &bytes[MAGIC.len()..HEADER_LEN];
+        let version = i32::from(header[0]);
+        if version > CURRENT_SHARE_VERSION as i32 {
+            bail!(Error::UnsupportedShareVersion(version));
+        }
+        let scheme = SchemeProto::try_from(i32::from(header[1]))
+            .map_err(|_| Error::ShareParsingError(format!("Unsupported scheme: {}.", header[1])))?;
+        Ok(Envelope {
+            scheme,
+            threshold: header[2],
+            id: header[3],
+            body: bytes[HEADER_LEN..].to_vec(),
+        })
+    }
+
+    /// Decodes a share encoded by `Envelope::to_text`. Its scheme is the one recorded
+    /// by its message, if any, which is left to the scheme to decode.
+    pub fn from_text(raw: &str) -> Result<Self> {
+        let (k, n, data) = encoding::split_parts(raw)?;
+        let body = TextEncoding::decode(data)?;
+        let scheme = SchemeHeaderProto::decode(body.as_slice())
+            .map_or(SchemeProto::UnspecifiedScheme, |header| header.scheme());
+        Ok(Envelope {
+            scheme,
+            threshold: k.parse()?,
+            id: n.parse()?,
+            body,
+        })
+    }
+
+    /// Checks that the share was dealt by one of the given schemes.
+    pub fn expect_scheme(&self, schemes: &[SchemeProto]) -> Result<()> {
+        if !schemes.contains(&self.scheme) {
+            bail!(Error::ShareParsingError(format!(
+                "Unexpected share of scheme {:?}.",
+                self.scheme
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that the scheme of the share agrees with the one its message records.
+    pub fn check_scheme(&self, recorded: SchemeProto) -> Result<()> {
+        if recorded != self.scheme {
+            bail!(Error::ShareParsingError(format!(
+                "The share records the {:?} scheme, but its binary header the {:?} scheme.",
+                recorded, self.scheme
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn to_bytes_then_from_bytes() {
+        let envelope = Envelope {
+            scheme: SchemeProto::WrappedSecrets,
+            threshold: 3,
+            id: 2,
+            body: vec![10, 3, 1, 2, 3],
+        };
+        let bytes = envelope.to_bytes();
+        assert_eq!(bytes, [b'R', b'S', b'S', b'H', 0, 2, 3, 2, 10, 3, 1, 2, 3]);
+        assert_eq!(envelope.to_text(TextEncoding::Base64), "3-2-CgMBAgM");
+        assert_eq!(envelope.to_text(TextEncoding::Hex), "3-2-hex.0A03010203");
+
+        let decoded = Envelope::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, envelope);
+        assert!(decoded.expect_scheme(&[SchemeProto::Sss]).is_err());
+        assert!(decoded.check_scheme(SchemeProto::Sss).is_err());
+
+        // The message of the share records no scheme.
+        let decoded = Envelope::from_text("3-2-hex.0A03010203").unwrap();
+        assert_eq!(decoded.scheme, SchemeProto::UnspecifiedScheme);
+        assert_eq!(decoded.body, envelope.body);
+    }
+
+    #[test]
+    fn invalid_envelopes() {
+        assert!(Envelope::from_bytes(b"RSSH\0\x01\x02").is_err());
+        assert!(Envelope::from_bytes(b"RSSX\0\x01\x02\x01body").is_err());
+        assert!(Envelope::from_bytes(b"RSSH\0\x09\x02\x01body").is_err());
+        assert!(matches!(
+            Envelope::from_bytes(b"RSSH\x07\x01\x02\x01body"),
+            Err(Error::UnsupportedShareVersion(7))
+        ));
+    }
+}
@@ -3,9 +3,14 @@
 //! These traits are currently not exposed, but this might
 //! change in the future.
 
+#[cfg(feature = "std")]
 use crate::errors::*;
 
+#[cfg(feature = "std")]
+pub(crate) mod envelope;
 pub(crate) mod validation;
+#[cfg(feature = "std")]
+pub(crate) mod version;
 
 /// All types of share should implement this trait.
 #[allow(dead_code)]
@@ -25,6 +30,7 @@ pub(crate) trait IsShare: Sized {
 }
 
 /// This trait must be implemented by shares' types wich can be signed.
+#[cfg(feature = "std")]
 #[allow(dead_code)]
 pub(crate) trait IsSignedShare: IsShare {
     /// The type of shares' sigature.
@@ -1,5 +1,9 @@
+use alloc::vec::Vec;
+
 use crate::errors::*;
-use crate::share::{IsShare, IsSignedShare};
+use crate::share::IsShare;
+#[cfg(feature = "std")]
+use crate::share::IsSignedShare;
 
 // The order of validation that we think makes the most sense is the following:
 // 1) Validate shares individually
@@ -8,6 +12,7 @@ use crate::share::{IsShare, IsSignedShare};
 // 3) Validate other properties, in no specific order
 
 /// TODO: Doc
+#[cfg(feature = "std")]
 pub(crate) fn validate_signed_shares<S: IsSignedShare>(
     shares: &[S],
     verify_signatures: bool,
@@ -24,7 +29,7 @@ pub(crate) fn validate_signed_shares<S: IsSignedShare>(
 /// TODO: Doc
 pub(crate) fn validate_shares<S: IsShare>(shares: &[S]) -> Result<(u8, usize)> {
     if shares.is_empty() {
-        bail!(ErrorKind::EmptyShares);
+        bail!(Error::EmptyShares);
     }
 
     let shares_count = shares.len();
@@ -42,21 +47,21 @@ pub(crate) fn validate_shares<S: IsShare>(shares: &[S]) -> Result<(u8, usize)> {
         // type which implements `IsShare` is implemented later that doesn't do that validation,
         // we'll leave them.
         if id < 1 {
-            bail!(ErrorKind::ShareParsingInvalidShareId(id))
+            bail!(Error::ShareParsingInvalidShareId(id))
         } else if threshold_ < 2 {
-            bail!(ErrorKind::ShareParsingInvalidShareThreshold(threshold, id))
+            bail!(Error::ShareParsingInvalidShareThreshold(threshold, id))
         } else if slen_ < 1 {
-            bail!(ErrorKind::ShareParsingErrorEmptyShare(id))
+            bail!(Error::ShareParsingErrorEmptyShare(id))
         }
 
-        if ids.iter().any(|&x| x == id) {
-            bail!(ErrorKind::DuplicateShareId(id));
+        if ids.contains(&id) {
+            bail!(Error::DuplicateShareId(id));
         }
 
         if threshold == 0 {
             threshold = threshold_;
         } else if threshold_ != threshold {
-            bail!(ErrorKind::InconsistentThresholds(
+            bail!(Error::InconsistentThresholds(
                 id, threshold_, ids, threshold
             ))
         }
@@ -64,7 +69,7 @@ pub(crate) fn validate_shares<S: IsShare>(shares: &[S]) -> Result<(u8, usize)> {
         if slen == 0 {
             slen = slen_;
         } else if slen_ != slen {
-            bail!(ErrorKind::InconsistentSecretLengths(id, slen_, ids, slen))
+            bail!(Error::InconsistentSecretLengths(id, slen_, ids, slen))
         }
 
         ids.push(id);
@@ -73,7 +78,7 @@ pub(crate) fn validate_shares<S: IsShare>(shares: &[S]) -> Result<(u8, usize)> {
     // Only once the threshold is confirmed as consistent should we determine if shares are
     // missing.
     if shares_count < threshold as usize {
-        bail!(ErrorKind::MissingShares(shares_count, threshold))
+        bail!(Error::MissingShares(shares_count, threshold))
     }
 
     Ok((threshold, slen))
@@ -81,16 +86,16 @@ pub(crate) fn validate_shares<S: IsShare>(shares: &[S]) -> Result<(u8, usize)> {
 
 pub(crate) fn validate_share_count(threshold: u8, shares_count: u8) -> Result<(u8, u8)> {
     if threshold < MIN_THRESHOLD {
-        bail!(ErrorKind::ThresholdTooSmall(threshold));
+        bail!(Error::ThresholdTooSmall(threshold));
     }
     if shares_count > MAX_SHARES {
-        bail!(ErrorKind::InvalidShareCountMax(shares_count, MAX_SHARES));
+        bail!(Error::InvalidShareCountMax(shares_count, MAX_SHARES));
     }
     if shares_count < MIN_SHARES {
-        bail!(ErrorKind::InvalidShareCountMin(shares_count, MIN_SHARES));
+        bail!(Error::InvalidShareCountMin(shares_count, MIN_SHARES));
     }
     if threshold > shares_count {
-        bail!(ErrorKind::ThresholdTooBig(threshold, shares_count));
+        bail!(Error::ThresholdTooBig(threshold, shares_count));
     }
 
     Ok((threshold, shares_count))
@@ -0,0 +1,103 @@
+//! The versions of the format of share messages, and how to read each of them.
+//!
+//! Every share message records the version of the format it was written in. When
+//! the format changes, `CURRENT_SHARE_VERSION` is bumped, and the share messages
+//! register a decoder for the former version, which converts its messages into
+//! the current ones, so that stored shares can still be recovered.
+
+use prost::Message;
+
+use crate::errors::*;
+use crate::proto::{SchemeHeaderProto, VersionProto};
+
+/// The version of the format of the shares written by this release.
+pub(crate) const CURRENT_SHARE_VERSION: VersionProto = VersionProto::InitialRelease;
+
+/// Decodes a share message written in some version of the format.
+pub(crate) type Decoder<M> = fn(&[u8]) -> Result<M>;
+
+/// A share message, which can be read from every version of the format having a
+/// decoder.
+pub(crate) trait VersionedShareProto: Message + Default + 'static {
+    /// The decoders of the messages written in each supported version of the format.
+    const DECODERS: &'static [(VersionProto, Decoder<Self>)];
+}
+
+impl VersionedShareProto for crate::proto::wrapped::ShareProto {
+    const DECODERS: &'static [(VersionProto, Decoder<Self>)] =
+        &[(VersionProto::InitialRelease, decode_current)];
+}
+
+#[cfg(feature = "dss")]
+impl VersionedShareProto for crate::proto::dss::ShareProto {
+    const DECODERS: &'static [(VersionProto, Decoder<Self>)] =
+        &[(VersionProto::InitialRelease, decode_current)];
+}
+
+/// Decodes a share message with the decoder of the version it was written in.
+pub(crate) fn decode_share<M: VersionedShareProto>(data: &[u8]) -> Result<M> {
+    let header = SchemeHeaderProto::decode(data).map_err(Error::ShareParsingErrorProtobuf)?;
+    match M::DECODERS
+        .iter()
+        .find(|(version, _)| *version as i32 == header.version)
+    {
+        Some((_, decode)) => decode(data),
+        None => bail!(Error::UnsupportedShareVersion(header.version)),
+    }
+}
+
+/// Checks that a version of a format is not newer than its given current version,
+/// such as `CURRENT_SHARE_VERSION`, and reports it with the given error otherwise.
+pub(crate) fn check_version(
+    version: i32,
+    current: VersionProto,
+    unsupported: fn(i32) -> Error,
+) -> Result<()> {
+    if version > current as i32 || version < 0 {
+        bail!(unsupported(version));
+    }
+    Ok(())
+}
+
+fn decode_current<M: Message + Default>(data: &[u8]) -> Result<M> {
+    M::decode(data).map_err(Error::ShareParsingErrorProtobuf)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::proto::wrapped::ShareProto;
+
+    #[test]
+    fn current_version_is_decoded() {
+        let share = ShareProto {
+            shamir_data: vec![1, 2, 3],
+            version: CURRENT_SHARE_VERSION.into(),
+            ..Default::default()
+        };
+        let decoded = decode_share::<ShareProto>(&share.encode_to_vec()).unwrap();
+        assert_eq!(decoded, share);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let share = ShareProto {
+            shamir_data: vec![1, 2, 3],
+            version: CURRENT_SHARE_VERSION as i32 + 1,
+            ..Default::default()
+        };
+        let data = share.encode_to_vec();
+        assert!(matches!(
+            decode_share::<ShareProto>(&data),
+            Err(Error::UnsupportedShareVersion(1))
+        ));
+        let header = SchemeHeaderProto::decode(data.as_slice()).unwrap();
+        assert!(check_version(
+            header.version,
+            CURRENT_SHARE_VERSION,
+            Error::UnsupportedShareVersion
+        )
+        .is_err());
+    }
+}
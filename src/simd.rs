@@ -0,0 +1,214 @@
+//! Vectorised GF(256) arithmetic over whole byte slices.
+//!
+//! Multiplying a slice by a constant `c` is done with the split-nibble technique:
+//! since multiplication distributes over addition, `c * y = c * (y & 0x0F) ^ c * (y & 0xF0)`,
+//! so two 16-entry tables indexed by the low and high nibbles of `y` are enough.
+//! On x86-64 CPUs supporting SSSE3, these lookups are performed 16 bytes at a time
+//! with `PSHUFB`, which does not access memory and thus runs in constant time.
+//! Elsewhere, a portable fallback is used.
+
+#![allow(unsafe_code)]
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::gf256::Gf256;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Lookup tables for multiplying by a given constant, indexed by nibbles.
+struct NibbleTables {
+    lo: [u8; 16],
+    hi: [u8; 16],
+}
+
+impl NibbleTables {
+    fn new(c: Gf256) -> Self {
+        let mut tables = NibbleTables {
+            lo: [0; 16],
+            hi: [0; 16],
+        };
+        for n in 0..16u8 {
+            tables.lo[n as usize] = (c * Gf256::from_byte(n)).to_byte();
+            tables.hi[n as usize] = (c * Gf256::from_byte(n << 4)).to_byte();
+        }
+        tables
+    }
+}
+
+/// Computes `dst[i] += c * src[i]` for every `i`, in GF(256).
+///
+/// # Panics
+///
+/// Panics if `dst` and `src` do not have the same length.
+pub(crate) fn mul_add_assign(dst: &mut [u8], src: &[u8], c: Gf256) {
+    assert_eq!(dst.len(), src.len());
+
+    if c == Gf256::zero() {
+        return;
+    }
+    if c == Gf256::one() {
+        dst.iter_mut().zip(src).for_each(|(d, &s)| *d ^= s);
+        return;
+    }
+
+    let tables = NibbleTables::new(c);
+
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        if is_x86_feature_detected!("ssse3") {
+            let done = dst.len() - dst.len() % 16;
+            // Safe since we just checked that the CPU supports SSSE3.
+            unsafe { mul_add_assign_ssse3(&mut dst[..done], &src[..done], &tables) };
+            mul_add_assign_portable(&mut dst[done..], &src[done..], c, &tables);
+            return;
+        }
+    }
+
+    // Without the standard library, the CPU features cannot be detected at runtime,
+    // so SSSE3 is only used if the target is known to support it.
+    #[cfg(all(target_arch = "x86_64", not(feature = "std"), target_feature = "ssse3"))]
+    {
+        let done = dst.len() - dst.len() % 16;
+        // Safe since the target supports SSSE3.
+        unsafe { mul_add_assign_ssse3(&mut dst[..done], &src[..done], &tables) };
+        mul_add_assign_portable(&mut dst[done..], &src[done..], c, &tables);
+        return;
+    }
+
+    #[allow(unreachable_code)]
+    mul_add_assign_portable(dst, src, c, &tables);
+}
+
+/// Computes the sum of `weights[i] * rows[i]`, in GF(256), where every row
+/// holds `len` bytes.
+pub(crate) fn linear_combination(rows: &[&[u8]], weights: &[Gf256], len: usize) -> Vec<u8> {
+    let mut result = vec![0u8; len];
+
+    #[cfg(not(feature = "parallel"))]
+    for (row, &w) in rows.iter().zip(weights) {
+        mul_add_assign(&mut result, row, w);
+    }
+
+    #[cfg(feature = "parallel")]
+    result
+        .par_chunks_mut(crate::parallel::CHUNK_LEN)
+        .enumerate()
+        .for_each(|(c, chunk)| {
+            let offset = c * crate::parallel::CHUNK_LEN;
+            for (row, &w) in rows.iter().zip(weights) {
+                mul_add_assign(chunk, &row[offset..offset + chunk.len()], w);
+            }
+        });
+
+    result
+}
+
+#[cfg(not(feature = "constant-time"))]
+#[inline]
+fn mul_add_assign_portable(dst: &mut [u8], src: &[u8], _c: Gf256, tables: &NibbleTables) {
+    for (d, &s) in dst.iter_mut().zip(src) {
+        *d ^= tables.lo[(s & 0x0F) as usize] ^ tables.hi[(s >> 4) as usize];
+    }
+}
+
+/// Table lookups indexed by secret bytes leak through the cache, so stick to
+/// the constant-time multiplication instead.
+#[cfg(feature = "constant-time")]
+#[inline]
+fn mul_add_assign_portable(dst: &mut [u8], src: &[u8], c: Gf256, _tables: &NibbleTables) {
+    for (d, &s) in dst.iter_mut().zip(src) {
+        *d ^= (c * Gf256::from_byte(s)).to_byte();
+    }
+}
+
+/// SSSE3 implementation of `mul_add_assign`, over slices whose length is a multiple of 16.
+#[cfg(all(target_arch = "x86_64", any(feature = "std", target_feature = "ssse3")))]
+#[target_feature(enable = "ssse3")]
+unsafe fn mul_add_assign_ssse3(dst: &mut [u8], src: &[u8], tables: &NibbleTables) {
+    use core::arch::x86_64::{
+        __m128i, _mm_and_si128, _mm_loadu_si128, _mm_set1_epi8, _mm_shuffle_epi8, _mm_srli_epi64,
+        _mm_storeu_si128, _mm_xor_si128,
+    };
+
+    debug_assert_eq!(dst.len() % 16, 0);
+
+    let lo = _mm_loadu_si128(tables.lo.as_ptr() as *const __m128i);
+    let hi = _mm_loadu_si128(tables.hi.as_ptr() as *const __m128i);
+    let mask = _mm_set1_epi8(0x0F);
+
+    for (d, s) in dst.chunks_exact_mut(16).zip(src.chunks_exact(16)) {
+        let s = _mm_loadu_si128(s.as_ptr() as *const __m128i);
+        let s_lo = _mm_and_si128(s, mask);
+        let s_hi = _mm_and_si128(_mm_srli_epi64(s, 4), mask);
+        let prod = _mm_xor_si128(_mm_shuffle_epi8(lo, s_lo), _mm_shuffle_epi8(hi, s_hi));
+
+        let acc = _mm_loadu_si128(d.as_ptr() as *const __m128i);
+        _mm_storeu_si128(d.as_mut_ptr() as *mut __m128i, _mm_xor_si128(acc, prod));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use quickcheck::*;
+
+    quickcheck! {
+        fn mul_add_assign_matches_scalar(dst: Vec<u8>, c: u8) -> bool {
+            let src = dst.iter().map(|b| b.wrapping_mul(31).wrapping_add(7)).collect::<Vec<_>>();
+            let c = Gf256::from_byte(c);
+
+            let expected = dst
+                .iter()
+                .zip(&src)
+                .map(|(&d, &s)| (Gf256::from_byte(d) + c * Gf256::from_byte(s)).to_byte())
+                .collect::<Vec<_>>();
+
+            let mut actual = dst.clone();
+            mul_add_assign(&mut actual, &src, c);
+
+            actual == expected
+        }
+    }
+
+    #[test]
+    fn linear_combination_matches_scalar() {
+        let rows = (1..=4u8)
+            .map(|r| {
+                (0..1000)
+                    .map(|i| (i as u8).wrapping_mul(r))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let rows = rows.iter().map(|r| &r[..]).collect::<Vec<_>>();
+        let weights = [3, 0, 1, 200]
+            .iter()
+            .map(|&w| Gf256::from_byte(w))
+            .collect::<Vec<_>>();
+
+        let actual = linear_combination(&rows, &weights, 1000);
+        for (i, &a) in actual.iter().enumerate() {
+            let expected = rows
+                .iter()
+                .zip(&weights)
+                .fold(Gf256::zero(), |acc, (row, &w)| {
+                    acc + w * Gf256::from_byte(row[i])
+                });
+            assert_eq!(a, expected.to_byte());
+        }
+    }
+
+    #[test]
+    fn mul_add_assign_all_constants_and_bytes() {
+        let src = (0..=255).collect::<Vec<u8>>();
+        for c in 0..=255 {
+            let c = Gf256::from_byte(c);
+            let mut dst = vec![0u8; 256];
+            mul_add_assign(&mut dst, &src, c);
+            for (&d, &s) in dst.iter().zip(&src) {
+                assert_eq!(d, (c * Gf256::from_byte(s)).to_byte());
+            }
+        }
+    }
+}
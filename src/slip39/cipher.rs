@@ -0,0 +1,109 @@
+//! The four-round Feistel network which encrypts SLIP-39 master secrets with a
+//! passphrase, using PBKDF2-HMAC-SHA256 as round function.
+
+use std::num::NonZeroU32;
+
+use ring::pbkdf2;
+
+use crate::secret::Zeroizing;
+
+const BASE_ITERATION_COUNT: u32 = 10_000;
+const ROUND_COUNT: u8 = 4;
+const CUSTOMIZATION_STRING: &[u8] = b"shamir";
+
+pub(super) fn encrypt(
+    master_secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Vec<u8> {
+    let salt = salt(identifier, extendable);
+    feistel(
+        master_secret,
+        passphrase,
+        iteration_exponent,
+        &salt,
+        0..ROUND_COUNT,
+    )
+}
+
+pub(super) fn decrypt(
+    encrypted_master_secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Vec<u8> {
+    let salt = salt(identifier, extendable);
+    feistel(
+        encrypted_master_secret,
+        passphrase,
+        iteration_exponent,
+        &salt,
+        (0..ROUND_COUNT).rev(),
+    )
+}
+
+/// Extendable backups leave the identifier out of the salt, so that shares dealt
+/// later under a new identifier still decrypt to the same master secret.
+fn salt(identifier: u16, extendable: bool) -> Vec<u8> {
+    if extendable {
+        Vec::new()
+    } else {
+        [CUSTOMIZATION_STRING, &identifier.to_be_bytes()].concat()
+    }
+}
+
+fn feistel(
+    data: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    salt: &[u8],
+    rounds: impl Iterator<Item = u8>,
+) -> Vec<u8> {
+    let (left, right) = data.split_at(data.len() / 2);
+    let (mut left, mut right) = (
+        Zeroizing::new(left.to_vec()),
+        Zeroizing::new(right.to_vec()),
+    );
+
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / u32::from(ROUND_COUNT);
+    // Unwrap is safe, since the iteration exponent is smaller than 16.
+    let iterations = NonZeroU32::new(iterations).unwrap();
+
+    for round in rounds {
+        let password = Zeroizing::new([&[round], passphrase].concat());
+        let mut f = Zeroizing::new(vec![0; right.len()]);
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            iterations,
+            &[salt, &right].concat(),
+            &password,
+            &mut f,
+        );
+        for (l, f) in left.iter_mut().zip(f.iter()) {
+            *l ^= f;
+        }
+        core::mem::swap(&mut left, &mut right);
+    }
+
+    [&right[..], &left[..]].concat()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn decrypt_reverses_encrypt() {
+        let secret = b"0123456789abcdef";
+        for extendable in [false, true] {
+            let encrypted = encrypt(secret, b"TREZOR", 0, 42, extendable);
+            assert_ne!(&encrypted[..], &secret[..]);
+            assert_eq!(decrypt(&encrypted, b"TREZOR", 0, 42, extendable), secret);
+            assert_ne!(decrypt(&encrypted, b"", 0, 42, extendable), secret);
+        }
+    }
+}
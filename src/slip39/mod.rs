@@ -0,0 +1,577 @@
+//! Secret sharing following SLIP-0039, as used by hardware wallets.
+//!
+//! The master secret is first encrypted with a passphrase, then split into groups,
+//! a threshold of which are needed to recover it. The secret of each group is in
+//! turn split into member shares, a threshold of which are needed to recover it.
+//! Each member share is written as a mnemonic of words from the SLIP-39 word list,
+//! protected by a Reed-Solomon checksum.
+//!
+//! Unlike the other schemes of this crate, SLIP-39 works in the field defined by the
+//! polynomial x^8 + x^4 + x^3 + x + 1, and hides the secret and a digest of it at the
+//! points 255 and 254 of the polynomial, so that wrong shares are detected.
+//!
+//! See <https://github.com/satoshilabs/slips/blob/master/slip-0039.md>.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use etospheres_labs_rusty_secrets::slip39::{recover_secret, Group, Slip39};
+//!
+//! let master_secret = b"0123456789abcdef";
+//!
+//! // Either the single share of the first group, or two shares of the second one.
+//! let groups = [
+//!     Group { member_threshold: 1, member_count: 1 },
+//!     Group { member_threshold: 2, member_count: 3 },
+//! ];
+//! let mnemonics = Slip39::new(1, &groups)
+//!     .split_secret(master_secret, "TREZOR")
+//!     .unwrap();
+//!
+//! let secret = recover_secret(&mnemonics[1][1..], "TREZOR").unwrap();
+//! assert_eq!(secret.as_bytes(), master_secret);
+//! ```
+
+use std::collections::BTreeMap;
+
+use rand_core::{CryptoRng, RngCore};
+use ring::hmac;
+
+use crate::errors::*;
+use crate::field::{Field, Rijndael};
+use crate::lagrange::LagrangeBasis;
+use crate::secret::{Secret, Zeroizing};
+
+mod cipher;
+mod share;
+pub use self::share::Share;
+
+/// The maximum number of groups, and of member shares in a group.
+const MAX_SHARE_COUNT: u8 = 16;
+const DEFAULT_ITERATION_EXPONENT: u8 = 1;
+
+/// The point at which the shared secret lies.
+const SECRET_INDEX: u8 = 255;
+/// The point at which the digest of the shared secret lies.
+const DIGEST_INDEX: u8 = 254;
+const DIGEST_LENGTH_BYTES: usize = 4;
+
+/// The threshold and number of the member shares of a group.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Group {
+    /// The number of member shares needed to recover the secret of the group.
+    pub member_threshold: u8,
+    /// The number of member shares of the group.
+    pub member_count: u8,
+}
+
+/// Splits master secrets into SLIP-39 mnemonics, along the given groups.
+#[derive(Clone, Debug)]
+pub struct Slip39 {
+    group_threshold: u8,
+    groups: Vec<Group>,
+    iteration_exponent: u8,
+    extendable: bool,
+}
+
+impl Slip39 {
+    /// Splits master secrets into the given groups, `group_threshold` of which are
+    /// needed to recover them.
+    ///
+    /// Shares are extendable, and the master secret is encrypted with an iteration
+    /// exponent of 1, as by the reference implementation.
+    pub fn new(group_threshold: u8, groups: &[Group]) -> Self {
+        Slip39 {
+            group_threshold,
+            groups: groups.to_vec(),
+            iteration_exponent: DEFAULT_ITERATION_EXPONENT,
+            extendable: true,
+        }
+    }
+
+    /// Sets the iteration exponent, the encryption of the master secret going through
+    /// `10000 << iteration_exponent` iterations of PBKDF2. At most 15.
+    pub fn iteration_exponent(mut self, iteration_exponent: u8) -> Self {
+        self.iteration_exponent = iteration_exponent;
+        self
+    }
+
+    /// Sets whether the shares are extendable, ie. whether more shares can later be
+    /// dealt for the same master secret under a new identifier. Shares which are not
+    /// extendable can be read by wallets predating extendable backups.
+    pub fn extendable(mut self, extendable: bool) -> Self {
+        self.extendable = extendable;
+        self
+    }
+
+    /// Splits the given master secret, encrypted with the given passphrase,
+    /// returning the mnemonics of each group.
+    ///
+    /// Uses the thread-local random number generator of `rand` as a source of entropy.
+    pub fn split_secret(&self, master_secret: &[u8], passphrase: &str) -> Result<Vec<Vec<String>>> {
+        self.split_secret_rng(&mut rand::rng(), master_secret, passphrase)
+    }
+
+    /// Splits the given master secret, encrypted with the given passphrase,
+    /// returning the mnemonics of each group.
+    ///
+    /// Uses the given random number generator as a source of entropy.
+    pub fn split_secret_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        master_secret: &[u8],
+        passphrase: &str,
+    ) -> Result<Vec<Vec<String>>> {
+        let shares = self.split_secret_shares_rng(rng, master_secret, passphrase)?;
+        shares
+            .iter()
+            .map(|group| group.iter().map(Share::to_mnemonic).collect())
+            .collect()
+    }
+
+    /// Splits the given master secret, encrypted with the given passphrase,
+    /// returning the typed shares of each group.
+    pub fn split_secret_shares_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        master_secret: &[u8],
+        passphrase: &str,
+    ) -> Result<Vec<Vec<Share>>> {
+        self.validate(master_secret)?;
+        let passphrase = validate_passphrase(passphrase)?;
+
+        let identifier = (rng.next_u32() & 0x7FFF) as u16;
+        let encrypted_master_secret = Zeroizing::new(cipher::encrypt(
+            master_secret,
+            passphrase,
+            self.iteration_exponent,
+            identifier,
+            self.extendable,
+        ));
+
+        let group_count = self.groups.len() as u8;
+        let group_secrets = split(
+            rng,
+            self.group_threshold,
+            group_count,
+            &encrypted_master_secret,
+        );
+
+        let shares = group_secrets
+            .iter()
+            .zip(&self.groups)
+            .map(|((group_index, group_secret), group)| {
+                split(
+                    rng,
+                    group.member_threshold,
+                    group.member_count,
+                    group_secret,
+                )
+                .into_iter()
+                .map(|(member_index, value)| Share {
+                    identifier,
+                    extendable: self.extendable,
+                    iteration_exponent: self.iteration_exponent,
+                    group_index: *group_index,
+                    group_threshold: self.group_threshold,
+                    group_count,
+                    member_index,
+                    member_threshold: group.member_threshold,
+                    value: value.to_vec(),
+                })
+                .collect()
+            })
+            .collect();
+
+        Ok(shares)
+    }
+
+    fn validate(&self, master_secret: &[u8]) -> Result<()> {
+        if master_secret.len() * 8 < share::MIN_STRENGTH_BITS
+            || !master_secret.len().is_multiple_of(2)
+        {
+            bail!(Error::InvalidMasterSecretLength(master_secret.len()));
+        }
+        if self.iteration_exponent > share::MAX_ITERATION_EXPONENT {
+            bail!(Error::InvalidIterationExponent(self.iteration_exponent));
+        }
+
+        validate_counts(self.group_threshold, self.groups.len())?;
+        for group in &self.groups {
+            validate_counts(group.member_threshold, group.member_count.into())?;
+            // A single share would be enough to recover the group, so there is no
+            // point in having more than one.
+            if group.member_threshold == 1 && group.member_count > 1 {
+                bail!(Error::InvalidShareCountMax(group.member_count, 1));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_counts(threshold: u8, count: usize) -> Result<()> {
+    let count = u8::try_from(count).unwrap_or(u8::MAX);
+    if threshold == 0 || count == 0 {
+        bail!(Error::InvalidSplitParametersZero(threshold, count));
+    }
+    if count > MAX_SHARE_COUNT {
+        bail!(Error::InvalidShareCountMax(count, MAX_SHARE_COUNT));
+    }
+    if threshold > count {
+        bail!(Error::ThresholdTooBig(threshold, count));
+    }
+    Ok(())
+}
+
+/// SLIP-39 passphrases may only contain printable ASCII characters.
+fn validate_passphrase(passphrase: &str) -> Result<&[u8]> {
+    if !passphrase.bytes().all(|byte| (32..=126).contains(&byte)) {
+        bail!(Error::InvalidPassphrase);
+    }
+    Ok(passphrase.as_bytes())
+}
+
+/// Recovers the master secret from the given mnemonics, decrypting it with the
+/// given passphrase.
+///
+/// The mnemonics must include exactly `member_threshold` shares of exactly
+/// `group_threshold` groups, as extra ones would not be checked. Since the passphrase is not checked, a wrong one yields a wrong secret.
+pub fn recover_secret(mnemonics: &[String], passphrase: &str) -> Result<Secret> {
+    let shares = mnemonics
+        .iter()
+        .map(|mnemonic| Share::from_mnemonic(mnemonic))
+        .collect::<Result<Vec<_>>>()?;
+    recover_secret_shares(&shares, passphrase)
+}
+
+/// Recovers the master secret from the given shares, decrypting it with the given
+/// passphrase. See `recover_secret`.
+pub fn recover_secret_shares(shares: &[Share], passphrase: &str) -> Result<Secret> {
+    let passphrase = validate_passphrase(passphrase)?;
+    let first = match shares.first() {
+        None => bail!(Error::EmptyShares),
+        Some(first) => first,
+    };
+
+    let mut groups = BTreeMap::<u8, Vec<&Share>>::new();
+    for share in shares {
+        let same_secret = share.identifier == first.identifier
+            && share.extendable == first.extendable
+            && share.iteration_exponent == first.iteration_exponent
+            && share.group_threshold == first.group_threshold
+            && share.group_count == first.group_count
+            && share.value.len() == first.value.len();
+        if !same_secret {
+            bail!(Error::InconsistentShares);
+        }
+
+        let members = groups.entry(share.group_index).or_default();
+        if let Some(member) = members
+            .iter()
+            .find(|member| member.member_index == share.member_index)
+        {
+            if member.value != share.value {
+                bail!(Error::DuplicateShareId(share.member_index));
+            }
+            continue;
+        }
+        if members
+            .first()
+            .is_some_and(|member| member.member_threshold != share.member_threshold)
+        {
+            bail!(Error::InconsistentShares);
+        }
+        members.push(share);
+    }
+
+    // As in the reference implementation, exactly `group_threshold` groups of
+    // exactly `member_threshold` members each are expected.
+    let group_threshold = first.group_threshold;
+    if groups.len() < group_threshold.into() {
+        bail!(Error::MissingShares(groups.len(), group_threshold));
+    } else if groups.len() > group_threshold.into() {
+        bail!(Error::TooManyShares(groups.len(), group_threshold));
+    }
+
+    let group_secrets = groups
+        .into_iter()
+        .map(|(group_index, members)| {
+            let threshold = members[0].member_threshold;
+            if members.len() < threshold.into() {
+                bail!(Error::MissingShares(members.len(), threshold));
+            } else if members.len() > threshold.into() {
+                bail!(Error::TooManyShares(members.len(), threshold));
+            }
+            let members = members
+                .iter()
+                .map(|member| (member.member_index, Zeroizing::new(member.value.clone())))
+                .collect::<Vec<_>>();
+            recover(threshold, &members).map(|secret| (group_index, secret))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let encrypted_master_secret = recover(group_threshold, &group_secrets)?;
+    Ok(Secret::new(cipher::decrypt(
+        &encrypted_master_secret,
+        passphrase,
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+    )))
+}
+
+/// Splits the secret into `count` shares, `threshold` of which are needed to
+/// recover it, as pairs of indices and values.
+///
+/// Unless the threshold is 1, the polynomial interpolating the shares goes through
+/// the secret at `SECRET_INDEX`, and through a digest of the secret followed by
+/// random bytes at `DIGEST_INDEX`.
+fn split<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    threshold: u8,
+    count: u8,
+    secret: &[u8],
+) -> Vec<(u8, Zeroizing<Vec<u8>>)> {
+    if threshold == 1 {
+        return (0..count)
+            .map(|index| (index, Zeroizing::new(secret.to_vec())))
+            .collect();
+    }
+
+    let random_bytes = |rng: &mut R, len: usize| {
+        let mut bytes = Zeroizing::new(vec![0; len]);
+        rng.fill_bytes(&mut bytes);
+        bytes
+    };
+
+    // The first shares are random, and fix the polynomial along with the secret
+    // and its digest.
+    let random_share_count = threshold - 2;
+    let mut shares = (0..random_share_count)
+        .map(|index| (index, random_bytes(rng, secret.len())))
+        .collect::<Vec<_>>();
+
+    let random_part = random_bytes(rng, secret.len() - DIGEST_LENGTH_BYTES);
+    let mut digest = Zeroizing::new(create_digest(&random_part, secret).to_vec());
+    digest.extend_from_slice(&random_part);
+
+    let mut base_shares = shares
+        .iter()
+        .map(|(index, value)| (*index, Zeroizing::new(value.to_vec())))
+        .collect::<Vec<_>>();
+    base_shares.push((DIGEST_INDEX, digest));
+    base_shares.push((SECRET_INDEX, Zeroizing::new(secret.to_vec())));
+
+    for index in random_share_count..count {
+        shares.push((index, interpolate(&base_shares, index)));
+    }
+    shares
+}
+
+/// Recovers the secret from exactly `threshold` shares, checking its digest.
+fn recover(threshold: u8, shares: &[(u8, Zeroizing<Vec<u8>>)]) -> Result<Zeroizing<Vec<u8>>> {
+    if threshold == 1 {
+        return Ok(Zeroizing::new(shares[0].1.to_vec()));
+    }
+
+    let mut indices = shares.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+    indices.sort_unstable();
+    if let Some(pair) = indices.windows(2).find(|pair| pair[0] == pair[1]) {
+        bail!(Error::DuplicateShareId(pair[0]));
+    }
+
+    let secret = interpolate(shares, SECRET_INDEX);
+    let digest = interpolate(shares, DIGEST_INDEX);
+    let (digest, random_part) = digest.split_at(DIGEST_LENGTH_BYTES);
+    if digest != create_digest(random_part, &secret) {
+        bail!(Error::InconsistentShares);
+    }
+
+    Ok(secret)
+}
+
+fn create_digest(random_part: &[u8], secret: &[u8]) -> [u8; DIGEST_LENGTH_BYTES] {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, random_part);
+    let mut digest = [0; DIGEST_LENGTH_BYTES];
+    digest.copy_from_slice(&hmac::sign(&key, secret).as_ref()[..DIGEST_LENGTH_BYTES]);
+    digest
+}
+
+/// Evaluates at `x` the polynomial interpolating the given shares, byte by byte.
+fn interpolate(shares: &[(u8, Zeroizing<Vec<u8>>)], x: u8) -> Zeroizing<Vec<u8>> {
+    let xs = shares
+        .iter()
+        .map(|(index, _)| Rijndael(*index))
+        .collect::<Vec<_>>();
+    let basis = LagrangeBasis::new(&xs).at(Rijndael(x));
+
+    let len = shares[0].1.len();
+    Zeroizing::new(
+        (0..len)
+            .map(|i| {
+                basis
+                    .iter()
+                    .zip(shares)
+                    .fold(Rijndael::zero(), |acc, (&b, (_, value))| {
+                        acc + b * Rijndael(value[i])
+                    })
+                    .0
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    const SECRET: &[u8] = b"0123456789abcdefghijklmnopqrstuv";
+
+    fn groups() -> Vec<Group> {
+        vec![
+            Group {
+                member_threshold: 1,
+                member_count: 1,
+            },
+            Group {
+                member_threshold: 2,
+                member_count: 3,
+            },
+            Group {
+                member_threshold: 3,
+                member_count: 5,
+            },
+        ]
+    }
+
+    #[test]
+    fn split_then_recover() {
+        let mut rng = ChaChaRng::from_seed([42; 32]);
+        for extendable in [false, true] {
+            let mnemonics = Slip39::new(2, &groups())
+                .extendable(extendable)
+                .iteration_exponent(0)
+                .split_secret_rng(&mut rng, SECRET, "TREZOR")
+                .unwrap();
+            assert_eq!(
+                mnemonics.iter().map(Vec::len).collect::<Vec<_>>(),
+                [1, 3, 5]
+            );
+
+            let chosen = [&mnemonics[0][..], &mnemonics[2][1..4]].concat();
+            assert_eq!(recover_secret(&chosen, "TREZOR").unwrap(), SECRET);
+
+            let chosen = [&mnemonics[1][1..], &mnemonics[2][2..]].concat();
+            assert_eq!(recover_secret(&chosen, "TREZOR").unwrap(), SECRET);
+
+            // A wrong passphrase goes undetected.
+            let chosen = [&mnemonics[0][..], &mnemonics[1][..2]].concat();
+            assert_ne!(recover_secret(&chosen, "").unwrap(), SECRET);
+        }
+    }
+
+    #[test]
+    fn missing_shares() {
+        let mnemonics = Slip39::new(2, &groups())
+            .iteration_exponent(0)
+            .split_secret(SECRET, "")
+            .unwrap();
+
+        let chosen = [&mnemonics[0][..], &mnemonics[2][..2]].concat();
+        assert!(matches!(
+            recover_secret(&chosen, ""),
+            Err(Error::MissingShares(2, 3))
+        ));
+        assert!(matches!(
+            recover_secret(&mnemonics[2], ""),
+            Err(Error::MissingShares(1, 2))
+        ));
+        assert!(matches!(recover_secret(&[], ""), Err(Error::EmptyShares)));
+    }
+
+    #[test]
+    fn extra_shares_are_rejected() {
+        let mnemonics = Slip39::new(2, &groups())
+            .iteration_exponent(0)
+            .split_secret(SECRET, "")
+            .unwrap();
+
+        let chosen = [&mnemonics[0][..], &mnemonics[1][..2], &mnemonics[2][..3]].concat();
+        assert!(matches!(
+            recover_secret(&chosen, ""),
+            Err(Error::TooManyShares(3, 2))
+        ));
+
+        let chosen = [&mnemonics[0][..], &mnemonics[2][..4]].concat();
+        assert!(matches!(
+            recover_secret(&chosen, ""),
+            Err(Error::TooManyShares(4, 3))
+        ));
+    }
+
+    #[test]
+    fn tampered_shares_are_detected() {
+        let mut rng = ChaChaRng::from_seed([42; 32]);
+        let shares = Slip39::new(1, &groups()[1..2])
+            .iteration_exponent(0)
+            .split_secret_shares_rng(&mut rng, SECRET, "")
+            .unwrap()
+            .remove(0);
+
+        let mut tampered = shares[..2].to_vec();
+        tampered[1].value[0] ^= 1;
+        assert!(matches!(
+            recover_secret_shares(&tampered, ""),
+            Err(Error::InconsistentShares)
+        ));
+
+        tampered[1] = shares[0].clone();
+        tampered[1].value[0] ^= 1;
+        assert!(matches!(
+            recover_secret_shares(&tampered, ""),
+            Err(Error::DuplicateShareId(_))
+        ));
+
+        let other = Slip39::new(1, &groups()[1..2])
+            .iteration_exponent(0)
+            .split_secret_shares_rng(&mut rng, SECRET, "")
+            .unwrap()
+            .remove(0);
+        let mixed = [shares[0].clone(), other[1].clone()];
+        assert!(matches!(
+            recover_secret_shares(&mixed, ""),
+            Err(Error::InconsistentShares)
+        ));
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let split = |slip39: Slip39, secret: &[u8], passphrase: &str| {
+            slip39.split_secret(secret, passphrase).unwrap_err()
+        };
+
+        let err = split(Slip39::new(1, &groups()), &SECRET[..15], "");
+        assert!(matches!(err, Error::InvalidMasterSecretLength(15)));
+        let err = split(Slip39::new(1, &groups()), &SECRET[..17], "");
+        assert!(matches!(err, Error::InvalidMasterSecretLength(17)));
+        let err = split(Slip39::new(4, &groups()), SECRET, "");
+        assert!(matches!(err, Error::ThresholdTooBig(4, 3)));
+        let err = split(Slip39::new(0, &groups()), SECRET, "");
+        assert!(matches!(err, Error::InvalidSplitParametersZero(0, 3)));
+        let err = split(Slip39::new(1, &groups()).iteration_exponent(16), SECRET, "");
+        assert!(matches!(err, Error::InvalidIterationExponent(16)));
+        let err = split(Slip39::new(1, &groups()), SECRET, "pässphrase");
+        assert!(matches!(err, Error::InvalidPassphrase));
+
+        let group = Group {
+            member_threshold: 1,
+            member_count: 2,
+        };
+        let err = split(Slip39::new(1, &[group]), SECRET, "");
+        assert!(matches!(err, Error::InvalidShareCountMax(2, 1)));
+    }
+}
@@ -0,0 +1,428 @@
+use std::sync::OnceLock;
+
+use crate::errors::*;
+use crate::mnemonic::find_word;
+
+/// The SLIP-39 word list, one word per line, in alphabetical order.
+const WORD_LIST: &str = include_str!("wordlist.txt");
+
+/// The number of bits held by each word.
+const RADIX_BITS: usize = 10;
+const RADIX_MASK: u32 = (1 << RADIX_BITS) - 1;
+
+/// The number of words holding the identifier, the extendable flag and the
+/// iteration exponent.
+const ID_EXP_LENGTH_WORDS: usize = 2;
+/// The number of words holding the group and member indices and thresholds.
+const PARAMS_LENGTH_WORDS: usize = 2;
+const CHECKSUM_LENGTH_WORDS: usize = 3;
+const METADATA_LENGTH_WORDS: usize =
+    ID_EXP_LENGTH_WORDS + PARAMS_LENGTH_WORDS + CHECKSUM_LENGTH_WORDS;
+
+/// The identifier is held by 15 bits, and the iteration exponent, the indices, the
+/// thresholds and the counts by 4.
+const MAX_IDENTIFIER: u16 = 0x7FFF;
+pub(super) const MAX_ITERATION_EXPONENT: u8 = 15;
+const MAX_INDEX: u8 = 15;
+
+/// The minimum length of a master secret, in bits.
+pub(super) const MIN_STRENGTH_BITS: usize = 128;
+const MIN_MNEMONIC_LENGTH_WORDS: usize =
+    METADATA_LENGTH_WORDS + MIN_STRENGTH_BITS.div_ceil(RADIX_BITS);
+
+const CUSTOMIZATION_STRING: &[u8] = b"shamir";
+const CUSTOMIZATION_STRING_EXTENDABLE: &[u8] = b"shamir_extendable";
+
+/// The generator of the Reed-Solomon code over GF(1024) checksumming the mnemonics.
+const RS1024_GEN: [u32; 10] = [
+    0x00E0_E040,
+    0x01C1_C080,
+    0x0383_8100,
+    0x0707_0200,
+    0x0E0E_0009,
+    0x1C0C_2412,
+    0x3808_6C24,
+    0x3090_FC48,
+    0x21B1_F890,
+    0x03F3_F120,
+];
+
+fn words() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORD_LIST.lines().collect())
+}
+
+/// A SLIP-39 share, a member share of one of the groups the master secret was
+/// split into.
+///
+/// Group and member indices start from 0, and thresholds and counts from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Share {
+    /// The random identifier shared by all the shares of a master secret.
+    pub identifier: u16,
+    /// Whether the identifier is left out of the encryption of the master secret,
+    /// so that more shares can later be dealt with a new identifier.
+    pub extendable: bool,
+    /// The encryption of the master secret goes through `10000 << iteration_exponent`
+    /// iterations of PBKDF2.
+    pub iteration_exponent: u8,
+    /// The index of the group of this share.
+    pub group_index: u8,
+    /// The number of groups needed to recover the master secret.
+    pub group_threshold: u8,
+    /// The number of groups the master secret was split into.
+    pub group_count: u8,
+    /// The index of this share within its group.
+    pub member_index: u8,
+    /// The number of member shares needed to recover the secret of the group.
+    pub member_threshold: u8,
+    /// The value of the share.
+    pub value: Vec<u8>,
+}
+
+impl Share {
+    /// Parses a SLIP-39 mnemonic, checking its checksum.
+    ///
+    /// Words are matched regardless of their case, and may be abbreviated to their
+    /// first four letters.
+    pub fn from_mnemonic(mnemonic: &str) -> Result<Share> {
+        let words = words();
+        let indices = mnemonic
+            .split_whitespace()
+            .enumerate()
+            .map(|(position, word)| {
+                find_word(words, word)
+                    .map(|index| index as u32)
+                    .ok_or_else(|| Error::UnknownMnemonicWord(position + 1, word.to_owned()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if indices.len() < MIN_MNEMONIC_LENGTH_WORDS {
+            bail!(Error::ShareParsingError(format!(
+                "The mnemonic must be at least {} words long, found {} words.",
+                MIN_MNEMONIC_LENGTH_WORDS,
+                indices.len()
+            )));
+        }
+        let padding_len = (RADIX_BITS * (indices.len() - METADATA_LENGTH_WORDS)) % 16;
+        if padding_len > 8 {
+            bail!(Error::ShareParsingError(format!(
+                "The mnemonic cannot be {} words long.",
+                indices.len()
+            )));
+        }
+
+        let id_exp = int_from_indices(&indices[..ID_EXP_LENGTH_WORDS]);
+        let extendable = (id_exp >> 4) & 1 == 1;
+        if !rs1024_verify_checksum(customization_string(extendable), &indices) {
+            bail!(Error::InvalidMnemonicChecksum);
+        }
+
+        let params = int_from_indices(&indices[ID_EXP_LENGTH_WORDS..][..PARAMS_LENGTH_WORDS]);
+        let nibble = |shift: u32| ((params >> shift) & 0xF) as u8;
+        let share = Share {
+            identifier: (id_exp >> 5) as u16,
+            extendable,
+            iteration_exponent: (id_exp & 0xF) as u8,
+            group_index: nibble(16),
+            group_threshold: nibble(12) + 1,
+            group_count: nibble(8) + 1,
+            member_index: nibble(4),
+            member_threshold: nibble(0) + 1,
+            value: value_from_indices(
+                &indices[ID_EXP_LENGTH_WORDS + PARAMS_LENGTH_WORDS..]
+                    [..indices.len() - METADATA_LENGTH_WORDS],
+                padding_len,
+            )?,
+        };
+
+        if share.group_count < share.group_threshold {
+            bail!(Error::ShareParsingError(format!(
+                "The group threshold ({}) cannot be greater than the group count ({}).",
+                share.group_threshold, share.group_count
+            )));
+        }
+
+        Ok(share)
+    }
+
+    /// Formats the share as a SLIP-39 mnemonic.
+    ///
+    /// Fails if a field of the share does not fit in the bits the mnemonic holds it
+    /// in: thresholds and counts go from 1 to 16, indices from 0 to 15.
+    pub fn to_mnemonic(&self) -> Result<String> {
+        self.validate()?;
+
+        let id_exp = (u32::from(self.identifier) << 5)
+            | (u32::from(self.extendable) << 4)
+            | u32::from(self.iteration_exponent);
+        let params = (u32::from(self.group_index) << 16)
+            | (u32::from(self.group_threshold - 1) << 12)
+            | (u32::from(self.group_count - 1) << 8)
+            | (u32::from(self.member_index) << 4)
+            | u32::from(self.member_threshold - 1);
+
+        let mut indices = vec![
+            id_exp >> RADIX_BITS,
+            id_exp & RADIX_MASK,
+            params >> RADIX_BITS,
+            params & RADIX_MASK,
+        ];
+
+        // The value is left-padded with zeros up to a whole number of words.
+        let value_bits = self.value.len() * 8;
+        let (mut acc, mut acc_bits) = (
+            0u32,
+            value_bits.div_ceil(RADIX_BITS) * RADIX_BITS - value_bits,
+        );
+        for &byte in &self.value {
+            acc = (acc << 8) | u32::from(byte);
+            acc_bits += 8;
+            if acc_bits >= RADIX_BITS {
+                acc_bits -= RADIX_BITS;
+                indices.push((acc >> acc_bits) & RADIX_MASK);
+                acc &= (1 << acc_bits) - 1;
+            }
+        }
+
+        let checksum = rs1024_create_checksum(customization_string(self.extendable), &indices);
+        indices.extend_from_slice(&checksum);
+
+        let words = words();
+        Ok(indices
+            .into_iter()
+            .map(|index| words[index as usize])
+            .collect::<Vec<_>>()
+            .join(" "))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.iteration_exponent > MAX_ITERATION_EXPONENT {
+            bail!(Error::InvalidIterationExponent(self.iteration_exponent));
+        }
+        let fields = [
+            (self.identifier <= MAX_IDENTIFIER, "identifier"),
+            (self.group_index <= MAX_INDEX, "group index"),
+            (
+                (1..=MAX_INDEX + 1).contains(&self.group_threshold),
+                "group threshold",
+            ),
+            (
+                (1..=MAX_INDEX + 1).contains(&self.group_count),
+                "group count",
+            ),
+            (self.member_index <= MAX_INDEX, "member index"),
+            (
+                (1..=MAX_INDEX + 1).contains(&self.member_threshold),
+                "member threshold",
+            ),
+        ];
+        match fields.iter().find(|(is_valid, _)| !is_valid) {
+            Some(&(_, field)) => bail!(Error::InvalidShareField(field)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn customization_string(extendable: bool) -> &'static [u8] {
+    if extendable {
+        CUSTOMIZATION_STRING_EXTENDABLE
+    } else {
+        CUSTOMIZATION_STRING
+    }
+}
+
+fn int_from_indices(indices: &[u32]) -> u32 {
+    indices
+        .iter()
+        .fold(0, |acc, &index| (acc << RADIX_BITS) | index)
+}
+
+/// Decodes the value held by the given words, whose first `padding_len` bits must be zero.
+fn value_from_indices(indices: &[u32], padding_len: usize) -> Result<Vec<u8>> {
+    let mut value = Vec::with_capacity((indices.len() * RADIX_BITS - padding_len) / 8);
+    let (mut acc, mut acc_bits) = (0u32, 0);
+    for (position, &index) in indices.iter().enumerate() {
+        acc = (acc << RADIX_BITS) | index;
+        acc_bits += RADIX_BITS;
+        if position == 0 {
+            if acc >> (RADIX_BITS - padding_len) != 0 {
+                bail!(Error::ShareParsingError(
+                    "The padding of the share value is not zero.".to_owned()
+                ));
+            }
+            acc_bits -= padding_len;
+        }
+        while acc_bits >= 8 {
+            acc_bits -= 8;
+            value.push((acc >> acc_bits) as u8);
+            acc &= (1 << acc_bits) - 1;
+        }
+    }
+    Ok(value)
+}
+
+fn rs1024_polymod(values: impl Iterator<Item = u32>) -> u32 {
+    let mut chk = 1;
+    for value in values {
+        let b = chk >> 20;
+        chk = ((chk & 0xF_FFFF) << 10) ^ value;
+        for (i, gen) in RS1024_GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= gen;
+            }
+        }
+    }
+    chk
+}
+
+fn rs1024_create_checksum(
+    customization_string: &[u8],
+    data: &[u32],
+) -> [u32; CHECKSUM_LENGTH_WORDS] {
+    let values = customization_string
+        .iter()
+        .map(|&byte| u32::from(byte))
+        .chain(data.iter().copied())
+        .chain([0; CHECKSUM_LENGTH_WORDS]);
+    let polymod = rs1024_polymod(values) ^ 1;
+    [
+        (polymod >> (2 * RADIX_BITS)) & RADIX_MASK,
+        (polymod >> RADIX_BITS) & RADIX_MASK,
+        polymod & RADIX_MASK,
+    ]
+}
+
+fn rs1024_verify_checksum(customization_string: &[u8], data: &[u32]) -> bool {
+    let values = customization_string
+        .iter()
+        .map(|&byte| u32::from(byte))
+        .chain(data.iter().copied());
+    rs1024_polymod(values) == 1
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const MNEMONIC: &str = "duckling enlarge academic academic agency result length solution \
+                            fridge kidney coal piece deal husband erode duke ajar critical \
+                            decision keyboard";
+
+    #[test]
+    fn word_list_is_sorted_and_unambiguous() {
+        let words = words();
+        assert_eq!(words.len(), 1 << RADIX_BITS);
+        for pair in words.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert_ne!(pair[0][..4], pair[1][..4]);
+        }
+    }
+
+    #[test]
+    fn parse_then_format() {
+        let share = Share::from_mnemonic(MNEMONIC).unwrap();
+        assert_eq!(share.identifier, 7945);
+        assert!(!share.extendable);
+        assert_eq!(share.iteration_exponent, 0);
+        assert_eq!(
+            (share.group_index, share.group_threshold, share.group_count),
+            (0, 1, 1)
+        );
+        assert_eq!((share.member_index, share.member_threshold), (0, 1));
+        assert_eq!(share.value.len(), 16);
+        assert_eq!(
+            share.to_mnemonic().unwrap(),
+            MNEMONIC.split_whitespace().collect::<Vec<_>>().join(" ")
+        );
+    }
+
+    #[test]
+    fn format_then_parse() {
+        for len in [16, 18, 20, 32, 64] {
+            let share = Share {
+                identifier: 0x7FFF,
+                extendable: len % 4 == 0,
+                iteration_exponent: 15,
+                group_index: 15,
+                group_threshold: 16,
+                group_count: 16,
+                member_index: 15,
+                member_threshold: 16,
+                value: (0..len).map(|i| 255 - i as u8).collect(),
+            };
+            assert_eq!(
+                Share::from_mnemonic(&share.to_mnemonic().unwrap()).unwrap(),
+                share
+            );
+        }
+    }
+
+    #[test]
+    fn fields_out_of_range_are_rejected() {
+        let share = Share::from_mnemonic(MNEMONIC).unwrap();
+        let invalid = [
+            Share {
+                identifier: 0x8000,
+                ..share.clone()
+            },
+            Share {
+                group_index: 16,
+                ..share.clone()
+            },
+            Share {
+                group_threshold: 0,
+                ..share.clone()
+            },
+            Share {
+                group_count: 17,
+                ..share.clone()
+            },
+            Share {
+                member_index: 16,
+                ..share.clone()
+            },
+            Share {
+                member_threshold: 0,
+                ..share.clone()
+            },
+        ];
+        for share in invalid {
+            assert!(matches!(
+                share.to_mnemonic(),
+                Err(Error::InvalidShareField(_))
+            ));
+        }
+        let share = Share {
+            iteration_exponent: 16,
+            ..share
+        };
+        assert!(matches!(
+            share.to_mnemonic(),
+            Err(Error::InvalidIterationExponent(16))
+        ));
+    }
+
+    #[test]
+    fn abbreviated_words() {
+        let abbreviated = MNEMONIC
+            .split_whitespace()
+            .map(|word| word[..4].to_uppercase())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(
+            Share::from_mnemonic(&abbreviated).unwrap(),
+            Share::from_mnemonic(MNEMONIC).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_checksum() {
+        let mnemonic = MNEMONIC.replace("keyboard", "kidney");
+        match Share::from_mnemonic(&mnemonic) {
+            Err(Error::InvalidMnemonicChecksum) => (),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+}
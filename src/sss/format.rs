@@ -1,92 +1,137 @@
+use crate::armor;
+use crate::compat::sellibitze;
+use crate::encoding;
 use crate::errors::*;
+use crate::mnemonic;
 use crate::proto::wrapped::ShareProto;
-use crate::sss::{Share, HASH_ALGO};
+use crate::proto::SchemeProto;
+use crate::reed_solomon;
+use crate::share::envelope::Envelope;
+use crate::share::version::{decode_share, CURRENT_SHARE_VERSION};
+use crate::sss::{Share, SignaturePair, HASH_ALGO};
 
 use base64::Engine;
 use merkle_sigs::{MerklePublicKey, Proof, PublicKey};
-use prost::Message;
 
 const BASE64_CONFIG: base64::engine::general_purpose::GeneralPurpose =
     base64::engine::general_purpose::STANDARD_NO_PAD;
 
-pub(crate) fn share_to_string(
-    share: Vec<u8>,
+/// The binary encoding of a share, which every format of shares encodes.
+pub(crate) fn share_envelope(
+    share: &[u8],
     threshold: u8,
     share_num: u8,
-    signature_pair: Option<(Vec<Vec<u8>>, Proof<MerklePublicKey>)>,
-) -> String {
+    signature_pair: Option<&SignaturePair>,
+    scheme: SchemeProto,
+) -> Envelope {
     let mut share_protobuf = ShareProto {
-        shamir_data: share,
+        shamir_data: share.to_vec(),
+        version: CURRENT_SHARE_VERSION.into(),
+        scheme: scheme.into(),
         ..Default::default()
     };
 
-    if let Some((signature, proof)) = signature_pair {
-        share_protobuf.signature = signature;
-        share_protobuf.proof = proof.write_to_bytes().unwrap();
+    if let Some(SignaturePair { signature, proof }) = signature_pair {
+        share_protobuf.signature = signature.clone();
+        share_protobuf.proof = proof.clone().write_to_bytes().unwrap();
     }
 
-    let mut buf = Vec::with_capacity(share_protobuf.encoded_len());
-    // Unwrap is safe, since we have reserved sufficient capacity in the vector.
-    share_protobuf.encode(&mut buf).unwrap();
-
-    let proto_buf = buf;
-    let b64_share = BASE64_CONFIG.encode(proto_buf);
-    format!("{}-{}-{}", threshold, share_num, b64_share)
+    Envelope::new(scheme, threshold, share_num, &share_protobuf)
 }
 
 pub(crate) fn share_from_string(s: &str, is_signed: bool) -> Result<Share> {
-    let parts: Vec<_> = s.trim().split('-').collect();
-
-    if parts.len() != SSS_SHARE_PARTS_COUNT {
-        bail! {
-            ErrorKind::ShareParsingError(
-                format!(
-                    "Expected 3 parts separated by a minus sign. Found {}.",
-                    s
-                ),
-            )
-        };
+    share_from_envelope(encoding::decode(s)?, is_signed)
+}
+
+/// Parses a share dealt by `secretshare`, whose data is the share itself in standard
+/// base64, rather than a protobuf message holding it. Returns `None` if the share
+/// cannot be one.
+pub(crate) fn legacy_share_from_string(s: &str) -> Result<Option<Share>> {
+    if armor::is_armored(s) || reed_solomon::is_reed_solomon(s) || mnemonic::is_mnemonic(s) {
+        return Ok(None);
     }
-    let (k, i, p3) = {
-        let mut iter = parts.into_iter();
-        let k = iter.next().unwrap().parse::<u8>()?;
-        let i = iter.next().unwrap().parse::<u8>()?;
-        let p3 = iter.next().unwrap();
-        (k, i, p3)
+    let (k, i, p3) = match encoding::split_parts(s) {
+        Ok(parts) => parts,
+        Err(_) => return Ok(None),
     };
+    let raw_data = match BASE64_CONFIG.decode(p3) {
+        Ok(raw_data) if sellibitze::may_be_legacy_data(&raw_data) => raw_data,
+        _ => return Ok(None),
+    };
+    share_from_parts(k.parse()?, i.parse()?, raw_data, true, false).map(Some)
+}
+
+pub(crate) fn share_from_bytes(bytes: &[u8], is_signed: bool) -> Result<Share> {
+    share_from_envelope(Envelope::from_bytes(bytes)?, is_signed)
+}
+
+fn share_from_envelope(mut envelope: Envelope, is_signed: bool) -> Result<Share> {
+    envelope.expect_scheme(&[
+        SchemeProto::UnspecifiedScheme,
+        SchemeProto::Sss,
+        SchemeProto::WrappedSecrets,
+    ])?;
+    let body = std::mem::take(&mut envelope.body);
+    let share = share_from_parts(envelope.threshold, envelope.id, body, false, is_signed)?;
+    envelope.check_scheme(share.scheme)?;
+    Ok(share)
+}
 
+/// Builds a share out of its threshold, its identifier and its protobuf message, or
+/// its raw data if `is_legacy` is `true`, for shares dealt by `secretshare`.
+fn share_from_parts(
+    k: u8,
+    i: u8,
+    raw_data: Vec<u8>,
+    is_legacy: bool,
+    is_signed: bool,
+) -> Result<Share> {
     if i < 1 {
-        bail!(ErrorKind::ShareParsingInvalidShareId(i))
+        bail!(Error::ShareParsingInvalidShareId(i))
     } else if k < 2 {
-        bail!(ErrorKind::ShareParsingInvalidShareThreshold(k, i))
-    } else if p3.is_empty() {
-        bail!(ErrorKind::ShareParsingErrorEmptyShare(i))
+        bail!(Error::ShareParsingInvalidShareThreshold(k, i))
+    } else if raw_data.is_empty() {
+        bail!(Error::ShareParsingErrorEmptyShare(i))
     }
 
-    let raw_data = BASE64_CONFIG.decode(p3).chain_err(|| {
-        ErrorKind::ShareParsingError("Base64 decoding of data block failed".to_owned())
-    })?;
-
-    let protobuf_data = ShareProto::decode(raw_data.as_slice()).map_err(|e| {
-        ErrorKind::ShareParsingError(format!(
-            "Protobuf decoding of data block failed with error: {} .",
-            e
-        ))
-    })?;
+    let protobuf_data = if is_legacy {
+        ShareProto {
+            shamir_data: raw_data,
+            scheme: SchemeProto::Sss.into(),
+            ..Default::default()
+        }
+    } else {
+        let protobuf_data = decode_share::<ShareProto>(&raw_data)?;
+        if protobuf_data.shamir_data.is_empty() {
+            bail!(Error::ShareParsingErrorEmptyShare(i))
+        }
+        protobuf_data
+    };
 
+    let scheme = protobuf_data.scheme();
     let data = protobuf_data.shamir_data;
 
-    let signature_pair = if is_signed {
-        let p_result = Proof::parse_from_bytes(&protobuf_data.proof, HASH_ALGO);
-
-        let p_opt = p_result.unwrap();
-        let p = p_opt.unwrap();
+    // Unsigned shares carry no proof, in which case we leave it to the signature
+    // verification to report the missing signature.
+    let signature_pair = if is_signed && !protobuf_data.proof.is_empty() {
+        let p = Proof::<Vec<u8>>::parse_from_bytes(&protobuf_data.proof, HASH_ALGO)
+            .map_err(|e| {
+                Error::ShareParsingError(format!(
+                    "Protobuf decoding of signature proof failed with error: {} .",
+                    e
+                ))
+            })?
+            .ok_or_else(|| Error::ShareParsingError("Signature proof is incomplete.".to_owned()))?;
+
+        let public_key = PublicKey::from_vec(p.value, HASH_ALGO).ok_or_else(|| {
+            Error::ShareParsingError("Invalid public key in signature proof.".to_owned())
+        })?;
 
         let proof = Proof {
             algorithm: HASH_ALGO,
             lemma: p.lemma,
             root_hash: p.root_hash,
-            value: MerklePublicKey::new(PublicKey::from_vec(p.value, HASH_ALGO).unwrap()),
+            value: MerklePublicKey::new(public_key),
         };
 
         let signature = protobuf_data.signature;
@@ -100,6 +145,7 @@ pub(crate) fn share_from_string(s: &str, is_signed: bool) -> Result<Share> {
         data,
         threshold: k,
         signature_pair,
+        scheme,
     })
 }
 
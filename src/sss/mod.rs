@@ -1,21 +1,32 @@
 //! SSS provides Shamir's secret sharing with raw data.
+//!
+//! Without the `std` feature, only the typed `Share`s are available, through
+//! `split_secret_shares_rng` and `recover_secret_shares`, and shares cannot be signed.
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use crate::errors::*;
+use crate::secret::Secret;
 
 mod share;
-pub(crate) use self::share::*;
+pub use self::share::Share;
+#[cfg(feature = "std")]
+pub use self::share::SignaturePair;
 
+#[cfg(feature = "std")]
 mod format;
 // pub use self::format::*;
 
 mod scheme;
-pub(crate) use self::scheme::*;
-
-mod encode;
+pub use self::scheme::Sss;
 
-use rand::Rng;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "std")]
 use ring::digest::{Algorithm, SHA512};
 
+#[cfg(feature = "std")]
 static HASH_ALGO: &Algorithm = &SHA512;
 
 /// Performs threshold k-out-of-n Shamir's secret sharing.
@@ -39,6 +50,7 @@ static HASH_ALGO: &Algorithm = &SHA512;
 ///     }
 /// }
 /// ```
+#[cfg(feature = "std")]
 pub fn split_secret(k: u8, n: u8, secret: &[u8], sign_shares: bool) -> Result<Vec<String>> {
     Sss.split_secret(&mut rand::rng(), k, n, secret, sign_shares)
         .map(|shares| shares.into_iter().map(Share::into_string).collect())
@@ -78,7 +90,8 @@ pub fn split_secret(k: u8, n: u8, secret: &[u8], sign_shares: bool) -> Result<Ve
 /// }
 /// # }
 /// ```
-pub fn split_secret_rng<R: Rng>(
+#[cfg(feature = "std")]
+pub fn split_secret_rng<R: RngCore + CryptoRng>(
     rng: &mut R,
     k: u8,
     n: u8,
@@ -111,7 +124,252 @@ pub fn split_secret_rng<R: Rng>(
 ///     }
 /// }
 /// ```
-pub fn recover_secret(shares: &[String], verify_signatures: bool) -> Result<Vec<u8>> {
+#[cfg(feature = "std")]
+pub fn recover_secret(shares: &[String], verify_signatures: bool) -> Result<Secret> {
     let shares = Share::parse_all(shares, verify_signatures)?;
+    Sss::recover_secret(&shares, verify_signatures)
+}
+
+/// Performs threshold k-out-of-n Shamir's secret sharing, returning typed shares.
+///
+/// Uses a `rand::rngs::OsRng` as a source of entropy.
+///
+/// # Examples
+///
+/// ```
+/// use etospheres_labs_rusty_secrets::sss::split_secret_shares;
+///
+/// let secret = "These programs were never about terrorism: they’re about economic spying, \
+///               social control, and diplomatic manipulation. They’re about power.";
+///
+/// let shares = split_secret_shares(7, 10, &secret.as_bytes(), false).unwrap();
+///
+/// for share in shares {
+///     println!("Share {} of threshold {}: {}", share.id, share.threshold, share);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn split_secret_shares(k: u8, n: u8, secret: &[u8], sign_shares: bool) -> Result<Vec<Share>> {
+    Sss.split_secret(&mut rand::rng(), k, n, secret, sign_shares)
+}
+
+/// Performs threshold k-out-of-n Shamir's secret sharing with a custom RNG,
+/// returning typed shares.
+///
+/// Signing the shares requires the `std` feature.
+pub fn split_secret_shares_rng<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    k: u8,
+    n: u8,
+    secret: &[u8],
+    sign_shares: bool,
+) -> Result<Vec<Share>> {
+    Sss.split_secret(rng, k, n, secret, sign_shares)
+}
+
+/// Recovers the secret from the given typed shares of a k-out-of-n Shamir's secret sharing scheme.
+///
+/// At least `k` distinct shares need to be provided to recover the secret.
+/// Verifying the signatures of the shares requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use etospheres_labs_rusty_secrets::sss::{recover_secret_shares, split_secret_shares_rng};
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaChaRng;
+///
+/// let mut rng = ChaChaRng::from_seed([42u8; 32]);
+/// let shares = split_secret_shares_rng(&mut rng, 2, 3, b"Hello, World!", false).unwrap();
+///
+/// match recover_secret_shares(&shares[1..], false) {
+///     Ok(secret) => {
+///         // Do something with the secret
+///     },
+///     Err(e) => {
+///         // Deal with the error
+///     }
+/// }
+/// ```
+pub fn recover_secret_shares(shares: &[Share], verify_signatures: bool) -> Result<Secret> {
     Sss::recover_secret(shares, verify_signatures)
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::gf256::Gf256;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    #[cfg(feature = "std")]
+    use std::collections::HashSet;
+
+    /// Straightforward implementation of the sharing, evaluating one polynomial
+    /// per secret byte, used as a reference for the vectorised one.
+    fn reference_secret_share<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        src: &[u8],
+        k: u8,
+        n: u8,
+    ) -> Vec<Vec<u8>> {
+        let mut result = vec![vec![0u8; src.len()]; n as usize];
+        let mut coeffs = vec![0u8; k as usize];
+        for (c, &s) in src.iter().enumerate() {
+            coeffs[0] = s;
+            rng.fill_bytes(&mut coeffs[1..]);
+            for (i, share) in result.iter_mut().enumerate() {
+                let x = Gf256::from_byte(i as u8 + 1);
+                let y = coeffs.iter().rev().fold(Gf256::zero(), |acc, &coeff| {
+                    Gf256::from_byte(coeff) + acc * x
+                });
+                share[c] = y.to_byte();
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn split_then_recover_yields_original_secret() {
+        let secret = "Hello, World!".to_string().into_bytes();
+
+        let mut rng = ChaChaRng::from_seed([42u8; 32]);
+        let shares = split_secret_shares_rng(&mut rng, 7, 10, &secret, false).unwrap();
+        assert_eq!(shares.len(), 10);
+
+        let recovered = recover_secret_shares(&shares[2..9], false).unwrap();
+        assert_eq!(secret, recovered);
+    }
+
+    #[test]
+    fn shares_built_from_their_data_recover_the_secret() {
+        let secret = b"Hello, World!";
+
+        let mut rng = ChaChaRng::from_seed([42u8; 32]);
+        let shares = reference_secret_share(&mut rng, secret, 3, 5)
+            .into_iter()
+            .zip(1..)
+            .map(|(data, id)| Share::new(id, 3, data))
+            .collect::<Vec<_>>();
+        assert_eq!(shares[0].clone().into_data(), shares[0].data);
+        assert_eq!(
+            recover_secret_shares(&shares[1..4], false).unwrap(),
+            secret[..]
+        );
+    }
+
+    #[test]
+    fn split_matches_reference_implementation() {
+        let secret = include_bytes!("../../benches/resources/1KB.txt");
+
+        for &(k, n) in &[(2, 2), (3, 5), (10, 25), (255, 255)] {
+            let mut rng = ChaChaRng::from_seed([42u8; 32]);
+            let shares = split_secret_shares_rng(&mut rng, k, n, secret, false).unwrap();
+
+            let mut rng = ChaChaRng::from_seed([42u8; 32]);
+            let expected = reference_secret_share(&mut rng, secret, k, n);
+
+            let actual = shares
+                .into_iter()
+                .map(|s| s.data.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn split_large_secret_matches_reference_implementation() {
+        // Spans several chunks when the work is spread across threads.
+        let secret = (0..200_003).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+        let mut rng = ChaChaRng::from_seed([7u8; 32]);
+        let shares = split_secret_shares_rng(&mut rng, 3, 5, &secret, false).unwrap();
+
+        let mut rng = ChaChaRng::from_seed([7u8; 32]);
+        let expected = reference_secret_share(&mut rng, &secret, 3, 5);
+
+        for (share, expected) in shares.iter().zip(&expected) {
+            assert!(share.data == *expected);
+        }
+        assert_eq!(recover_secret_shares(&shares[1..4], false).unwrap(), secret);
+    }
+
+    #[test]
+    fn recover_from_any_subset() {
+        let secret = include_bytes!("../../benches/resources/1KB.txt");
+        let mut rng = ChaChaRng::from_seed([42u8; 32]);
+        let shares = split_secret_shares_rng(&mut rng, 3, 6, secret, false).unwrap();
+
+        for (a, b, c) in [(0, 1, 2), (5, 3, 1), (2, 4, 5), (4, 0, 3)] {
+            let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+            assert_eq!(recover_secret_shares(&subset, false).unwrap(), &secret[..]);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn display_then_parse_yields_same_share() {
+        let secret = "Hello, World!".to_string().into_bytes();
+
+        for &signed in &[false, true] {
+            let shares = split_secret_shares(3, 5, &secret, signed).unwrap();
+
+            for share in &shares {
+                let parsed = share.to_string().parse::<Share>().unwrap();
+                assert_eq!(share, &parsed);
+                assert_eq!(share.signature_pair, parsed.signature_pair);
+                assert_eq!(share.to_string(), parsed.to_string());
+            }
+
+            let raw = shares.iter().map(Share::to_string).collect::<Vec<_>>();
+            assert_eq!(secret, recover_secret(&raw, signed).unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn equality_ignores_signature() {
+        let secret = "Hello, World!".to_string().into_bytes();
+
+        let signed = split_secret_shares(3, 5, &secret, true).unwrap();
+        let unsigned = signed
+            .iter()
+            .cloned()
+            .map(|mut share| {
+                share.signature_pair = None;
+                share
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(signed, unsigned);
+
+        let set = signed.iter().chain(unsigned.iter()).collect::<HashSet<_>>();
+        assert_eq!(set.len(), 5);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn parse_unsigned_share_as_signed_reports_missing_signature() {
+        let share1: Share = "2-1-Cha7s14Q/mSwWko0ittr+/Uf79RHQMIP".parse().unwrap();
+        let share2: Share = "2-4-ChaydsUJDypD9ZWxwvIICh/cmZvzusOF".parse().unwrap();
+        assert!(share1.signature_pair.is_none());
+
+        let err = recover_secret_shares(&[share1, share2], true).unwrap_err();
+        match err {
+            Error::MissingSignature(1) => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "std"))]
+    fn signing_requires_std() {
+        let mut rng = ChaChaRng::from_seed([42u8; 32]);
+        let err = split_secret_shares_rng(&mut rng, 2, 3, b"secret", true).unwrap_err();
+        assert!(matches!(err, Error::SignaturesUnsupported));
+
+        let shares = split_secret_shares_rng(&mut rng, 2, 3, b"secret", false).unwrap();
+        let err = recover_secret_shares(&shares, true).unwrap_err();
+        assert!(matches!(err, Error::SignaturesUnsupported));
+    }
+}
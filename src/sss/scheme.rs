@@ -1,23 +1,39 @@
 //! SSS provides Shamir's secret sharing with raw data.
 
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
 use merkle_sigs::sign_data_vec;
-use rand::Rng;
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::errors::*;
-use crate::lagrange::interpolate_at;
-use crate::share::validation::{validate_share_count, validate_signed_shares};
+use crate::gf256::Gf256;
+use crate::lagrange::barycentric_basis_at;
+#[cfg(feature = "std")]
+use crate::proto::SchemeProto;
+use crate::secret::{Secret, Zeroizing};
+use crate::share::validation::validate_share_count;
+#[cfg(not(feature = "std"))]
+use crate::share::validation::validate_shares;
+#[cfg(feature = "std")]
+use crate::share::validation::validate_signed_shares;
+use crate::simd::{linear_combination, mul_add_assign};
+#[cfg(feature = "std")]
 use crate::sss::format::format_share_for_signing;
-use crate::sss::{Share, HASH_ALGO};
-
-use super::encode::encode_secret_byte;
+use crate::sss::Share;
+#[cfg(feature = "std")]
+use crate::sss::{SignaturePair, HASH_ALGO};
 
 /// SSS provides Shamir's secret sharing with raw data.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
-pub(crate) struct Sss;
+pub struct Sss;
 
 impl Sss {
     /// Performs threshold k-out-of-n Shamir's secret sharing.
-    pub fn split_secret<R: Rng>(
+    pub fn split_secret<R: RngCore + CryptoRng>(
         &self,
         rng: &mut R,
         threshold: u8,
@@ -26,34 +42,20 @@ impl Sss {
         sign_shares: bool,
     ) -> Result<Vec<Share>> {
         let (threshold, shares_count) = validate_share_count(threshold, shares_count)?;
-        let shares = Self::secret_share(rng, secret, threshold, shares_count)?;
-
-        let signatures = if sign_shares {
-            let shares_to_sign = shares
-                .iter()
-                .enumerate()
-                .map(|(i, x)| format_share_for_signing(threshold, (i + 1) as u8, x))
-                .collect::<Vec<_>>();
-
-            let sign = sign_data_vec(&shares_to_sign, HASH_ALGO)
-                .unwrap()
-                .into_iter()
-                .map(Some)
-                .collect::<Vec<_>>();
-
-            Some(sign)
-        } else {
-            None
-        };
+        if secret.is_empty() {
+            bail!(Error::EmptySecret);
+        }
+        let shares = Self::secret_share(rng, secret, threshold, shares_count);
 
-        let sig_pairs = signatures
-            .unwrap_or_else(|| vec![None; shares_count as usize])
-            .into_iter()
-            .map(|sig_pair| sig_pair.map(From::from));
+        #[cfg(not(feature = "std"))]
+        if sign_shares {
+            bail!(Error::SignaturesUnsupported);
+        }
 
-        let shares_and_sigs = shares.into_iter().enumerate().zip(sig_pairs);
+        #[cfg(feature = "std")]
+        let mut sig_pairs = Self::sign_shares(threshold, &shares, sign_shares).into_iter();
 
-        let result = shares_and_sigs.map(|((index, data), signature_pair)| {
+        let result = shares.into_iter().enumerate().map(|(index, data)| {
             // This is actually safe since we alwaays generate less than 256 shares.
             let id = (index + 1) as u8;
 
@@ -61,55 +63,145 @@ impl Sss {
                 id,
                 threshold,
                 data,
-                signature_pair,
+                #[cfg(feature = "std")]
+                signature_pair: sig_pairs.next().unwrap(),
+                #[cfg(feature = "std")]
+                scheme: SchemeProto::Sss,
             }
         });
 
         Ok(result.collect())
     }
 
-    fn secret_share<R: Rng>(
+    /// Signs the given shares if `sign_shares` is `true`, returning one signature pair per share.
+    #[cfg(feature = "std")]
+    fn sign_shares(
+        threshold: u8,
+        shares: &[Vec<u8>],
+        sign_shares: bool,
+    ) -> Vec<Option<SignaturePair>> {
+        if !sign_shares {
+            return vec![None; shares.len()];
+        }
+
+        let shares_to_sign = shares
+            .iter()
+            .enumerate()
+            .map(|(i, x)| format_share_for_signing(threshold, (i + 1) as u8, x))
+            .collect::<Vec<_>>();
+
+        sign_data_vec(&shares_to_sign, HASH_ALGO)
+            .unwrap()
+            .into_iter()
+            .map(|sig_pair| Some(sig_pair.into()))
+            .collect()
+    }
+
+    /// Signs the given shares together, replacing their signatures, if any.
+    #[cfg(feature = "std")]
+    pub(crate) fn resign_shares(shares: &mut [Share]) {
+        let shares_to_sign = shares
+            .iter()
+            .map(|share| format_share_for_signing(share.threshold, share.id, &share.data))
+            .collect::<Vec<_>>();
+
+        let sig_pairs = sign_data_vec(&shares_to_sign, HASH_ALGO).unwrap();
+        for (share, sig_pair) in shares.iter_mut().zip(sig_pairs) {
+            share.signature_pair = Some(sig_pair.into());
+        }
+    }
+
+    /// Evaluates, for each byte of the secret, a random polynomial of degree `threshold - 1`
+    /// whose constant term is that byte, at x = 1, 2, 3, ... `shares_count` (inclusive).
+    ///
+    /// The polynomials are stored as rows of coefficients spanning the whole secret,
+    /// so that each share is computed as the sum of the rows weighted by the powers of
+    /// its identifier, using vectorised operations.
+    fn secret_share<R: RngCore + CryptoRng>(
         rng: &mut R,
         src: &[u8],
         threshold: u8,
         shares_count: u8,
-    ) -> Result<Vec<Vec<u8>>> {
-        let mut result = Vec::with_capacity(shares_count as usize);
-        for _ in 0..(shares_count as usize) {
-            result.push(vec![0u8; src.len()]);
-        }
-        let mut col_in = vec![0u8; threshold as usize];
-        let mut col_out = Vec::with_capacity(shares_count as usize);
-        for (c, &s) in src.iter().enumerate() {
-            col_in[0] = s;
+    ) -> Vec<Vec<u8>> {
+        let slen = src.len();
+        let degree = threshold as usize - 1;
+
+        // The random coefficients are drawn one byte of the secret at a time,
+        // in order to produce the same shares as earlier releases for a given RNG.
+        // They are wiped once the shares have been computed.
+        let mut coeffs = Zeroizing::new(vec![0u8; degree * slen]);
+        let mut column = Zeroizing::new(vec![0u8; degree]);
+        for c in 0..slen {
             // NOTE: switch to `try_fill_bytes` when it lands in a stable release:
             // https://github.com/rust-lang-nursery/rand/commit/230b2258dbd99ff8bd991008c972d923d4b5d10c
-            rng.fill_bytes(&mut col_in[1..]);
-            col_out.clear();
-            encode_secret_byte(&col_in, shares_count, &mut col_out)?;
-            for (&y, share) in col_out.iter().zip(result.iter_mut()) {
-                share[c] = y;
+            rng.fill_bytes(&mut column);
+            for (row, &coeff) in column.iter().enumerate() {
+                coeffs[row * slen + c] = coeff;
             }
         }
-        Ok(result)
+
+        let mut result = vec![src.to_vec(); shares_count as usize];
+
+        #[cfg(not(feature = "parallel"))]
+        for (i, share) in result.iter_mut().enumerate() {
+            Self::evaluate_rows(share, &coeffs, slen, 0, i as u8 + 1);
+        }
+
+        // Each share, and each chunk of a share, only depends on the matching
+        // columns of the coefficients, so they can all be computed independently.
+        #[cfg(feature = "parallel")]
+        result.par_iter_mut().enumerate().for_each(|(i, share)| {
+            share
+                .par_chunks_mut(crate::parallel::CHUNK_LEN)
+                .enumerate()
+                .for_each(|(c, chunk)| {
+                    let offset = c * crate::parallel::CHUNK_LEN;
+                    Self::evaluate_rows(chunk, &coeffs, slen, offset, i as u8 + 1)
+                });
+        });
+
+        result
+    }
+
+    /// Adds to `share` the columns of the random coefficients starting at `offset`,
+    /// weighted by the successive powers of the share identifier `id`.
+    fn evaluate_rows(share: &mut [u8], coeffs: &[u8], slen: usize, offset: usize, id: u8) {
+        let x = Gf256::from_byte(id);
+        let mut x_pow = Gf256::one();
+        for row in coeffs.chunks_exact(slen) {
+            x_pow *= x;
+            mul_add_assign(share, &row[offset..offset + share.len()], x_pow);
+        }
     }
 
     /// Recovers the secret from a k-out-of-n Shamir's secret sharing.
     ///
     /// At least `k` distinct shares need to be provided to recover the share.
-    pub fn recover_secret(shares: Vec<Share>, verify_signatures: bool) -> Result<Vec<u8>> {
-        let (threshold, slen) = validate_signed_shares(&shares, verify_signatures)?;
-
-        let mut col_in = Vec::with_capacity(threshold as usize);
-        let mut secret = Vec::with_capacity(slen);
-        for byteindex in 0..slen {
-            col_in.clear();
-            for s in shares.iter().take(threshold as usize) {
-                col_in.push((s.id, s.data[byteindex]));
+    pub fn recover_secret(shares: &[Share], verify_signatures: bool) -> Result<Secret> {
+        #[cfg(feature = "std")]
+        let (threshold, slen) = validate_signed_shares(shares, verify_signatures)?;
+
+        #[cfg(not(feature = "std"))]
+        let (threshold, slen) = {
+            if verify_signatures {
+                bail!(Error::SignaturesUnsupported);
             }
-            secret.push(interpolate_at(threshold, &col_in));
-        }
-
-        Ok(secret)
+            validate_shares(shares)?
+        };
+        let shares = &shares[..threshold as usize];
+
+        // The Lagrange basis only depends on the share identifiers,
+        // so it is computed once and applied to whole shares at a time.
+        let ids = shares
+            .iter()
+            .map(|share| Gf256::from_byte(share.id))
+            .collect::<Vec<_>>();
+        let basis = barycentric_basis_at(&ids, Gf256::zero());
+        let rows = shares
+            .iter()
+            .map(|share| &share.data[..])
+            .collect::<Vec<_>>();
+
+        Ok(Secret::new(linear_combination(&rows, &basis, slen)))
     }
 }
@@ -1,18 +1,49 @@
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
 use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::fmt;
+#[cfg(feature = "std")]
+use std::str::FromStr;
 
+#[cfg(feature = "std")]
 use merkle_sigs::verify_data_vec_signature;
+#[cfg(feature = "std")]
 use merkle_sigs::{MerklePublicKey, Proof};
 
+#[cfg(feature = "std")]
+use crate::encoding::TextEncoding;
+#[cfg(feature = "std")]
 use crate::errors::*;
-use crate::share::{IsShare, IsSignedShare};
-use crate::sss::format::{format_share_for_signing, share_from_string, share_to_string};
+#[cfg(feature = "std")]
+use crate::proto::SchemeProto;
+#[cfg(feature = "std")]
+use crate::share::envelope::Envelope;
+use crate::share::IsShare;
+#[cfg(feature = "std")]
+use crate::share::IsSignedShare;
+#[cfg(feature = "std")]
+use crate::sss::format::{
+    format_share_for_signing, legacy_share_from_string, share_envelope, share_from_bytes,
+    share_from_string,
+};
+#[cfg(feature = "std")]
+use crate::{armor, mnemonic};
 
-/// A share identified by an `id`, a threshold `k`, a number of total shares `n`,
-/// the `data` held in the share, and the share's `metadata`.
-// #[derive(Clone, Debug, Hash, PartialEq, Eq)]
-// TODO: Write manual instances which ignore the signature / fix merkle_sigs+merkle.rs
+/// A share identified by an `id`, a threshold `k`, the `data` held in the share,
+/// and, if the share is signed, its signature.
+///
+/// Two shares are considered equal if they have the same `id`, `threshold` and `data`,
+/// regardless of their signature.
+///
+/// When the `zeroize` feature is enabled, the share data is wiped when the share is dropped.
+///
+/// The `signature_pair` field only exists with the `std` feature, so shares are built
+/// with `Share::new` rather than field by field, whichever features are enabled.
 #[derive(Clone, Debug)]
-pub(crate) struct Share {
+#[non_exhaustive]
+pub struct Share {
     /// The identifier of the share (varies between 1 and n where n is the total number of generated shares)
     pub id: u8,
     /// The number of shares necessary to recover the secret, aka a threshold
@@ -21,21 +52,111 @@ pub(crate) struct Share {
     pub data: Vec<u8>,
     /// If the share is signed, this fields holds the signature
     /// along with the proof of inclusion into the underlying MerkleTree.
+    #[cfg(feature = "std")]
     pub signature_pair: Option<SignaturePair>,
+    /// The scheme which dealt the share, either `Sss` or `WrappedSecrets`.
+    #[cfg(feature = "std")]
+    pub(crate) scheme: SchemeProto,
 }
 
 impl Share {
-    /// Attempts to parse the given string into a share which should have the given `id`.
-    /// The string `raw` should follow the format of `Share::into_string`.
-    pub(crate) fn from_string(raw: &str, is_signed: bool) -> Result<Self> {
+    /// Builds an unsigned share out of its identifier, its threshold and its data.
+    pub fn new(id: u8, threshold: u8, data: Vec<u8>) -> Self {
+        Share {
+            id,
+            threshold,
+            data,
+            #[cfg(feature = "std")]
+            signature_pair: None,
+            #[cfg(feature = "std")]
+            scheme: SchemeProto::Sss,
+        }
+    }
+
+    /// Takes the data out of the share, which cannot be moved out of it otherwise,
+    /// as shares implement `Drop`.
+    ///
+    /// The returned `Vec` will not be wiped when dropped, even if the `zeroize`
+    /// feature is enabled.
+    pub fn into_data(mut self) -> Vec<u8> {
+        core::mem::take(&mut self.data)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Share {
+    /// Attempts to parse the given string into a share.
+    /// The string `raw` should follow the format of `Share::into_string`,
+    /// `Share::into_mnemonic` or `Share::into_armor`.
+    ///
+    /// The signature held by the share, if any, is only parsed if `is_signed` is `true`.
+    /// Shares dealt by `secretshare` are not accepted, see `compat::sellibitze`.
+    pub fn from_string(raw: &str, is_signed: bool) -> Result<Self> {
         share_from_string(raw, is_signed)
     }
 
+    /// Attempts to parse the given bytes, in the format of `Share::to_bytes`, into a
+    /// share.
+    ///
+    /// The signature held by the share, if any, is only parsed if `is_signed` is `true`.
+    pub fn from_bytes(bytes: &[u8], is_signed: bool) -> Result<Self> {
+        share_from_bytes(bytes, is_signed)
+    }
+
+    /// Attempts to parse the given string into a share dealt by `secretshare`, or
+    /// returns `None` if it cannot be one, see `compat::sellibitze`.
+    pub(crate) fn from_legacy_string(raw: &str) -> Result<Option<Self>> {
+        legacy_share_from_string(raw)
+    }
+
     /// Attempts to parse all the given strings into shares.
     /// Calls out to `Share::from_string`.
+    ///
+    /// Shares dealt by `secretshare` are accepted as such as long as they hold as
+    /// much data as the current shares among them: damaged shares, which are not
+    /// valid protobuf messages either, still fail to parse, and so do sets holding
+    /// no current share.
     pub(crate) fn parse_all(raws: &[String], is_signed: bool) -> Result<Vec<Share>> {
-        raws.iter()
-            .map(|raw| Self::from_string(raw, is_signed))
+        Ok(Self::parse_all_legacy(raws, is_signed, false)?
+            .into_iter()
+            .map(|(share, _)| share)
+            .collect())
+    }
+
+    /// Parses all the given strings into shares as `Share::parse_all` does, along
+    /// with whether each one was dealt by `secretshare`.
+    ///
+    /// If `all_legacy` is `true`, sets holding no current share are accepted too, as
+    /// long as their shares all hold as much data.
+    pub(crate) fn parse_all_legacy(
+        raws: &[String],
+        is_signed: bool,
+        all_legacy: bool,
+    ) -> Result<Vec<(Share, bool)>> {
+        let shares = raws
+            .iter()
+            .map(|raw| match Self::from_legacy_string(raw) {
+                Ok(Some(share)) => Ok((share, true)),
+                _ => Self::from_string(raw, is_signed).map(|share| (share, false)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The data of a share of `secretshare` is as long as that of a current share,
+        // stripped of its protobuf message.
+        let data_len = shares
+            .iter()
+            .find(|(_, is_legacy)| !is_legacy)
+            .or(shares.first().filter(|_| all_legacy))
+            .map(|(share, _)| share.data.len());
+        shares
+            .into_iter()
+            .zip(raws)
+            .map(|((share, is_legacy), raw)| {
+                if is_legacy && Some(share.data.len()) != data_len {
+                    return Self::from_string(raw, is_signed).map(|share| (share, false));
+                }
+                Ok((share, is_legacy))
+            })
             .collect()
     }
 
@@ -56,15 +177,110 @@ impl Share {
     ///   information about the share, and if signed, the signature.
     /// ```
     pub fn into_string(self) -> String {
-        share_to_string(
-            self.data,
+        self.to_string()
+    }
+
+    /// Format the share as `Share::into_string` does, with D in the given encoding
+    /// rather than in standard base64, marked so that `Share::from_string` detects
+    /// it:
+    ///
+    /// ```text
+    /// 2-1-hex.2E24F2797C043FBD4850
+    /// ```
+    pub fn into_string_with(self, encoding: TextEncoding) -> String {
+        self.envelope().to_text(encoding)
+    }
+
+    /// Format the share as a sequence of words, which is easier to write down and
+    /// to read back than `Share::into_string`. `Share::from_string` parses both formats.
+    ///
+    /// The words hold the same bytes as `Share::to_bytes`, along with a checksum
+    /// catching transcription errors.
+    pub fn into_mnemonic(self) -> String {
+        mnemonic::encode(&self.envelope())
+    }
+
+    /// Format the share as an ASCII-armored block, whose checksum catches typos,
+    /// with headers giving its threshold, its identifier, the scheme which dealt it,
+    /// and the given identifier of the deal if any. `Share::from_string` parses it.
+    ///
+    /// ```text
+    /// -----BEGIN RUSTY SECRETS SHARE-----
+    /// Threshold: 2
+    /// Share-Id: 1
+    /// Scheme: Sss
+    ///
+    /// UlNTSAABAgEK...
+    /// =kQ3x
+    /// -----END RUSTY SECRETS SHARE-----
+    /// ```
+    pub fn into_armor(self, deal_id: Option<&str>) -> String {
+        armor::encode(&self.envelope(), deal_id)
+    }
+
+    /// Encode the share into bytes, for storing it without going through strings.
+    ///
+    /// The bytes are the magic bytes `RSSH`, the version of the share format, the
+    /// scheme which dealt the share, its threshold and its identifier, one byte
+    /// each, followed by the protobuf message of the `K-N-D` format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.envelope().to_bytes()
+    }
+
+    /// The binary encoding of the share, which every format of shares encodes.
+    pub(crate) fn envelope(&self) -> Envelope {
+        share_envelope(
+            &self.data,
             self.threshold,
             self.id,
-            self.signature_pair.map(Into::into),
+            self.signature_pair.as_ref(),
+            self.scheme,
         )
     }
 }
 
+#[cfg(feature = "std")]
+impl fmt::Display for Share {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.envelope().to_text(TextEncoding::Base64))
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Share {
+    type Err = Error;
+
+    /// Parses a share formatted as `K-N-D`, along with its signature if it has one.
+    fn from_str(raw: &str) -> Result<Self> {
+        Self::from_string(raw, true)
+    }
+}
+
+impl PartialEq for Share {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.threshold == other.threshold && self.data == other.data
+    }
+}
+
+impl Eq for Share {}
+
+impl Hash for Share {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.threshold.hash(state);
+        self.data.hash(state);
+    }
+}
+
+// Implemented regardless of the `zeroize` feature, so that enabling it does not
+// forbid moving fields out of the share.
+impl Drop for Share {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut self.data);
+    }
+}
+
 impl IsShare for Share {
     fn get_id(&self) -> u8 {
         self.id
@@ -83,6 +299,7 @@ impl IsShare for Share {
     }
 }
 
+#[cfg(feature = "std")]
 impl IsSignedShare for Share {
     type Signature = Option<SignaturePair>;
 
@@ -91,7 +308,7 @@ impl IsSignedShare for Share {
 
         for share in shares {
             if !share.is_signed() {
-                bail!(ErrorKind::MissingSignature(share.get_id()));
+                bail!(Error::MissingSignature(share.get_id()));
             }
 
             let sig_pair = share.signature_pair.as_ref().unwrap();
@@ -104,7 +321,7 @@ impl IsSignedShare for Share {
                 &(signature.to_vec(), proof.clone()),
                 root_hash,
             )
-            .map_err(|e| ErrorKind::InvalidSignature(share.id, e.to_string()))?;
+            .map_err(|e| Error::InvalidSignature(share.id, e.to_string()))?;
 
             rh_compatibility_sets
                 .entry(root_hash)
@@ -117,11 +334,11 @@ impl IsSignedShare for Share {
         let rh_sets = rh_compatibility_sets.keys().count();
 
         match rh_sets {
-            0 => bail!(ErrorKind::EmptyShares),
+            0 => bail!(Error::EmptyShares),
             1 => {} // All shares have the same roothash.
             _ => {
                 bail! {
-                    ErrorKind::IncompatibleSets(
+                    Error::IncompatibleSets(
                         rh_compatibility_sets
                             .values()
                             .map(|x| x.to_owned())
@@ -143,7 +360,8 @@ impl IsSignedShare for Share {
     }
 }
 
-#[derive(Clone, Debug)]
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
 /// Holds the signature along with the proof of inclusion
 /// in the underlying Merkle tree used in the Lamport signature scheme.
 pub struct SignaturePair {
@@ -153,12 +371,14 @@ pub struct SignaturePair {
     pub proof: Proof<MerklePublicKey>,
 }
 
+#[cfg(feature = "std")]
 impl From<SignaturePair> for (Vec<Vec<u8>>, Proof<MerklePublicKey>) {
     fn from(pair: SignaturePair) -> Self {
         (pair.signature, pair.proof)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<(Vec<Vec<u8>>, Proof<MerklePublicKey>)> for SignaturePair {
     fn from(pair: (Vec<Vec<u8>>, Proof<MerklePublicKey>)) -> Self {
         Self {
@@ -168,10 +388,10 @@ impl From<(Vec<Vec<u8>>, Proof<MerklePublicKey>)> for SignaturePair {
     }
 }
 
-// TODO: Uncomment when re-implementating standard traits for `Share`
-// impl Hash for SignaturePair {
-//     fn hash<H: Hasher>(&self, state: &mut H) {
-//         self.signature.hash(state);
-//         self.proof.root_hash.hash(state);
-//     }
-// }
+#[cfg(feature = "std")]
+impl Hash for SignaturePair {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.signature.hash(state);
+        self.proof.root_hash.hash(state);
+    }
+}
@@ -1,5 +1,7 @@
 use ring::digest::{Algorithm, Context};
 
+use crate::secret::Zeroizing;
+
 #[allow(unsafe_code)]
 fn u32_to_bytes(x: u32) -> [u8; 4] {
     // unsafe { transmute(x.to_be()) }
@@ -8,14 +10,14 @@ fn u32_to_bytes(x: u32) -> [u8; 4] {
 
 pub struct VOLHash {
     algorithm: &'static Algorithm,
-    bytes: Vec<u8>,
+    bytes: Zeroizing<Vec<u8>>,
 }
 
 impl VOLHash {
     pub fn new(algorithm: &'static Algorithm) -> VOLHash {
         Self {
             algorithm,
-            bytes: Vec::new(),
+            bytes: Zeroizing::new(Vec::new()),
         }
     }
 
@@ -32,9 +34,13 @@ impl VOLHash {
         ctx.update(&u32_to_bytes(len as u32));
         ctx.update(&self.bytes);
 
-        let mut state = ctx.finish().as_ref().to_vec();
+        let output_len = self.algorithm.output_len();
+        let iter_num = len / output_len;
 
-        let iter_num = len / self.algorithm.output_len();
+        // Reserve the full capacity upfront so that the state is never reallocated,
+        // which would leave copies of it behind.
+        let mut state = Zeroizing::new(Vec::with_capacity(output_len * (iter_num + 1)));
+        state.extend_from_slice(ctx.finish().as_ref());
 
         for i in 0..iter_num {
             let mut inner_ctx = Context::new(self.algorithm);
@@ -47,7 +53,6 @@ impl VOLHash {
 
         assert!(state.len() >= len);
 
-        let src = state.drain(0..len).collect::<Vec<_>>();
-        dest.copy_from_slice(&src);
+        dest.copy_from_slice(&state[0..len]);
     }
 }
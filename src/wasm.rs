@@ -0,0 +1,264 @@
+//! WebAssembly bindings, enabled by the `wasm` feature.
+//!
+//! Secrets are passed to and returned from JavaScript as `Uint8Array`s, while
+//! shares are passed around in their string format.
+//!
+//! Errors are thrown as JavaScript `Error` objects whose `name` is the name of the
+//! corresponding `Error` variant (eg. `"MissingShares"`), and which carry a
+//! `kind` property holding that same name, along with a `shareId` property when
+//! the error relates to a specific share.
+//!
+//! The crate is not built as a `cdylib` by default, which the bindings need. Build
+//! it with `cargo rustc --release --lib --target wasm32-unknown-unknown --features wasm
+//! --crate-type cdylib`, then generate the JavaScript glue with `wasm-bindgen`.
+
+#![allow(missing_docs)]
+
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use crate::dss::thss::MetaData;
+use crate::dss::{ss1, thss, AccessStructure};
+use crate::errors::*;
+use crate::secret::Secret;
+use crate::sss;
+
+/// Performs threshold k-out-of-n Shamir's secret sharing, returning the shares as strings.
+#[wasm_bindgen(js_name = splitSecret)]
+pub fn split_secret(
+    k: u8,
+    n: u8,
+    secret: &[u8],
+    sign_shares: bool,
+) -> std::result::Result<Vec<String>, JsValue> {
+    sss::split_secret(k, n, secret, sign_shares).map_err(to_js_error)
+}
+
+/// Recovers the secret from the given shares, formatted as strings.
+#[wasm_bindgen(js_name = recoverSecret)]
+pub fn recover_secret(
+    shares: Vec<String>,
+    verify_signatures: bool,
+) -> std::result::Result<Uint8Array, JsValue> {
+    sss::recover_secret(&shares, verify_signatures)
+        .map(|secret| to_uint8_array(&secret))
+        .map_err(to_js_error)
+}
+
+/// Parses the given share, without recovering anything, and returns an object
+/// describing it: `{ id, threshold, signed, dataLength }`.
+#[wasm_bindgen(js_name = inspectShare)]
+pub fn inspect_share(share: &str) -> std::result::Result<Object, JsValue> {
+    let share = share.parse::<sss::Share>().map_err(to_js_error)?;
+
+    let info = Object::new();
+    set(&info, "id", share.id.into());
+    set(&info, "threshold", share.threshold.into());
+    set(&info, "signed", share.signature_pair.is_some().into());
+    set(&info, "dataLength", (share.data.len() as u32).into());
+    Ok(info)
+}
+
+/// Performs threshold k-out-of-n secret sharing using the `ThSS` scheme.
+///
+/// `metadata`, if given, must be an object whose values are all strings.
+#[wasm_bindgen(js_name = thssSplitSecret)]
+pub fn thss_split_secret(
+    k: u8,
+    n: u8,
+    secret: &[u8],
+    metadata: Option<Object>,
+) -> std::result::Result<Vec<String>, JsValue> {
+    let metadata = metadata.map(to_metadata).transpose()?;
+    let shares = thss::split_secret(k, n, secret, &metadata).map_err(to_js_error)?;
+    Ok(shares.into_iter().map(thss::Share::into_string).collect())
+}
+
+/// Recovers the secret from the given `ThSS` shares, returning an object of the form
+/// `{ secret, threshold, sharesCount, metadata }`.
+#[wasm_bindgen(js_name = thssRecoverSecret)]
+pub fn thss_recover_secret(shares: Vec<String>) -> std::result::Result<Object, JsValue> {
+    let shares = shares
+        .iter()
+        .map(|raw| thss::Share::from_string(raw))
+        .collect::<Result<Vec<_>>>()
+        .map_err(to_js_error)?;
+    let recovered = thss::recover_secret(&shares).map_err(to_js_error)?;
+    Ok(to_recovered(recovered))
+}
+
+/// Performs threshold k-out-of-n deterministic secret sharing using the `SS1` scheme.
+///
+/// When `reproducible` is `true`, splitting the same secret twice yields the same shares.
+/// `metadata`, if given, must be an object whose values are all strings.
+#[wasm_bindgen(js_name = ss1SplitSecret)]
+pub fn ss1_split_secret(
+    k: u8,
+    n: u8,
+    secret: &[u8],
+    reproducible: bool,
+    metadata: Option<Object>,
+) -> std::result::Result<Vec<String>, JsValue> {
+    let reproducibility = if reproducible {
+        ss1::Reproducibility::reproducible()
+    } else {
+        ss1::Reproducibility::none()
+    };
+    let metadata = metadata.map(to_metadata).transpose()?;
+    let shares =
+        ss1::split_secret(k, n, secret, reproducibility, &metadata).map_err(to_js_error)?;
+    Ok(shares.into_iter().map(ss1::Share::into_string).collect())
+}
+
+/// Recovers the secret from the given `SS1` shares, returning an object of the form
+/// `{ secret, threshold, sharesCount, metadata }`.
+#[wasm_bindgen(js_name = ss1RecoverSecret)]
+pub fn ss1_recover_secret(shares: Vec<String>) -> std::result::Result<Object, JsValue> {
+    let shares = shares
+        .iter()
+        .map(|raw| ss1::Share::from_string(raw))
+        .collect::<Result<Vec<_>>>()
+        .map_err(to_js_error)?;
+    let recovered = ss1::recover_secret(&shares).map_err(to_js_error)?;
+    Ok(to_recovered(recovered))
+}
+
+fn set(target: &Object, key: &str, value: JsValue) {
+    // Setting a property on a plain object cannot fail.
+    let _ = Reflect::set(target, &JsValue::from_str(key), &value);
+}
+
+fn to_uint8_array(secret: &Secret) -> Uint8Array {
+    Uint8Array::from(secret.as_bytes())
+}
+
+fn to_metadata(object: Object) -> std::result::Result<MetaData, JsValue> {
+    let mut metadata = MetaData::new();
+    for entry in Object::entries(&object).iter() {
+        let entry = Array::from(&entry);
+        let (key, value) = (entry.get(0), entry.get(1));
+        let value = value.as_string().ok_or_else(|| {
+            let error = js_sys::Error::new("Metadata values must be strings");
+            error.set_name("TypeError");
+            JsValue::from(error)
+        })?;
+        metadata
+            .tags
+            .insert(key.as_string().unwrap_or_default(), value);
+    }
+    Ok(metadata)
+}
+
+fn to_recovered(
+    (secret, access_structure, metadata): (Secret, AccessStructure, Option<MetaData>),
+) -> Object {
+    let result = Object::new();
+    set(&result, "secret", to_uint8_array(&secret).into());
+    set(&result, "threshold", access_structure.threshold.into());
+    set(&result, "sharesCount", access_structure.shares_count.into());
+
+    let tags = metadata.map(|metadata| {
+        let tags = Object::new();
+        for (key, value) in &metadata.tags {
+            set(&tags, key, JsValue::from_str(value));
+        }
+        JsValue::from(tags)
+    });
+    set(&result, "metadata", tags.unwrap_or(JsValue::UNDEFINED));
+
+    result
+}
+
+/// Converts the given error into a JavaScript `Error`, named after its kind.
+fn to_js_error(err: Error) -> JsValue {
+    let error = js_sys::Error::new(&err.to_string());
+    let kind = kind_name(&err);
+    error.set_name(kind);
+    set(&error, "kind", JsValue::from_str(kind));
+    if let Some(share_id) = share_id(&err) {
+        set(&error, "shareId", share_id.into());
+    }
+    error.into()
+}
+
+fn kind_name(err: &Error) -> &'static str {
+    match *err {
+        Error::ThresholdTooBig(..) => "ThresholdTooBig",
+        Error::ThresholdTooSmall(..) => "ThresholdTooSmall",
+        Error::SecretTooBig(..) => "SecretTooBig",
+        Error::InvalidShareCountMax(..) => "InvalidShareCountMax",
+        Error::InvalidShareCountMin(..) => "InvalidShareCountMin",
+        Error::EmptySecret => "EmptySecret",
+        Error::EmptyShares => "EmptyShares",
+        Error::IncompatibleSets(..) => "IncompatibleSets",
+        Error::MissingShares(..) => "MissingShares",
+        Error::InvalidSignature(..) => "InvalidSignature",
+        Error::MissingSignature(..) => "MissingSignature",
+        Error::SecretDeserializationError(..) => "SecretDeserializationError",
+        Error::ShareParsingError(..) => "ShareParsingError",
+        Error::ShareParsingErrorEmptyShare(..) => "ShareParsingErrorEmptyShare",
+        Error::ShareParsingErrorBase64(..) => "ShareParsingErrorBase64",
+        Error::ShareParsingErrorProtobuf(..) => "ShareParsingErrorProtobuf",
+        Error::ShareParsingInvalidShareId(..) => "ShareParsingInvalidShareId",
+        Error::ShareParsingInvalidShareThreshold(..) => "ShareParsingInvalidShareThreshold",
+        Error::InvalidSS1Parameters(..) => "InvalidSS1Parameters",
+        Error::InvalidSplitParametersZero(..) => "InvalidSplitParametersZero",
+        Error::MismatchingShares(..) => "MismatchingShares",
+        Error::CannotGenerateRandomNumbers => "CannotGenerateRandomNumbers",
+        Error::DuplicateShareId(..) => "DuplicateShareId",
+        Error::InconsistentSecretLengths(..) => "InconsistentSecretLengths",
+        Error::InconsistentShares => "InconsistentShares",
+        Error::InconsistentThresholds(..) => "InconsistentThresholds",
+        Error::Io(..) => "Io",
+        Error::SignaturesUnsupported => "SignaturesUnsupported",
+        Error::UnsupportedOption(..) => "UnsupportedOption",
+        Error::InconsistentSchemes => "InconsistentSchemes",
+        Error::UnknownMnemonicWord(..) => "UnknownMnemonicWord",
+        Error::InvalidMnemonicChecksum => "InvalidMnemonicChecksum",
+        Error::InvalidMasterSecretLength(..) => "InvalidMasterSecretLength",
+        Error::InvalidIterationExponent(..) => "InvalidIterationExponent",
+        Error::InvalidPassphrase => "InvalidPassphrase",
+        Error::InvalidShareField(..) => "InvalidShareField",
+        Error::InvalidSecurityLevel(..) => "InvalidSecurityLevel",
+        Error::UnsupportedShareVersion(..) => "UnsupportedShareVersion",
+        Error::InvalidArmorChecksum(..) => "InvalidArmorChecksum",
+        Error::ShareTooBig(..) => "ShareTooBig",
+        Error::InvalidCorrectionCount(..) => "InvalidCorrectionCount",
+        Error::UncorrectableShare => "UncorrectableShare",
+        Error::InvalidMimeType(..) => "InvalidMimeType",
+        Error::TooManyShares(..) => "TooManyShares",
+        Error::UnsupportedSecretVersion(..) => "UnsupportedSecretVersion",
+        Error::IntegerParsingError(..) => "IntegerParsingError",
+    }
+}
+
+fn share_id(err: &Error) -> Option<u8> {
+    match *err {
+        Error::InvalidSignature(id, _)
+        | Error::MissingSignature(id)
+        | Error::ShareParsingErrorEmptyShare(id)
+        | Error::ShareParsingInvalidShareId(id)
+        | Error::ShareParsingInvalidShareThreshold(_, id)
+        | Error::DuplicateShareId(id)
+        | Error::InconsistentSecretLengths(id, ..)
+        | Error::InconsistentThresholds(id, ..) => Some(id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn kind_names_match_variants() {
+        let err = Error::MissingShares(1, 2);
+        assert_eq!(kind_name(&err), "MissingShares");
+        assert_eq!(share_id(&err), None);
+
+        let err = Error::DuplicateShareId(3);
+        assert_eq!(kind_name(&err), "DuplicateShareId");
+        assert_eq!(share_id(&err), Some(3));
+    }
+}
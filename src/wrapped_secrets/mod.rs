@@ -1,12 +1,18 @@
-//! (Beta) `wrapped_secrets` provides Shamir's secret sharing with a wrapped secret. It currently offers versioning and MIME information about the data.
+//! (Beta) `wrapped_secrets` provides Shamir's secret sharing with a wrapped secret. It currently offers versioning and MIME information about the data,
+//! along with its filename, creation time and labels, which `scheme::SplitOptions` sets.
 
 use crate::errors::*;
-use crate::proto::wrapped::SecretProto;
 
-use rand::Rng;
+use rand_core::{CryptoRng, RngCore};
 
 mod scheme;
-pub(crate) use self::scheme::*;
+pub use self::scheme::WrappedSecrets;
+
+mod secret;
+pub(crate) use self::secret::unix_time;
+pub use self::secret::WrappedSecret;
+
+pub use crate::sss::Share;
 
 /// Performs threshold k-out-of-n Shamir's secret sharing.
 ///
@@ -87,7 +93,7 @@ pub fn split_secret(
 /// }
 /// # }
 /// ```
-pub fn split_secret_rng<R: Rng>(
+pub fn split_secret_rng<R: RngCore + CryptoRng>(
     rng: &mut R,
     k: u8,
     n: u8,
@@ -115,14 +121,59 @@ pub fn split_secret_rng<R: Rng>(
 ///
 /// match recover_secret(&shares, false) {
 ///     Ok(secret) => {
-///         // Do something with the secret
+///         // Do something with `secret.data`, of type `secret.mime_type()`
 ///     },
 ///     Err(e) => {
 ///         // Deal with the error
 ///     }
 /// }
 /// ```
-pub fn recover_secret(shares: &[String], verify_signatures: bool) -> Result<SecretProto> {
+pub fn recover_secret(shares: &[String], verify_signatures: bool) -> Result<WrappedSecret> {
     let shares = Share::parse_all(shares, verify_signatures)?;
+    WrappedSecrets::recover_secret(&shares, verify_signatures)
+}
+
+/// Performs threshold k-out-of-n Shamir's secret sharing, returning typed shares.
+///
+/// Uses a `rand::rngs::OsRng` as a source of entropy.
+pub fn split_secret_shares(
+    k: u8,
+    n: u8,
+    secret: &[u8],
+    mime_type: Option<String>,
+    sign_shares: bool,
+) -> Result<Vec<Share>> {
+    WrappedSecrets.split_secret(&mut rand::rng(), k, n, secret, mime_type, sign_shares)
+}
+
+/// Performs threshold k-out-of-n Shamir's secret sharing with a custom RNG,
+/// returning typed shares.
+pub fn split_secret_shares_rng<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    k: u8,
+    n: u8,
+    secret: &[u8],
+    mime_type: Option<String>,
+    sign_shares: bool,
+) -> Result<Vec<Share>> {
+    WrappedSecrets.split_secret(rng, k, n, secret, mime_type, sign_shares)
+}
+
+/// Recovers the secret from the given typed shares of a k-out-of-n Shamir's secret sharing.
+///
+/// At least `k` distinct shares need to be provided to recover the share.
+///
+/// # Examples
+///
+/// ```rust
+/// use etospheres_labs_rusty_secrets::wrapped_secrets::{self, recover_secret_shares};
+///
+/// let secret = b"Hello, World!";
+/// let shares = wrapped_secrets::split_secret_shares(3, 5, secret, None, true).unwrap();
+///
+/// let recovered = recover_secret_shares(&shares[1..4], true).unwrap();
+/// assert_eq!(recovered.data, secret[..]);
+/// ```
+pub fn recover_secret_shares(shares: &[Share], verify_signatures: bool) -> Result<WrappedSecret> {
     WrappedSecrets::recover_secret(shares, verify_signatures)
 }
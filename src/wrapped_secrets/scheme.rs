@@ -1,19 +1,24 @@
+use super::secret::{parse_mime_type, WrappedSecret, CURRENT_SECRET_VERSION};
 use crate::errors::*;
 use crate::proto::wrapped::SecretProto;
-use crate::proto::VersionProto;
+use crate::proto::SchemeProto;
 use crate::sss::Sss;
 
 use prost::Message;
-use rand::Rng;
+use rand_core::{CryptoRng, RngCore};
 
-pub(crate) use crate::sss::Share;
+use crate::sss::Share;
 
+/// Shamir's secret sharing of a secret wrapped along with its MIME type.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
-pub(crate) struct WrappedSecrets;
+pub struct WrappedSecrets;
 
 impl WrappedSecrets {
     /// Performs threshold k-out-of-n Shamir's secret sharing.
-    pub fn split_secret<R: Rng>(
+    ///
+    /// The MIME type, if any, must be of the form `type/subtype`, with optional
+    /// parameters, see `Error::InvalidMimeType`.
+    pub fn split_secret<R: RngCore + CryptoRng>(
         &self,
         rng: &mut R,
         k: u8,
@@ -22,27 +27,49 @@ impl WrappedSecrets {
         mime_type: Option<String>,
         sign_shares: bool,
     ) -> Result<Vec<Share>> {
-        let mut rusty_secret = SecretProto::default();
-        rusty_secret.set_version(VersionProto::InitialRelease);
-        rusty_secret.secret = secret.to_owned();
-
+        let mut rusty_secret = SecretProto {
+            secret: secret.to_owned(),
+            ..Default::default()
+        };
         if let Some(mt) = mime_type {
             rusty_secret.mime_type = mt;
         }
+        self.split_wrapped(rng, k, n, rusty_secret, sign_shares)
+    }
+
+    /// Splits the given secret, wrapped along with its information.
+    pub(crate) fn split_wrapped<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        k: u8,
+        n: u8,
+        mut rusty_secret: SecretProto,
+        sign_shares: bool,
+    ) -> Result<Vec<Share>> {
+        if !rusty_secret.mime_type.is_empty() {
+            parse_mime_type(&rusty_secret.mime_type)?;
+        }
+        rusty_secret.set_version(CURRENT_SECRET_VERSION);
 
         let mut buf = Vec::with_capacity(rusty_secret.encoded_len());
         // Unwrap is safe, since we have reserved sufficient capacity in the vector.
         rusty_secret.encode(&mut buf).unwrap();
 
-        Sss.split_secret(rng, k, n, buf.as_slice(), sign_shares)
+        let mut shares = Sss.split_secret(rng, k, n, buf.as_slice(), sign_shares)?;
+        for share in &mut shares {
+            share.scheme = SchemeProto::WrappedSecrets;
+        }
+        Ok(shares)
     }
 
     /// Recovers the secret from a k-out-of-n Shamir's secret sharing.
     ///
     /// At least `k` distinct shares need to be provided to recover the share.
-    pub fn recover_secret(shares: Vec<Share>, verify_signatures: bool) -> Result<SecretProto> {
+    pub fn recover_secret(shares: &[Share], verify_signatures: bool) -> Result<WrappedSecret> {
         let secret = Sss::recover_secret(shares, verify_signatures)?;
 
-        SecretProto::decode(secret.as_slice()).chain_err(|| ErrorKind::SecretDeserializationError)
+        SecretProto::decode(secret.as_bytes())
+            .map_err(Error::SecretDeserializationError)
+            .and_then(WrappedSecret::from_proto)
     }
 }
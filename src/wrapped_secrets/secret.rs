@@ -0,0 +1,146 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use mime::Mime;
+
+use crate::errors::*;
+use crate::proto::wrapped::SecretProto;
+use crate::proto::VersionProto;
+use crate::secret::Secret;
+use crate::share::version::check_version;
+
+/// The version of the format of the secrets wrapped by this release.
+pub(crate) const CURRENT_SECRET_VERSION: VersionProto = VersionProto::InitialRelease;
+
+/// A secret recovered from `WrappedSecrets` shares, along with the information
+/// it was wrapped with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WrappedSecret {
+    /// The secret itself.
+    pub data: Secret,
+    /// The MIME type of the secret as it was recorded, if it was given one. Secrets
+    /// wrapped before MIME types were validated may hold one which is not valid, see
+    /// `WrappedSecret::mime_type`.
+    pub raw_mime_type: Option<String>,
+    /// The version of the format the secret was wrapped in.
+    pub version: u32,
+    /// When the secret was split, to the second, if recorded.
+    pub created_at: Option<SystemTime>,
+    /// The name of the file holding the secret, if any.
+    pub filename: Option<String>,
+    /// The labels describing the secret.
+    pub labels: Vec<String>,
+}
+
+impl WrappedSecret {
+    /// The MIME type of the secret, if it was given a valid one.
+    pub fn mime_type(&self) -> Option<Mime> {
+        self.raw_mime_type
+            .as_deref()
+            .and_then(|mime_type| parse_mime_type(mime_type).ok())
+    }
+
+    /// Reads the secret out of its protobuf message.
+    ///
+    /// Secrets wrapped before MIME types were validated may hold an invalid one,
+    /// which is kept as it is rather than failing the recovery of the secret.
+    pub(crate) fn from_proto(mut proto: SecretProto) -> Result<Self> {
+        check_version(
+            proto.version,
+            CURRENT_SECRET_VERSION,
+            Error::UnsupportedSecretVersion,
+        )?;
+        Ok(WrappedSecret {
+            data: Secret::new(std::mem::take(&mut proto.secret)),
+            raw_mime_type: Some(proto.mime_type).filter(|mime_type| !mime_type.is_empty()),
+            // The version was checked above.
+            version: proto.version as u32,
+            created_at: match proto.created_at {
+                0 => None,
+                secs => UNIX_EPOCH.checked_add(Duration::from_secs(secs)),
+            },
+            filename: Some(proto.filename).filter(|filename| !filename.is_empty()),
+            labels: proto.labels,
+        })
+    }
+}
+
+/// Parses the MIME type a secret is wrapped with. `mime` lets the subtype be empty,
+/// which is rejected here.
+pub(crate) fn parse_mime_type(mime_type: &str) -> Result<Mime> {
+    mime_type
+        .parse::<Mime>()
+        .ok()
+        .filter(|mime| !mime.subtype().as_str().is_empty())
+        .ok_or_else(|| Error::InvalidMimeType(mime_type.to_owned()))
+}
+
+/// The number of seconds since the Unix epoch, as recorded in the protobuf message.
+pub(crate) fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn from_proto() {
+        let proto = SecretProto {
+            secret: b"secret".to_vec(),
+            mime_type: "text/plain; charset=utf-8".to_owned(),
+            filename: "notes.txt".to_owned(),
+            created_at: 1_700_000_000,
+            labels: vec!["backup".to_owned()],
+            ..Default::default()
+        };
+        let secret = WrappedSecret::from_proto(proto).unwrap();
+        assert_eq!(secret.data, b"secret"[..]);
+        assert_eq!(secret.mime_type().unwrap().essence_str(), "text/plain");
+        assert_eq!(
+            secret.raw_mime_type.as_deref(),
+            Some("text/plain; charset=utf-8")
+        );
+        assert_eq!(secret.version, 0);
+        assert_eq!(unix_time(secret.created_at.unwrap()), 1_700_000_000);
+        assert_eq!(secret.filename.as_deref(), Some("notes.txt"));
+        assert_eq!(secret.labels, ["backup"]);
+
+        let secret = WrappedSecret::from_proto(SecretProto::default()).unwrap();
+        assert_eq!(secret.mime_type(), None);
+        assert_eq!(secret.raw_mime_type, None);
+        assert_eq!(secret.created_at, None);
+        assert_eq!(secret.filename, None);
+
+        let proto = SecretProto {
+            mime_type: "not a MIME type".to_owned(),
+            ..Default::default()
+        };
+        let secret = WrappedSecret::from_proto(proto).unwrap();
+        assert_eq!(secret.mime_type(), None);
+        assert_eq!(secret.raw_mime_type.as_deref(), Some("not a MIME type"));
+
+        let proto = SecretProto {
+            version: 3,
+            ..Default::default()
+        };
+        assert!(matches!(
+            WrappedSecret::from_proto(proto),
+            Err(Error::UnsupportedSecretVersion(3))
+        ));
+    }
+
+    #[test]
+    fn mime_types_are_validated() {
+        assert!(parse_mime_type("application/json").is_ok());
+        assert!(parse_mime_type("text/html; charset=utf-8").is_ok());
+        for invalid in ["", "text", "text/", "/plain", "text plain"] {
+            assert!(matches!(
+                parse_mime_type(invalid),
+                Err(Error::InvalidMimeType(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "std")]
+
+use etospheres_labs_rusty_secrets::scheme::{Scheme, Signing, SplitOptions};
+use etospheres_labs_rusty_secrets::sss::{self, Share, Sss};
+use etospheres_labs_rusty_secrets::wrapped_secrets::WrappedSecrets;
+
+const SECRET: &[u8] = b"These programs were never about terrorism.";
+
+#[test]
+fn test_sss_shares_to_bytes_and_back() {
+    let options = SplitOptions::new(2, 3).signing(Signing::Merkle);
+    let raws = Sss.split(SECRET, options).unwrap();
+
+    let shares = raws
+        .iter()
+        .map(|raw| {
+            let bytes = Share::from_string(raw, true).unwrap().to_bytes();
+            assert!(bytes.starts_with(b"RSSH"));
+            let share = Share::from_bytes(&bytes, true).unwrap();
+            assert_eq!(&share.to_string(), raw);
+            share
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sss::recover_secret_shares(&shares[1..], true).unwrap(),
+        SECRET
+    );
+}
+
+#[test]
+fn test_binary_shares_are_smaller() {
+    let raws = WrappedSecrets
+        .split(SECRET, SplitOptions::new(2, 3).mime_type("text/plain"))
+        .unwrap();
+    let share = Share::from_string(&raws[0], false).unwrap();
+    assert!(share.to_bytes().len() < raws[0].len());
+}
+
+#[test]
+#[should_panic(expected = "ShareParsingError")]
+fn test_sss_share_from_other_bytes() {
+    Share::from_bytes(b"2-1-CgMBAgM", false).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "UnsupportedShareVersion(1)")]
+fn test_share_bytes_of_newer_version() {
+    let raws = Sss.split(SECRET, SplitOptions::new(2, 3)).unwrap();
+    let mut bytes = Share::from_string(&raws[0], false).unwrap().to_bytes();
+    bytes[4] = 1;
+    Share::from_bytes(&bytes, false).unwrap();
+}
+
+#[cfg(feature = "dss")]
+mod dss {
+
+    use super::SECRET;
+    use etospheres_labs_rusty_secrets::dss::ss1::{self, SS1};
+    use etospheres_labs_rusty_secrets::dss::thss::{self, ThSS};
+    use etospheres_labs_rusty_secrets::scheme::{Scheme, SplitOptions};
+
+    #[test]
+    fn test_dss_shares_to_bytes_and_back() {
+        let raws = ThSS::default()
+            .split(SECRET, SplitOptions::new(2, 3))
+            .unwrap();
+        let shares = raws
+            .iter()
+            .map(|raw| {
+                let share = thss::Share::from_string(raw).unwrap();
+                let decoded = thss::Share::from_bytes(&share.to_bytes()).unwrap();
+                assert_eq!(decoded, share);
+                decoded
+            })
+            .collect::<Vec<_>>();
+        let (secret, ..) = ThSS::default().recover_secret(&shares[..2]).unwrap();
+        assert_eq!(secret, SECRET);
+
+        let raws = SS1::default()
+            .split(SECRET, SplitOptions::new(2, 3))
+            .unwrap();
+        let share = ss1::Share::from_string(&raws[0]).unwrap();
+        let bytes = share.to_bytes();
+        assert_eq!(ss1::Share::from_bytes(&bytes).unwrap(), share);
+        assert_eq!(share.into_string(), raws[0]);
+
+        // The binary encoding records the scheme.
+        assert!(thss::Share::from_bytes(&bytes).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(all(feature = "capi", unix))]
+
+//! Builds the library as a `cdylib`, compiles `tests/capi/harness.c` against it and
+//! the generated header, then runs it.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn c_harness() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let out_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let harness = out_dir.join("capi_harness");
+
+    // The crate is not built as a `cdylib` by default. It is built in a target
+    // directory of its own, as the one of the tests is locked while they run.
+    let target_dir = out_dir.join("capi");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(&manifest_dir)
+        .args([
+            "rustc",
+            "--lib",
+            "--features",
+            "capi",
+            "--crate-type",
+            "cdylib",
+        ])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "failed to build the cdylib");
+    let lib_dir = target_dir.join("debug");
+
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(cc)
+        .arg(manifest_dir.join("tests/capi/harness.c"))
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-letospheres_labs_rusty_secrets")
+        .arg("-o")
+        .arg(&harness)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "failed to compile the C harness");
+
+    // Cargo points the library path at `target/<profile>`, which may hold another
+    // build of the library, so rely on the rpath alone.
+    let output = Command::new(&harness)
+        .env_remove("LD_LIBRARY_PATH")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "C harness failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,187 @@
+/* Exercises the C bindings through `include/rusty_secrets.h`, see `tests/capi.rs`. */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "rusty_secrets.h"
+
+#define CHECK(cond)                                                            \
+  do {                                                                         \
+    if (!(cond)) {                                                             \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+      return 1;                                                                \
+    }                                                                          \
+  } while (0)
+
+#define CHECK_CODE(expected, call)                                             \
+  do {                                                                         \
+    RsError code_ = (call);                                                    \
+    if (code_ != (expected)) {                                                 \
+      fprintf(stderr, "%s:%d: expected %s, got %d (%s)\n", __FILE__, __LINE__, \
+              #expected, (int)code_, rs_error_message(code_));                 \
+      return 1;                                                                \
+    }                                                                          \
+  } while (0)
+
+static const char SECRET[] = "These programs were never about terrorism";
+#define SECRET_LEN (sizeof(SECRET) - 1)
+
+/* Keeps the first `count` lines of `shares`, in place. */
+static void keep_lines(char *shares, int count) {
+  char *cursor = shares;
+  for (int i = 0; i < count && cursor != NULL; i++) {
+    cursor = strchr(cursor, '\n');
+    if (cursor != NULL && i < count - 1) {
+      cursor++;
+    }
+  }
+  if (cursor != NULL) {
+    *cursor = '\0';
+  }
+}
+
+static int test_sss_with(char *shares, size_t shares_len, uint8_t *secret,
+                         size_t secret_len) {
+  size_t written = 0;
+
+  CHECK_CODE(RS_ERROR_OK,
+             rs_sss_split_secret(3, 5, (const uint8_t *)SECRET, SECRET_LEN,
+                                 true, shares, shares_len, &written));
+  CHECK(strlen(shares) + 1 == written);
+
+  keep_lines(shares, 3);
+  CHECK_CODE(RS_ERROR_OK, rs_sss_recover_secret(shares, true, secret,
+                                                secret_len, &written));
+  CHECK(written == SECRET_LEN);
+  CHECK(memcmp(secret, SECRET, SECRET_LEN) == 0);
+
+  /* Too small a buffer still reports the size of the secret. */
+  CHECK_CODE(RS_ERROR_BUFFER_TOO_SMALL,
+             rs_sss_recover_secret(shares, true, secret, 4, &written));
+  CHECK(written == SECRET_LEN);
+
+  keep_lines(shares, 2);
+  CHECK_CODE(RS_ERROR_MISSING_SHARES,
+             rs_sss_recover_secret(shares, true, secret, secret_len,
+                                   &written));
+  return 0;
+}
+
+static int test_sss(void) {
+  char *shares;
+  uint8_t secret[256];
+  size_t needed = 0;
+  int failed;
+
+  /* Query the size needed for the shares first. */
+  CHECK_CODE(RS_ERROR_BUFFER_TOO_SMALL,
+             rs_sss_split_secret(3, 5, (const uint8_t *)SECRET, SECRET_LEN,
+                                 true, NULL, 0, &needed));
+  CHECK(needed > 0);
+
+  shares = malloc(needed);
+  CHECK(shares != NULL);
+  failed = test_sss_with(shares, needed, secret, sizeof(secret));
+  free(shares);
+  return failed;
+}
+
+static int test_wrapped(void) {
+  char shares[8192];
+  uint8_t secret[256];
+  char mime_type[64];
+  size_t written = 0, mime_type_written = 0;
+
+  CHECK_CODE(RS_ERROR_OK,
+             rs_wrapped_split_secret(2, 3, (const uint8_t *)SECRET, SECRET_LEN,
+                                     "text/plain", false, shares,
+                                     sizeof(shares), &written));
+  keep_lines(shares, 2);
+  CHECK_CODE(RS_ERROR_OK,
+             rs_wrapped_recover_secret(shares, false, secret, sizeof(secret),
+                                       &written, mime_type, sizeof(mime_type),
+                                       &mime_type_written));
+  CHECK(written == SECRET_LEN);
+  CHECK(memcmp(secret, SECRET, SECRET_LEN) == 0);
+  CHECK(strcmp(mime_type, "text/plain") == 0);
+  CHECK(mime_type_written == sizeof("text/plain"));
+
+  CHECK_CODE(RS_ERROR_OK,
+             rs_wrapped_split_secret(2, 3, (const uint8_t *)SECRET, SECRET_LEN,
+                                     NULL, false, shares, sizeof(shares),
+                                     &written));
+  CHECK_CODE(RS_ERROR_OK,
+             rs_wrapped_recover_secret(shares, false, secret, sizeof(secret),
+                                       &written, mime_type, sizeof(mime_type),
+                                       &mime_type_written));
+  CHECK(strcmp(mime_type, "") == 0);
+  return 0;
+}
+
+static int test_thss(void) {
+  char shares[8192];
+  uint8_t secret[256];
+  size_t written = 0;
+
+  CHECK_CODE(RS_ERROR_OK,
+             rs_thss_split_secret(3, 5, (const uint8_t *)SECRET, SECRET_LEN,
+                                  shares, sizeof(shares), &written));
+  keep_lines(shares, 3);
+  CHECK_CODE(RS_ERROR_OK, rs_thss_recover_secret(shares, secret,
+                                                 sizeof(secret), &written));
+  CHECK(written == SECRET_LEN);
+  CHECK(memcmp(secret, SECRET, SECRET_LEN) == 0);
+  return 0;
+}
+
+static int test_ss1(void) {
+  char shares[8192], again[8192];
+  uint8_t secret[256];
+  size_t written = 0;
+
+  CHECK_CODE(RS_ERROR_OK,
+             rs_ss1_split_secret(3, 5, (const uint8_t *)SECRET, SECRET_LEN,
+                                 true, shares, sizeof(shares), &written));
+  CHECK_CODE(RS_ERROR_OK,
+             rs_ss1_split_secret(3, 5, (const uint8_t *)SECRET, SECRET_LEN,
+                                 true, again, sizeof(again), &written));
+  CHECK(strcmp(shares, again) == 0);
+
+  keep_lines(shares, 3);
+  CHECK_CODE(RS_ERROR_OK, rs_ss1_recover_secret(shares, secret,
+                                                sizeof(secret), &written));
+  CHECK(written == SECRET_LEN);
+  CHECK(memcmp(secret, SECRET, SECRET_LEN) == 0);
+  return 0;
+}
+
+static int test_errors(void) {
+  char shares[8192];
+  uint8_t secret[256];
+  size_t written = 0;
+
+  CHECK_CODE(RS_ERROR_THRESHOLD_TOO_BIG,
+             rs_sss_split_secret(6, 5, (const uint8_t *)SECRET, SECRET_LEN,
+                                 false, shares, sizeof(shares), &written));
+  CHECK_CODE(RS_ERROR_NULL_POINTER,
+             rs_sss_recover_secret(NULL, false, secret, sizeof(secret),
+                                   &written));
+  CHECK_CODE(RS_ERROR_EMPTY_SHARES,
+             rs_sss_recover_secret("\n", false, secret, sizeof(secret),
+                                   &written));
+  CHECK(strlen(rs_error_message(RS_ERROR_MISSING_SHARES)) > 0);
+  CHECK(strcmp(rs_error_message(99), "Unknown error") == 0);
+  CHECK(strcmp(rs_error_message(98), "Unknown error") == 0);
+  CHECK(strcmp(rs_error_message(-1), "Unknown error") == 0);
+  return 0;
+}
+
+int main(void) {
+  if (test_sss() || test_wrapped() || test_thss() || test_ss1() ||
+      test_errors()) {
+    return 1;
+  }
+  puts("ok");
+  return 0;
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use etospheres_labs_rusty_secrets::sss;
 
 #[test]
@@ -7,3 +9,9 @@ fn test_generate_invalid_k() {
 
     sss::split_secret(10, 5, share1.as_slice(), true).unwrap();
 }
+
+#[test]
+#[should_panic(expected = "EmptySecret")]
+fn test_generate_empty_secret() {
+    sss::split_secret(2, 3, b"", false).unwrap();
+}
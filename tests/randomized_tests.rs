@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use etospheres_labs_rusty_secrets::wrapped_secrets;
 
 #[ignore]
@@ -28,8 +30,8 @@ fn test_reasonable_splits() {
                 println!("Testing {} out-of- {}", k, n);
 
                 let s = wrapped_secrets::recover_secret(&shares, *is_signing).unwrap();
-                assert_eq!(s.secret.to_owned(), secret);
-                assert_eq!(mime_type, s.mime_type);
+                assert_eq!(s.data.as_bytes(), secret);
+                assert_eq!(Some(mime_type.as_str()), s.raw_mime_type.as_deref());
             }
         }
     }
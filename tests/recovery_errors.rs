@@ -1,3 +1,9 @@
+#![cfg(feature = "std")]
+
+use base64::Engine;
+use prost::Message;
+
+use etospheres_labs_rusty_secrets::proto::wrapped::ShareProto;
 use etospheres_labs_rusty_secrets::sss::{recover_secret, split_secret};
 
 #[test]
@@ -131,3 +137,20 @@ fn test_recover_too_few_shares_bug() {
         assert_ne!(original, recovered);
     }
 }
+
+#[test]
+#[should_panic(expected = "UnsupportedShareVersion(1)")]
+fn test_recover_share_of_newer_version() {
+    let share = ShareProto {
+        shamir_data: vec![1, 2, 3],
+        version: 1,
+        ..Default::default()
+    };
+    let data = base64::engine::general_purpose::STANDARD_NO_PAD.encode(share.encode_to_vec());
+    let share1 = "2-1-CgnlCxRNtnkzENE".to_string();
+    let share2 = format!("2-2-{}", data);
+
+    let shares = vec![share1, share2];
+
+    recover_secret(&shares, false).unwrap();
+}
@@ -0,0 +1,194 @@
+#![cfg(all(feature = "serde", feature = "dss"))]
+
+use base64::Engine;
+
+use etospheres_labs_rusty_secrets::dss::{ss1, thss, AccessStructure};
+
+const BASE64_CONFIG: base64::engine::general_purpose::GeneralPurpose =
+    base64::engine::general_purpose::STANDARD_NO_PAD;
+
+const TEST_SECRET: &[u8] =
+    b"These programs were never about terrorism: they're about economic spying, \
+      social control, and diplomatic manipulation. They're about power.";
+
+fn test_metadata() -> ss1::MetaData {
+    let mut metadata = ss1::MetaData::new();
+    metadata
+        .tags
+        .insert("mime_type".to_string(), "text/plain".to_string());
+    metadata
+}
+
+#[test]
+fn test_ss1_share_json_roundtrip() {
+    let shares = ss1::split_secret(
+        3,
+        5,
+        TEST_SECRET,
+        ss1::Reproducibility::reproducible(),
+        &Some(test_metadata()),
+    )
+    .unwrap();
+
+    for share in shares {
+        let json = serde_json::to_string(&share).unwrap();
+        let decoded: ss1::Share = serde_json::from_str(&json).unwrap();
+        assert_eq!(share, decoded);
+
+        let parsed = ss1::Share::from_string(&share.clone().into_string()).unwrap();
+        assert_eq!(parsed, decoded);
+        assert_eq!(share.into_string(), decoded.into_string());
+    }
+}
+
+#[test]
+fn test_ss1_share_json_encodes_bytes_as_base64() {
+    let shares = ss1::split_secret(
+        2,
+        2,
+        TEST_SECRET,
+        ss1::Reproducibility::reproducible(),
+        &None,
+    )
+    .unwrap();
+
+    let json = serde_json::to_value(&shares[0]).unwrap();
+    let data = BASE64_CONFIG
+        .decode(json["data"].as_str().unwrap())
+        .unwrap();
+    let hash = BASE64_CONFIG
+        .decode(json["hash"].as_str().unwrap())
+        .unwrap();
+
+    assert_eq!(data, shares[0].data);
+    assert_eq!(hash, shares[0].hash);
+}
+
+#[test]
+fn test_ss1_share_binary_roundtrip() {
+    let shares = ss1::split_secret(
+        3,
+        5,
+        TEST_SECRET,
+        ss1::Reproducibility::reproducible(),
+        &Some(test_metadata()),
+    )
+    .unwrap();
+
+    for share in shares {
+        let bytes = bincode::serialize(&share).unwrap();
+        let decoded: ss1::Share = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(share.clone().into_string(), decoded.clone().into_string());
+        assert_eq!(share, decoded);
+    }
+}
+
+#[test]
+fn test_thss_share_json_roundtrip() {
+    let shares = thss::split_secret(3, 5, TEST_SECRET, &Some(test_metadata())).unwrap();
+
+    for share in shares {
+        let json = serde_json::to_string(&share).unwrap();
+        let decoded: thss::Share = serde_json::from_str(&json).unwrap();
+        assert_eq!(share, decoded);
+
+        let parsed = thss::Share::from_string(&share.clone().into_string()).unwrap();
+        assert_eq!(parsed, decoded);
+        assert_eq!(share.into_string(), decoded.into_string());
+    }
+}
+
+#[test]
+fn test_thss_share_binary_roundtrip() {
+    let shares = thss::split_secret(3, 5, TEST_SECRET, &None).unwrap();
+
+    for share in shares {
+        let bytes = bincode::serialize(&share).unwrap();
+        let decoded: thss::Share = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(share, decoded);
+    }
+}
+
+#[test]
+fn test_thss_recover_from_json_shares() {
+    let shares = thss::split_secret(3, 5, TEST_SECRET, &None).unwrap();
+    let json = serde_json::to_string(&shares).unwrap();
+    let decoded: Vec<thss::Share> = serde_json::from_str(&json).unwrap();
+
+    let (secret, access_structure, _) = thss::recover_secret(&decoded[1..4]).unwrap();
+    assert_eq!(secret, TEST_SECRET);
+    assert_eq!(access_structure.threshold, 3);
+    assert_eq!(access_structure.shares_count, 5);
+}
+
+#[test]
+fn test_metadata_roundtrip() {
+    let metadata = test_metadata();
+
+    let json = serde_json::to_string(&metadata).unwrap();
+    assert_eq!(json, r#"{"tags":{"mime_type":"text/plain"}}"#);
+    assert_eq!(metadata, serde_json::from_str(&json).unwrap());
+
+    let bytes = bincode::serialize(&metadata).unwrap();
+    assert_eq!(metadata, bincode::deserialize(&bytes).unwrap());
+}
+
+#[test]
+fn test_access_structure_roundtrip() {
+    let access_structure = AccessStructure {
+        threshold: 7,
+        shares_count: 10,
+    };
+
+    let json = serde_json::to_string(&access_structure).unwrap();
+    assert_eq!(json, r#"{"threshold":7,"shares_count":10}"#);
+
+    let decoded: AccessStructure = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.threshold, 7);
+    assert_eq!(decoded.shares_count, 10);
+}
+
+#[test]
+fn test_reproducibility_roundtrip() {
+    let variants = vec![
+        ss1::Reproducibility::none(),
+        ss1::Reproducibility::reproducible(),
+        ss1::Reproducibility::seeded(vec![1, 2, 3, 4]),
+        ss1::Reproducibility::with_entropy(vec![5, 6, 7, 8]),
+    ];
+
+    for variant in variants {
+        let json = serde_json::to_string(&variant).unwrap();
+        assert_eq!(
+            variant,
+            serde_json::from_str::<ss1::Reproducibility>(&json).unwrap()
+        );
+
+        let bytes = bincode::serialize(&variant).unwrap();
+        assert_eq!(
+            variant,
+            bincode::deserialize::<ss1::Reproducibility>(&bytes).unwrap()
+        );
+    }
+
+    let json = serde_json::to_string(&ss1::Reproducibility::seeded(vec![1, 2, 3, 4])).unwrap();
+    assert_eq!(json, r#"{"Seeded":"AQIDBA"}"#);
+}
+
+#[test]
+fn test_seeded_reproducibility_from_json_is_deterministic() {
+    let reproducibility: ss1::Reproducibility =
+        serde_json::from_str(r#"{"Seeded":"AQIDBA"}"#).unwrap();
+
+    let shares_1 = ss1::split_secret(3, 5, TEST_SECRET, reproducibility.clone(), &None).unwrap();
+    let shares_2 = ss1::split_secret(
+        3,
+        5,
+        TEST_SECRET,
+        ss1::Reproducibility::seeded(vec![1, 2, 3, 4]),
+        &None,
+    )
+    .unwrap();
+
+    assert_eq!(shares_1, shares_2);
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "std")]
+
+use etospheres_labs_rusty_secrets::slip39::{recover_secret, Group, Share, Slip39};
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaChaRng;
+use ring::digest::{digest, SHA256};
+use ring::hmac;
+
+/// Test vectors of the SLIP-39 specification, in the format of the `vectors.json`
+/// file of the reference implementation: a description, the mnemonics, the master
+/// secret they decrypt to with the passphrase `TREZOR`, in hexadecimal, and the
+/// BIP-32 master extended private key derived from it, or empty strings if
+/// recovering it must fail.
+///
+/// The committed file only holds some of the reference cases. It is read as it is,
+/// so that the complete reference file can replace it unchanged.
+const VECTORS: &str = include_str!("fixtures/slip39/vectors.json");
+
+const PASSPHRASE: &str = "TREZOR";
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn base58check(payload: &[u8]) -> String {
+    let checksum = digest(&SHA256, digest(&SHA256, payload).as_ref());
+    let bytes = [payload, &checksum.as_ref()[..4]].concat();
+
+    // Base 58 digits, least significant first.
+    let mut digits = Vec::<u8>::new();
+    for &byte in &bytes {
+        let mut carry = byte as u32;
+        for digit in &mut digits {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    std::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|&digit| BASE58_ALPHABET[digit as usize] as char),
+        )
+        .collect()
+}
+
+/// The BIP-32 master extended private key derived from the given seed.
+fn xprv(seed: &[u8]) -> String {
+    let key = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA512, b"Bitcoin seed"), seed);
+    let (private_key, chain_code) = key.as_ref().split_at(32);
+    let mut payload = vec![0x04, 0x88, 0xAD, 0xE4];
+    // Depth, parent fingerprint and child number.
+    payload.extend_from_slice(&[0; 9]);
+    payload.extend_from_slice(chain_code);
+    payload.push(0);
+    payload.extend_from_slice(private_key);
+    base58check(&payload)
+}
+
+fn vectors() -> Vec<(String, Vec<String>, String, String)> {
+    serde_json::from_str(VECTORS).unwrap()
+}
+
+#[test]
+fn test_vectors() {
+    for (description, mnemonics, master_secret, master_xprv) in vectors() {
+        let result = recover_secret(&mnemonics, PASSPHRASE);
+        if master_secret.is_empty() {
+            assert!(result.is_err(), "{}: recovered a secret", description);
+        } else {
+            let secret = result.unwrap_or_else(|err| panic!("{}: {}", description, err));
+            assert_eq!(
+                secret.as_bytes(),
+                &from_hex(&master_secret)[..],
+                "{}",
+                description
+            );
+            assert_eq!(xprv(secret.as_bytes()), master_xprv, "{}", description);
+        }
+    }
+}
+
+#[test]
+fn test_vectors_roundtrip() {
+    for (description, mnemonics, ..) in vectors() {
+        for mnemonic in mnemonics {
+            if let Ok(share) = Share::from_mnemonic(&mnemonic) {
+                assert_eq!(share.to_mnemonic().unwrap(), mnemonic, "{}", description);
+            }
+        }
+    }
+}
+
+/// Inconsistent share sets, on top of the reference vectors, built from shares dealt
+/// by this crate: each share is altered, then formatted again with a valid checksum.
+#[test]
+fn test_altered_shares() {
+    let mut rng = ChaChaRng::from_seed([39; 32]);
+    let groups = [
+        Group {
+            member_threshold: 1,
+            member_count: 1,
+        },
+        Group {
+            member_threshold: 2,
+            member_count: 3,
+        },
+    ];
+    let master_secret = (0..64).collect::<Vec<u8>>();
+
+    type Alteration = fn(&mut Share);
+    let alterations: [(&str, Alteration); 9] = [
+        ("Mismatched iteration exponents", |share| {
+            share.iteration_exponent += 1
+        }),
+        ("Mismatched group thresholds", |share| {
+            share.group_threshold = 1
+        }),
+        ("Mismatched group counts", |share| share.group_count += 1),
+        ("Mismatched member thresholds", |share| {
+            share.member_threshold = 3
+        }),
+        ("Mismatched identifiers", |share| share.identifier ^= 1),
+        ("Mismatched extendable flags", |share| {
+            share.extendable = !share.extendable
+        }),
+        ("Invalid digest", |share| share.value[0] ^= 1),
+        ("Mismatched lengths", |share| {
+            share.value.truncate(share.value.len() - 2)
+        }),
+        ("Duplicate member indices", |share| share.member_index = 0),
+    ];
+
+    for extendable in [false, true] {
+        for secret_len in [16, 32, 64] {
+            let master_secret = &master_secret[..secret_len];
+            let shares = Slip39::new(2, &groups)
+                .extendable(extendable)
+                .iteration_exponent(0)
+                .split_secret_shares_rng(&mut rng, master_secret, PASSPHRASE)
+                .unwrap();
+            let mnemonics = |shares: &[&Share]| {
+                shares
+                    .iter()
+                    .map(|share| share.to_mnemonic().unwrap())
+                    .collect::<Vec<_>>()
+            };
+            let chosen = [&shares[0][0], &shares[1][2], &shares[1][1]];
+
+            let secret = recover_secret(&mnemonics(&chosen), PASSPHRASE).unwrap();
+            assert_eq!(secret.as_bytes(), master_secret);
+
+            for (description, alter) in alterations {
+                let mut altered = shares[1][1].clone();
+                alter(&mut altered);
+                let chosen = [&shares[0][0], &shares[1][2], &altered];
+                assert!(
+                    recover_secret(&mnemonics(&chosen), PASSPHRASE).is_err(),
+                    "{} ({} bits, extendable: {})",
+                    description,
+                    secret_len * 8,
+                    extendable
+                );
+            }
+
+            // Shares hold at least 128 bits, in a whole number of bytes.
+            let mut short = shares[0][0].clone();
+            short.value.truncate(15);
+            assert!(Share::from_mnemonic(&short.to_mnemonic().unwrap()).is_err());
+        }
+    }
+}
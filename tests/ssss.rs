@@ -0,0 +1,118 @@
+#![cfg(feature = "std")]
+
+use etospheres_labs_rusty_secrets::compat::ssss::Ssss;
+
+/// Shares dealt by `ssss-split`, along with the secret they were split from, the
+/// threshold, and whether the diffusion layer was used. Secrets shorter than the
+/// security level are recovered padded with zeros on the left.
+const VECTORS: &[(&[u8], u8, bool, &[&str])] = &[
+    // The example of the documentation of `ssss`.
+    (
+        b"my secret root password",
+        3,
+        true,
+        &[
+            "1-1c41ef496eccfbeba439714085df8437236298da8dd824",
+            "2-fbc74a03a50e14ab406c225afb5f45c40ae11976d2b665",
+            "3-fa1c3a9c6df8af0779c36de6c33f6e36e989d0e0b91309",
+            "4-468de7d6eb36674c9cf008c8e8fc8c566537ad6301eb9e",
+            "5-4756974923c0dce0a55f4774d09ca7a4865f64f56a4ee0",
+        ],
+    ),
+    // With a token, and a secret with a leading zero byte, split with `-x -D`.
+    (
+        b"\x00\x11\x22\x33\x44\x55\x66\x77\x88\x99\xaa\xbb\xcc\xdd\xee\xff",
+        2,
+        false,
+        &[
+            "backup-1-0129956ce5264eb0ab76305210b7eb8a",
+            "backup-2-02604c8c06b337f9cf469f687409e413",
+            "backup-3-0358fbd3a7c01f3eeca90581a863e166",
+        ],
+    ),
+    // With the highest security level.
+    (
+        b"correct horse battery staple",
+        4,
+        true,
+        &[
+            "01-3805cf61ae29648e1dcc6b652c6ab28553b6acb9c42079aeecb13f7089c0a76c9dec8ef51fc119e81f48b2e56b5ff00a3f8e0a815fddf6510704e335766c3bfa34d60463240b8dba20ae28d29c6194be392a803a2d2deae2ff423fc0d494e0b594d7e7a4abc4589022470a8bdb4b71ba08508afbe0cde1788b71bea1b2494fc4",
+            "02-f380dc49f2822c41aa260ca4e0b38aa98f17331b63ef9f633a71dc58cfc929a7d86b193e7f98fea3ad6db152c86a4d09d29cb608e4c6759b9f3c9496ab9a9cef3701654b900d26d9f05817fcc139c5e78ad2874a4fb388ad943d7e96c1737261cfcac8867863257a0114dd26b5f23d6a5b5fafd75bf494955c95b1d240885214",
+            "03-43aec648b2cf134c1a35a568a6dab2dec9d5002a1e38786bf1bc0a5f519a9296fce7a43b76906508df0dad311bd3ca7a75fb5d2bcacf73762e7954340cce3672f3cd238bf7eba59eee343d536267d0dc94cd1706f5ddc7a31f572de53fd19b79629e0751b04aea101b6a7a47d59cfde7acf6dfcca8e93ff9c68423f01d171399",
+            "04-3591cdbe9f1bdfed4774474e1c676d97a6bca7d3b9fcb7c3c604974bc7b4317191a61e952f38104c168df6bdbaa5fd1b5993de44c1a10c86412205ef23b5043e07b70384948dcc5985d1001337f51d1b33375bf5cf610210960fb81acd13ec7cc457e2298e2d08b29d4bb28c87030b36fa8045a8a1478053131c4859f69cdb17",
+            "05-24b01b1f78e9f663efc758f5bd720b1ed8517c6f9e1f54b8973bf1793c53373208f851f28d75604979e07218e53dd9aeea134e67c9b89b407272bfafbfd59822d6ff31aaf36f470a074b2212c55d7e887af4135a9815e8488532e10b2e928bbdb668496c77262f20b27d142ce0e71a314748a0b8807a3acb90ce578d50429fc8",
+            "06-ad2a91766b3c93aa696c53dbbf538ece74af32d78db8ba92741e723bb133c31c36da22fcbba7505ef1de40225e4b23212ecf04ee3e823addee603dc8144b5235fe20b95e4761fc40ee510c603be9c28166b5a5ed20bec0aadee3b499013204da53a3af6ac6c4833afbbec003814bf5f58084af839f036cce74ad4313540988ff",
+            "07-31d268816df166c9c452c6713f542cb8b4967ce9028aee9416b1f2d886854c94267490e0979d03b99adcf7c658683c632cd85c7be12b9d78305a22c5ed211f72285ff7522082c2a2e8ec9bee6266069ce8841e0f5579a683931935458b653a5ed3e4a5a934c2ea56054b0b555141cfc748377b4b235ed0c74452688dd7c97b11",
+            "08-409b39563802e63cae5002dc4e094258108a5f5507598977341e70e7905d72fd779981d5e5ee709af2d44af49a0c60e02d1bafa6cdf3fea43fa011191c55b84fa842fb8b2f4b0b6afa9f71e5025e95b15f689d24773c6fb1ed2eb578a0b7459fb8dc09635d5d7ff44cf64327f7653be936981c7bd8a5bc1a00531f233f3bd3aa",
+            "09-f9533e83078c5fd0794cf8deb483c52808235bd3ba8fc8cca78980e4548db5cd2dae0821cf86666bf0ed06cc2088537c68766033ac088c846bda9f7e32db198d3da812d148bc1dce130ccc7c4dfefda5d9f69daa5b8b399459df174e434dcea59a5df57a5af25d80526b8eb43ece96789dcc0997fe5856374e618e90aaae30d8",
+            "10-d05e009bf1dd1e63454da5f6d6df56f003e68e3be2bda7ba5191143ccb7f6b053e722ea92b5e6df8fdeb3a5f1fefa0eafd7cad0d025441a38049fe2ff20a5a4cfdf6e621fc877bd776af38e481f1ff69b40ca423f03687bf677aeaeb858adbf7d47360b19ca3b3bd80433fa0cd7b49781f905e0e77e1df27a9fe7bde478eab9d",
+        ],
+    ),
+    // With a security level too low for the diffusion layer.
+    (
+        b"rusty!!",
+        3,
+        true,
+        &[
+            "1-00b55985dda4aa",
+            "2-2b921348dc0c58",
+            "3-595239b97889d5",
+            "4-3027647283e33d",
+        ],
+    ),
+    // With a secret shorter than the security level, split with `-s 128`.
+    (
+        b"short",
+        2,
+        true,
+        &[
+            "1-6fd128bc97454133266f38afcae384f7",
+            "2-4d485ea044823f0d89e107d68935af1a",
+        ],
+    ),
+];
+
+#[test]
+fn test_vectors() {
+    for &(secret, k, diffusion, shares) in VECTORS {
+        let ssss = Ssss::new().diffusion(diffusion);
+        let shares = shares.iter().map(|&s| s.to_owned()).collect::<Vec<_>>();
+        let width = shares[0].rsplit('-').next().unwrap().len() / 2;
+        let mut padded = vec![0; width - secret.len()];
+        padded.extend_from_slice(secret);
+        for start in 0..=shares.len() - usize::from(k) {
+            let recovered = ssss.recover_secret(k, &shares[start..]).unwrap();
+            assert_eq!(recovered.as_bytes(), padded);
+        }
+        let mut reversed = shares.clone();
+        reversed.reverse();
+        assert_eq!(
+            ssss.recover_secret(k, &reversed).unwrap().as_bytes(),
+            padded
+        );
+        let trimmed = ssss.trim_leading_zeros(true).recover_secret(k, &shares);
+        assert_eq!(
+            trimmed.unwrap().as_bytes(),
+            secret.strip_prefix(b"\0").unwrap_or(secret)
+        );
+    }
+}
+
+#[test]
+fn split_like_ssss() {
+    let shares = Ssss::new()
+        .token("backup")
+        .security_level(1024)
+        .split_secret(4, 10, b"correct horse battery staple")
+        .unwrap();
+    for (i, share) in shares.iter().enumerate() {
+        let (prefix, value) = share.split_at(10);
+        assert_eq!(prefix, format!("backup-{:02}-", i + 1));
+        assert_eq!(value.len(), 256);
+    }
+    let secret = Ssss::new()
+        .trim_leading_zeros(true)
+        .recover_secret(4, &shares[3..])
+        .unwrap();
+    assert_eq!(secret.as_bytes(), b"correct horse battery staple");
+}
@@ -1,7 +1,11 @@
+#![cfg(feature = "std")]
+
 use base64::Engine;
 use prost::Message;
 
+use etospheres_labs_rusty_secrets::compat::sellibitze;
 use etospheres_labs_rusty_secrets::proto::wrapped::ShareProto;
+use etospheres_labs_rusty_secrets::scheme::{detect_scheme, recover_any, SchemeKind};
 use etospheres_labs_rusty_secrets::sss::recover_secret;
 
 const BASE64_CONFIG: base64::engine::general_purpose::GeneralPurpose =
@@ -11,11 +15,12 @@ pub fn wrap_from_sellibitze(share: &str) -> String {
     let parts: Vec<_> = share.trim().split('-').collect();
     let share_data = BASE64_CONFIG.decode(parts[2]).unwrap();
 
-    let mut share_protobuf = ShareProto::default();
-    share_protobuf.shamir_data = share_data;
+    let share_protobuf = ShareProto {
+        shamir_data: share_data,
+        ..Default::default()
+    };
 
-    let mut buf = Vec::new();
-    buf.reserve(share_protobuf.encoded_len());
+    let mut buf = Vec::with_capacity(share_protobuf.encoded_len());
     // Unwrap is safe, since we have reserved sufficient capacity in the vector.
     share_protobuf.encode(&mut buf).unwrap();
 
@@ -80,3 +85,35 @@ fn test_recover_sellibitze_more_than_threshold_shars() {
     secret.push(10);
     assert_eq!(recover_secret(&shares, false).unwrap(), secret);
 }
+
+#[test]
+fn test_upgrade_sellibitze_shares() {
+    let legacy = ["2-1-1YAYwmOHqZ69jA", "2-4-F7rAjX3UOa53KA"]
+        .iter()
+        .map(|&x| x.to_owned())
+        .collect::<Vec<_>>();
+
+    let shares = sellibitze::upgrade_shares(&legacy, false).unwrap();
+    assert!(!shares
+        .iter()
+        .any(|share| sellibitze::is_legacy_share(share)));
+
+    let mut secret = "My secret".to_string().into_bytes();
+    secret.push(10);
+    assert_eq!(recover_secret(&shares, false).unwrap(), secret);
+}
+
+#[test]
+fn test_recover_raw_sellibitze_shares() {
+    let shares = vec![
+        wrap_from_sellibitze("2-1-1YAYwmOHqZ69jA"),
+        "2-4-F7rAjX3UOa53KA".to_owned(),
+    ];
+
+    let mut secret = "My secret".to_string().into_bytes();
+    secret.push(10);
+    assert_eq!(recover_secret(&shares, false).unwrap(), secret);
+
+    assert_eq!(detect_scheme(&shares[1]).unwrap(), SchemeKind::Sss);
+    assert_eq!(recover_any(&shares, false).unwrap().as_bytes(), &secret[..]);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use etospheres_labs_rusty_secrets::compat::vault;
+
+/// Unseal keys computed by Vault's `shamir.Split`, along with the threshold and the
+/// secret they were split from, in hexadecimal.
+const VECTORS: &[(&str, u8, &[&str])] = &[
+    (
+        "6c9ba1d4f0e2378a05bb3c91d8e4a6f21f0de7c4b9a85362718f4e0d3c2b1a09",
+        3,
+        &[
+            "j62UQn+rBKj8IenJp/ghkG8lY9VnBy60Wy9AGd9TvtJg",
+            "HDcYKFbYPSHl2BkFPDs8HfDfhqlUII4QwVcW2HZuKHE7",
+            "Wx7tCOG6ESsTLJev8ulrNudVqHit0lmKIw1Fy8gNKPaa",
+            "QMH3YMjwRQra8/rK7eqw5sX21anORuiTAYvfJYRYODeV",
+            "bonnP5XypC8NiboLbDDXzCcqlAGVMwX++NSCKufGnd4I",
+        ],
+    ),
+    (
+        "68656c6c6f2c207661756c74",
+        2,
+        &[
+            "7264eb91ccd358e47c16c98821",
+            "102803de4c0467c7e107fa8b2e",
+            "3fd62ad542e8a2bc0213c67e22",
+        ],
+    ),
+];
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Every subset of `k` elements of `items`.
+fn subsets<T: Clone>(items: &[T], k: usize) -> Vec<Vec<T>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    (0..items.len())
+        .flat_map(|i| {
+            subsets(&items[i + 1..], k - 1)
+                .into_iter()
+                .map(move |mut subset| {
+                    subset.insert(0, items[i].clone());
+                    subset
+                })
+        })
+        .collect()
+}
+
+#[test]
+fn test_vectors() {
+    for &(secret, k, unseal_keys) in VECTORS {
+        let unseal_keys = unseal_keys
+            .iter()
+            .map(|&key| key.to_owned())
+            .collect::<Vec<_>>();
+        for chosen in subsets(&unseal_keys, k.into()) {
+            let recovered = vault::recover_secret(&chosen).unwrap();
+            assert_eq!(recovered.as_bytes(), &from_hex(secret)[..]);
+        }
+        // Too few shares go undetected, but yield a wrong secret.
+        if k > 2 {
+            let recovered = vault::recover_secret(&unseal_keys[..usize::from(k) - 1]).unwrap();
+            assert_ne!(recovered.as_bytes(), &from_hex(secret)[..]);
+        }
+    }
+}
+
+#[test]
+fn split_then_recover_with_vault_algorithm() {
+    let secret = from_hex(VECTORS[0].0);
+    let unseal_keys = vault::split_secret(3, 5, &secret).unwrap();
+    for chosen in subsets(&unseal_keys, 3) {
+        assert_eq!(
+            vault::recover_secret(&chosen).unwrap().as_bytes(),
+            &secret[..]
+        );
+    }
+}
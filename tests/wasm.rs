@@ -0,0 +1,95 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+//! Run with `wasm-pack test --node --features wasm`.
+
+use js_sys::{Object, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+use etospheres_labs_rusty_secrets::wasm;
+
+const TEST_SECRET: &[u8] =
+    b"These programs were never about terrorism: they're about economic spying, \
+      social control, and diplomatic manipulation. They're about power.";
+
+fn get(target: &JsValue, key: &str) -> JsValue {
+    Reflect::get(target, &JsValue::from_str(key)).unwrap()
+}
+
+fn error_kind(err: JsValue) -> String {
+    let error = err.dyn_into::<js_sys::Error>().unwrap();
+    assert_eq!(get(&error, "kind").as_string(), error.name().as_string());
+    error.name().into()
+}
+
+#[wasm_bindgen_test]
+fn split_then_recover() {
+    for &signed in &[false, true] {
+        let shares = wasm::split_secret(3, 5, TEST_SECRET, signed).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        let secret = wasm::recover_secret(shares[1..4].to_vec(), signed).unwrap();
+        assert_eq!(secret.to_vec(), TEST_SECRET);
+    }
+}
+
+#[wasm_bindgen_test]
+fn inspect_share() {
+    let shares = wasm::split_secret(3, 5, TEST_SECRET, true).unwrap();
+    let info = wasm::inspect_share(&shares[1]).unwrap();
+
+    assert_eq!(get(&info, "id").as_f64(), Some(2.0));
+    assert_eq!(get(&info, "threshold").as_f64(), Some(3.0));
+    assert_eq!(get(&info, "signed").as_bool(), Some(true));
+    assert_eq!(
+        get(&info, "dataLength").as_f64(),
+        Some(TEST_SECRET.len() as f64)
+    );
+}
+
+#[wasm_bindgen_test]
+fn errors_are_structured() {
+    let shares = wasm::split_secret(3, 5, TEST_SECRET, false).unwrap();
+
+    let err = wasm::recover_secret(shares[..2].to_vec(), false).unwrap_err();
+    assert_eq!(error_kind(err), "MissingShares");
+
+    let err = wasm::recover_secret(shares[..3].to_vec(), true).unwrap_err();
+    assert_eq!(get(&err, "shareId").as_f64(), Some(1.0));
+    assert_eq!(error_kind(err), "MissingSignature");
+
+    let err = wasm::split_secret(6, 5, TEST_SECRET, false).unwrap_err();
+    assert_eq!(error_kind(err), "ThresholdTooBig");
+}
+
+#[wasm_bindgen_test]
+fn thss_split_then_recover_with_metadata() {
+    let metadata = Object::new();
+    Reflect::set(&metadata, &"mime_type".into(), &"text/plain".into()).unwrap();
+
+    let shares = wasm::thss_split_secret(3, 5, TEST_SECRET, Some(metadata)).unwrap();
+    let recovered = wasm::thss_recover_secret(shares[2..].to_vec()).unwrap();
+
+    let secret = get(&recovered, "secret").dyn_into::<Uint8Array>().unwrap();
+    assert_eq!(secret.to_vec(), TEST_SECRET);
+    assert_eq!(get(&recovered, "threshold").as_f64(), Some(3.0));
+    assert_eq!(get(&recovered, "sharesCount").as_f64(), Some(5.0));
+    assert_eq!(
+        get(&get(&recovered, "metadata"), "mime_type").as_string(),
+        Some("text/plain".to_string())
+    );
+}
+
+#[wasm_bindgen_test]
+fn ss1_split_then_recover() {
+    let shares = wasm::ss1_split_secret(3, 5, TEST_SECRET, true, None).unwrap();
+    assert_eq!(
+        shares,
+        wasm::ss1_split_secret(3, 5, TEST_SECRET, true, None).unwrap()
+    );
+
+    let recovered = wasm::ss1_recover_secret(shares[..3].to_vec()).unwrap();
+    let secret = get(&recovered, "secret").dyn_into::<Uint8Array>().unwrap();
+    assert_eq!(secret.to_vec(), TEST_SECRET);
+    assert!(get(&recovered, "metadata").is_undefined());
+}